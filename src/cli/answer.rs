@@ -0,0 +1,21 @@
+use crate::orchestrator::Orchestrator;
+use crate::Result;
+
+/// Answer a question an agent asked with `wta ask`
+pub async fn run(id: String, reply: String, question: Option<u32>) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+
+    let answered = orchestrator.answer(&id, question, &reply)?;
+
+    // Let the agent count as working again, unless the daemon does it
+    if orchestrator.daemon_pid().is_none() {
+        orchestrator.check_status(&id)?;
+    }
+
+    println!(
+        "Answered question {} of agent {id}: {}",
+        answered.number, answered.question
+    );
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::cli::queue_reason;
+use crate::editor::edit_text;
+use crate::error::Error;
+use crate::orchestrator::{AgentStatus, Orchestrator};
+use crate::Result;
+
+/// Send an agent's plan, optionally edited, back to it as a follow-up task
+pub async fn run(id: String, edit: Option<String>) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
+
+    let plan = match edit {
+        Some(cmd) => {
+            let proposed = orchestrator.proposed_plan(&id)?;
+            let editor_cmd = if cmd.is_empty() { None } else { Some(cmd) };
+            let edited = edit_text(editor_cmd, "md", &format!("{proposed}\n"))?;
+            if edited.trim().is_empty() {
+                return Err(Error::EditorAborted("Empty plan".to_string()));
+            }
+            Some(edited.trim().to_string())
+        }
+        None => None,
+    };
+    orchestrator.approve(&id, plan)?;
+
+    let agent = orchestrator.get_agent(&id)?;
+    match agent.status {
+        AgentStatus::Queued => {
+            println!("Approved the plan of agent {id}; queued to implement it");
+            println!("{}", queue_reason(&orchestrator, &id)?);
+        }
+        AgentStatus::Running => {
+            println!(
+                "Approved the plan of agent {id}; implementing it on branch {}",
+                agent.branch
+            );
+            println!();
+            println!("Use 'wta attach {id}' to watch the agent");
+        }
+        status => println!("Agent {id} could not be started: {status}"),
+    }
+
+    Ok(())
+}
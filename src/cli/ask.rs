@@ -0,0 +1,39 @@
+use crate::duration::format_duration;
+use crate::error::Error;
+use crate::orchestrator::Orchestrator;
+use crate::Result;
+use std::time::{Duration, Instant};
+
+/// How often a waiting `wta ask` looks for the answer
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Ask the user a question on behalf of the current agent and print the
+/// answer once it arrives, giving up after `timeout` or once the agent or
+/// its question is removed
+pub async fn run(question: String, timeout: Duration) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    let agent = orchestrator.agent_for_path(&std::env::current_dir()?)?;
+    let id = agent.id.0.clone();
+
+    let number = orchestrator.ask(&id, &question)?;
+    eprintln!("Question {number} recorded; waiting for 'wta answer {id} \"...\"'");
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        orchestrator.reload_state()?;
+        orchestrator.get_agent(&id)?;
+        let question = orchestrator
+            .questions(&id)?
+            .into_iter()
+            .find(|q| q.number == number)
+            .ok_or_else(|| Error::QuestionRemoved(id.clone(), number))?;
+        if let Some(answer) = question.answer {
+            println!("{answer}");
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(Error::AskTimedOut(number, format_duration(timeout)));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,119 @@
+use crate::cli::{colorize_status, report_text};
+use crate::duration::format_duration;
+use crate::git::repository::diff_stats;
+use crate::orchestrator::{Agent, AgentStatus, Orchestrator};
+use crate::Result;
+use colored::Colorize;
+use tabled::settings::style::Style;
+use tabled::settings::Padding;
+use tabled::{Table, Tabled};
+
+const SUMMARY_MAX_LEN: usize = 60;
+
+#[derive(Tabled)]
+struct CompareRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "PROVIDER")]
+    provider: String,
+    #[tabled(rename = "STATUS")]
+    status: String,
+    #[tabled(rename = "TIME")]
+    time: String,
+    #[tabled(rename = "FILES")]
+    files: String,
+    #[tabled(rename = "+/-")]
+    lines: String,
+    #[tabled(rename = "CHECK")]
+    check: String,
+    #[tabled(rename = "SUMMARY")]
+    summary: String,
+}
+
+/// Compare the agents of an attempt group side by side, optionally running
+/// `check_command` in each first, or keep one and remove the rest
+pub async fn run(group: String, check: bool, keep: Option<String>, force: bool) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    orchestrator.sync_status()?;
+
+    if let Some(keep) = keep {
+        let removed = orchestrator.keep_attempt(&group, &keep, force).await?;
+        let ids: Vec<&str> = removed.iter().map(|id| id.0.as_str()).collect();
+        println!("Kept agent {keep} of attempt group {group}");
+        if !ids.is_empty() {
+            println!(
+                "Removed agent(s) {} with their worktrees and branches",
+                ids.join(", ")
+            );
+        }
+        return Ok(());
+    }
+
+    if check {
+        let finished: Vec<String> = orchestrator
+            .group(&group)?
+            .into_iter()
+            .filter(|a| is_checkable(a))
+            .map(|a| a.id.0.clone())
+            .collect();
+        for id in finished {
+            println!("Running check for agent {id}...");
+            orchestrator.run_check(&id)?;
+        }
+        println!();
+    }
+
+    let agents = orchestrator.group(&group)?;
+    let task = agents[0].task().to_string();
+    let rows: Vec<CompareRow> = agents.into_iter().map(|a| row(&orchestrator, a)).collect();
+
+    println!("Attempt group {group}: {task}");
+    println!(
+        "{}",
+        Table::new(rows)
+            .with(Style::rounded())
+            .with(Padding::new(1, 1, 0, 0))
+    );
+    println!("Use 'wta diff <id>' to inspect an attempt, 'wta compare {group} --keep <id>' to keep one and remove the rest");
+
+    Ok(())
+}
+
+/// Finished agents whose worktree is still there to run the check in
+fn is_checkable(agent: &Agent) -> bool {
+    !agent.status.is_active()
+        && !matches!(agent.status, AgentStatus::Queued | AgentStatus::Merged)
+        && agent.worktree_path.exists()
+}
+
+fn row(orchestrator: &Orchestrator, agent: &Agent) -> CompareRow {
+    let range = format!("{}...{}", agent.base_branch, agent.branch);
+    let stats = diff_stats(orchestrator.repo_root(), &range).unwrap_or_default();
+
+    let end = agent.completed_at.unwrap_or_else(chrono::Utc::now);
+    let time = match agent.status {
+        AgentStatus::Queued => "-".to_string(),
+        _ => format_duration((end - agent.launched_at).to_std().unwrap_or_default()),
+    };
+
+    let check = match &agent.check {
+        Some(c) if c.passed => "passed".green().to_string(),
+        Some(_) => "failed".red().to_string(),
+        None => "-".to_string(),
+    };
+
+    CompareRow {
+        id: agent.id.0.bright_white().to_string(),
+        provider: agent.provider.to_string(),
+        status: colorize_status(&agent.status),
+        time,
+        files: stats.files_changed.to_string(),
+        lines: format!(
+            "{} {}",
+            format!("+{}", stats.additions).green(),
+            format!("-{}", stats.deletions).red()
+        ),
+        check,
+        summary: report_text(agent, SUMMARY_MAX_LEN),
+    }
+}
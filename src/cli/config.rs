@@ -0,0 +1,157 @@
+use crate::config::{Config, Setting};
+use crate::duration::format_duration;
+use crate::orchestrator::Orchestrator;
+use crate::Result;
+use clap::Subcommand;
+use colored::Colorize;
+use tabled::settings::style::Style;
+use tabled::settings::Padding;
+use tabled::{Table, Tabled};
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Show resolved configuration values and where each came from
+    Show,
+}
+
+#[derive(Tabled)]
+struct ConfigRow {
+    #[tabled(rename = "KEY")]
+    key: String,
+    #[tabled(rename = "VALUE")]
+    value: String,
+    #[tabled(rename = "SOURCE")]
+    source: String,
+}
+
+fn row<T>(key: &str, setting: &Setting<T>, value: String) -> ConfigRow {
+    ConfigRow {
+        key: key.bright_white().to_string(),
+        value: value.cyan().to_string(),
+        source: setting.source.to_string(),
+    }
+}
+
+fn config_rows(config: &Config) -> Vec<ConfigRow> {
+    let mut rows = vec![
+        row(
+            "provider",
+            &config.provider,
+            config.provider.value.to_string(),
+        ),
+        row(
+            "base",
+            &config.base,
+            config
+                .base
+                .value
+                .clone()
+                .unwrap_or_else(|| "(current branch)".to_string()),
+        ),
+        row(
+            "merge_strategy",
+            &config.merge_strategy,
+            config.merge_strategy.value.to_string(),
+        ),
+        row(
+            "update_checkout",
+            &config.update_checkout,
+            config.update_checkout.value.to_string(),
+        ),
+        row(
+            "worktree_dir",
+            &config.worktree_dir,
+            config
+                .worktree_dir
+                .value
+                .as_ref()
+                .map(|d| d.display().to_string())
+                .unwrap_or_else(|| "(next to repository)".to_string()),
+        ),
+        row(
+            "dashboard_port",
+            &config.dashboard_port,
+            config.dashboard_port.value.to_string(),
+        ),
+        row(
+            "dangerously_allow_all",
+            &config.dangerously_allow_all,
+            config.dangerously_allow_all.value.to_string(),
+        ),
+        row(
+            "enable_edits",
+            &config.enable_edits,
+            config.enable_edits.value.to_string(),
+        ),
+        row(
+            "max_running",
+            &config.max_running,
+            config
+                .max_running
+                .value
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "(unlimited)".to_string()),
+        ),
+        row(
+            "notify_command",
+            &config.notify_command,
+            config
+                .notify_command
+                .value
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        row(
+            "check_command",
+            &config.check_command,
+            config
+                .check_command
+                .value
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        row(
+            "check_timeout",
+            &config.check_timeout,
+            format_duration(config.check_timeout.value),
+        ),
+        row(
+            "timeout",
+            &config.timeout,
+            config
+                .timeout
+                .value
+                .map(format_duration)
+                .unwrap_or_else(|| "(none)".to_string()),
+        ),
+    ];
+
+    for (provider, args) in &config.provider_args {
+        let value = if args.value.is_empty() {
+            "-".to_string()
+        } else {
+            args.value.join(" ")
+        };
+        rows.push(row(&format!("provider_args.{provider}"), args, value));
+    }
+
+    rows
+}
+
+pub async fn run(command: ConfigCommands) -> Result<()> {
+    match command {
+        ConfigCommands::Show => run_show().await,
+    }
+}
+
+async fn run_show() -> Result<()> {
+    let orchestrator = Orchestrator::new()?;
+
+    let table = Table::new(config_rows(orchestrator.config()))
+        .with(Style::rounded())
+        .with(Padding::new(1, 1, 0, 0))
+        .to_string();
+    println!("{table}");
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::cli::queue_reason;
+use crate::orchestrator::{AgentStatus, Orchestrator};
+use crate::Result;
+
+/// Give a finished agent a follow-up task on its branch
+pub async fn run(id: String, task: String) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
+
+    orchestrator.continue_agent(&id, task)?;
+
+    let agent = orchestrator.get_agent(&id)?;
+    let branch = agent.branch.clone();
+    let follow_up = agent.tasks.len() - 1;
+
+    match agent.status {
+        AgentStatus::Queued => {
+            println!("Queued follow-up {follow_up} for agent {id} on branch {branch}");
+            println!("{}", queue_reason(&orchestrator, &id)?);
+        }
+        AgentStatus::Running => {
+            println!("Started follow-up {follow_up} for agent {id} on branch {branch}");
+            println!("Provider: {}", agent.provider);
+            println!();
+            println!("Use 'wta attach {id}' to watch the agent");
+        }
+        status => println!("Follow-up for agent {id} could not be started: {status}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::daemon::{self, DEFAULT_INTERVAL_SECS, LOG_FILE};
+use crate::orchestrator::Orchestrator;
+use crate::Result;
+use clap::Subcommand;
+use std::time::Duration;
+
+#[derive(Subcommand)]
+pub enum DaemonCommands {
+    /// Supervise agents in the foreground until interrupted
+    Run {
+        /// Seconds between fallback checks (file changes are picked up immediately)
+        #[arg(long, default_value_t = DEFAULT_INTERVAL_SECS)]
+        interval: u64,
+    },
+
+    /// Start the daemon in the background
+    Start {
+        /// Seconds between fallback checks (file changes are picked up immediately)
+        #[arg(long, default_value_t = DEFAULT_INTERVAL_SECS)]
+        interval: u64,
+    },
+
+    /// Stop a background daemon
+    Stop,
+
+    /// Show whether a daemon is supervising this repository
+    Status,
+}
+
+pub async fn run(command: DaemonCommands) -> Result<()> {
+    match command {
+        DaemonCommands::Run { interval } => daemon::run(Duration::from_secs(interval.max(1))).await,
+        DaemonCommands::Start { interval } => run_start(interval),
+        DaemonCommands::Stop => run_stop(),
+        DaemonCommands::Status => run_status(),
+    }
+}
+
+fn run_start(interval: u64) -> Result<()> {
+    let orchestrator = Orchestrator::new()?;
+    let pid = daemon::spawn(&orchestrator, Duration::from_secs(interval.max(1)))?;
+
+    println!("Daemon started (pid {pid})");
+    println!("Log: {}", orchestrator.state_dir().join(LOG_FILE).display());
+
+    Ok(())
+}
+
+fn run_stop() -> Result<()> {
+    let orchestrator = Orchestrator::new()?;
+    let pid = daemon::stop(&orchestrator.state_dir())?;
+
+    println!("Daemon stopped (pid {pid})");
+
+    Ok(())
+}
+
+fn run_status() -> Result<()> {
+    let orchestrator = Orchestrator::new()?;
+
+    match orchestrator.daemon_pid() {
+        Some(pid) => println!("Daemon running (pid {pid})"),
+        None => println!("Daemon not running"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::cli::queue_reason;
+use crate::orchestrator::{AgentStatus, ForkRequest, Orchestrator};
+use crate::provider::Provider;
+use crate::Result;
+
+/// Launch a new agent from another agent's branch
+pub async fn run(
+    id: String,
+    task: String,
+    branch: Option<String>,
+    provider: Option<Provider>,
+) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
+
+    let fork_id = orchestrator
+        .fork(
+            &id,
+            ForkRequest {
+                task: task.clone(),
+                branch,
+                provider,
+            },
+        )
+        .await?;
+
+    let agent = orchestrator.get_agent(&fork_id.0)?;
+    let branch = agent.branch.clone();
+    let base = agent.base_branch.clone();
+    let provider = agent.provider;
+
+    if agent.status == AgentStatus::Queued {
+        println!("Queued agent {fork_id} on branch {branch}, forked from agent {id} ({base})");
+        println!("{}", queue_reason(&orchestrator, &fork_id.0)?);
+    } else {
+        println!("Launched agent {fork_id} on branch {branch}, forked from agent {id} ({base})");
+    }
+    println!("Provider: {provider}");
+    println!("Task: {task}");
+    println!();
+    println!("Use 'wta merge {fork_id} --into-parent' to merge it back into {base}");
+
+    Ok(())
+}
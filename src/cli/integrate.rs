@@ -0,0 +1,64 @@
+use crate::orchestrator::Orchestrator;
+use crate::Result;
+use colored::Colorize;
+
+/// Combine several agents on a new branch to test them together
+pub async fn run(ids: Vec<String>, branch: String, base: Option<String>) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    for id in &ids {
+        orchestrator.check_status(id)?;
+    }
+
+    let integration = orchestrator.integrate(&ids, &branch, base)?;
+
+    println!(
+        "Created {} from {} at {}",
+        integration.branch.cyan(),
+        integration.base_branch.cyan(),
+        integration.worktree_path.display()
+    );
+    for agent in &integration.agents {
+        if agent.is_merged() {
+            println!(
+                "  {} agent {} ({})",
+                "merged".green(),
+                agent.id,
+                agent.branch
+            );
+        } else {
+            println!(
+                "  {} agent {} ({}), left out:",
+                "conflict".red().bold(),
+                agent.id,
+                agent.branch
+            );
+            for file in &agent.conflicts {
+                println!("    {} {}", "-".red(), file.display());
+            }
+        }
+    }
+
+    let conflicted = integration.conflicted().count();
+    if conflicted > 0 {
+        println!();
+        println!(
+            "{} {conflicted} of {} agent(s) conflict with the agents merged before them",
+            "Warning:".yellow().bold(),
+            integration.agents.len()
+        );
+    }
+    println!();
+    println!(
+        "Test the combination in {}",
+        integration.worktree_path.display()
+    );
+    println!(
+        "Add more agents with 'wta merge <id> --target {branch}' (they are kept), or start one from it with 'wta launch --base {branch}'"
+    );
+    println!(
+        "Remove it with 'wta worktree remove {}'",
+        branch.replace('/', "-")
+    );
+
+    Ok(())
+}
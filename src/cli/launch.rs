@@ -1,33 +1,164 @@
+use crate::cli::{queue_reason, truncate_task};
+use crate::config::Config;
 use crate::editor::open_editor_for_task;
-use crate::orchestrator::{LaunchRequest, Orchestrator};
+use crate::error::Error;
+use crate::manifest::{self, TaskEntry};
+use crate::orchestrator::{AgentId, AgentStatus, LaunchRequest, Orchestrator};
 use crate::provider::Provider;
 use crate::Result;
+use colored::Colorize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tabled::settings::style::Style;
+use tabled::settings::Padding;
+use tabled::{Table, Tabled};
+
+const TASK_MAX_LEN: usize = 50;
 
 pub struct LaunchOptions {
     pub task: Option<String>,
     pub editor: Option<String>,
+    pub from: Option<PathBuf>,
     pub branch: Option<String>,
     pub base: Option<String>,
-    pub provider: Provider,
+    pub provider: Option<Provider>,
+    /// Launch one agent per provider as an attempt group
+    pub providers: Vec<Provider>,
+    /// Launch this many agents per provider as an attempt group
+    pub replicas: Option<u32>,
     pub code: bool,
-    pub dangerously_allow_all: bool,
-    pub enable_edits: bool,
+    /// Set by `--dangerously-allow-all` or `--no-dangerously-allow-all`
+    pub dangerously_allow_all: Option<bool>,
+    /// Set by `--enable-edits` or `--no-enable-edits`
+    pub enable_edits: Option<bool>,
     pub provider_args: Vec<String>,
+    pub depends_on: Vec<String>,
+    pub timeout: Option<Duration>,
+    /// Have agents propose a plan and wait for `wta approve` before editing
+    pub approve_plan: bool,
+}
+
+/// Launch settings shared by every request of a `wta launch` invocation
+struct LaunchDefaults {
+    base: Option<String>,
+    provider: Option<Provider>,
+    dangerously_allow_all: Option<bool>,
+    enable_edits: Option<bool>,
+    provider_args: Vec<String>,
+    timeout: Option<Duration>,
+    approve_plan: bool,
+}
+
+impl LaunchDefaults {
+    /// Build a request, letting explicit values win over CLI flags and CLI flags
+    /// win over .wta.toml and the user config
+    fn request(
+        &self,
+        config: &Config,
+        task: String,
+        branch: Option<String>,
+        base: Option<String>,
+        provider: Option<Provider>,
+        extra_args: Vec<String>,
+    ) -> LaunchRequest {
+        let provider = provider.or(self.provider).unwrap_or(config.provider.value);
+        let base = base
+            .or_else(|| self.base.clone())
+            .or_else(|| config.base.value.clone());
+        let dangerously_allow_all = self
+            .dangerously_allow_all
+            .unwrap_or(config.dangerously_allow_all.value);
+        let enable_edits = self.enable_edits.unwrap_or(config.enable_edits.value);
+        let timeout = self.timeout.or(config.timeout.value);
+
+        let mut provider_args = [
+            config.provider_args_for(provider),
+            self.provider_args.clone(),
+            extra_args,
+        ]
+        .concat();
+
+        if dangerously_allow_all {
+            match provider {
+                Provider::Claude | Provider::Amp => {
+                    provider_args.insert(0, "--dangerously-allow-all".to_string());
+                }
+                _ => {}
+            }
+        }
+
+        if enable_edits && provider == Provider::Claude {
+            provider_args.insert(0, "--enable-edits".to_string());
+        }
+
+        LaunchRequest {
+            task,
+            branch,
+            base,
+            provider,
+            provider_args,
+            depends_on: Vec::new(),
+            timeout,
+            group: None,
+            review_of: None,
+            approve_plan: self.approve_plan,
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct BatchRow {
+    #[tabled(rename = "#")]
+    index: usize,
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "BRANCH")]
+    branch: String,
+    #[tabled(rename = "PROVIDER")]
+    provider: String,
+    #[tabled(rename = "DEPENDS ON")]
+    depends_on: String,
+    #[tabled(rename = "RESULT")]
+    result: String,
+    #[tabled(rename = "TASK")]
+    task: String,
 }
 
 pub async fn run(options: LaunchOptions) -> Result<()> {
     let LaunchOptions {
         task,
         editor,
+        from,
         branch,
         base,
         provider,
+        providers,
+        replicas,
         code,
         dangerously_allow_all,
         enable_edits,
         provider_args,
+        depends_on,
+        timeout,
+        approve_plan,
     } = options;
 
+    let defaults = LaunchDefaults {
+        base,
+        provider,
+        dangerously_allow_all,
+        enable_edits,
+        provider_args,
+        timeout,
+        approve_plan,
+    };
+
+    if let Some(path) = from {
+        let entries = manifest::load(&path)?;
+        return run_batch(entries, defaults).await;
+    }
+
     // Resolve the task: either from --task, --editor, or error
     let task = match (task, editor) {
         (Some(t), None) => t,
@@ -36,58 +167,420 @@ pub async fn run(options: LaunchOptions) -> Result<()> {
             open_editor_for_task(editor_cmd)?
         }
         (None, None) => {
-            eprintln!("Error: Either --task or --editor must be provided");
+            eprintln!("Error: Either --task, --editor or --from must be provided");
             eprintln!("  Use --task \"description\" for inline task");
             eprintln!("  Use --editor [cmd] to compose in your editor");
+            eprintln!("  Use --from tasks.jsonl to launch a batch of tasks");
             std::process::exit(1);
         }
     };
 
-    let mut orchestrator = Orchestrator::new()?;
-
-    let mut provider_args = provider_args;
-    if dangerously_allow_all {
-        match provider {
-            Provider::Claude | Provider::Amp => {
-                provider_args.insert(0, "--dangerously-allow-all".to_string());
-            }
-            _ => {}
-        }
+    if !providers.is_empty() || replicas.is_some() {
+        return run_group(task, providers, replicas.unwrap_or(1), depends_on, defaults).await;
     }
 
-    if enable_edits {
-        match provider {
-            Provider::Claude => {
-                provider_args.insert(0, "--enable-edits".to_string());
-            }
-            _ => {}
-        }
-    }
+    let mut orchestrator = Orchestrator::new()?;
 
-    let request = LaunchRequest {
-        task: task.clone(),
+    let mut request = defaults.request(
+        orchestrator.config(),
+        task.clone(),
         branch,
-        base,
-        provider,
-        provider_args,
-    };
+        None,
+        None,
+        Vec::new(),
+    );
+    if !depends_on.is_empty() {
+        // Dependent agents start from their dependency unless --base is given
+        request.base = defaults.base.clone();
+        request.depends_on = depends_on.into_iter().map(AgentId).collect();
+    }
+    let provider = request.provider;
 
     let id = orchestrator.launch(request).await?;
+    let agent = orchestrator.get_agent(&id.0)?;
+    let branch = agent.branch.clone();
+
+    if agent.status == AgentStatus::Queued {
+        println!("Queued agent {id} on branch {branch}");
+        println!("{}", queue_reason(&orchestrator, &id.0)?);
+        println!("Provider: {provider}");
+        println!("Task: {task}");
+        println!();
+        println!("It starts automatically ('wta list' refreshes the queue)");
+        return Ok(());
+    }
 
     if code {
-        orchestrator.open_vscode(&id.to_string())?;
+        orchestrator.open_vscode(&id.0)?;
     }
 
-    println!("Launched agent {id} on branch wta/{id}");
+    println!("Launched agent {id} on branch {branch}");
     println!("Provider: {provider}");
     println!("Task: {task}");
     println!();
     println!("Use 'wta attach {id}' to watch the agent");
     println!("Use 'wta status {id}' to check progress");
 
-    if code {
-        orchestrator.open_vscode(&id.0)?;
+    Ok(())
+}
+
+/// Comma-separated agent IDs for display
+fn join_ids(ids: &[AgentId]) -> String {
+    ids.iter()
+        .map(|id| id.0.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Row of a launched agent. Its details are left out if it can no longer be
+/// looked up, such as when it was removed meanwhile; it still launched.
+fn launched_row(index: usize, orchestrator: &Orchestrator, id: &AgentId, task: String) -> BatchRow {
+    let Ok(agent) = orchestrator.get_agent(&id.0) else {
+        return BatchRow {
+            index,
+            id: id.0.bright_white().to_string(),
+            branch: "-".to_string(),
+            provider: "-".to_string(),
+            depends_on: "-".to_string(),
+            result: "launched".green().to_string(),
+            task,
+        };
+    };
+    let result = if agent.status == AgentStatus::Queued {
+        "queued".yellow().to_string()
+    } else {
+        "launched".green().to_string()
+    };
+    BatchRow {
+        index,
+        id: agent.id.0.bright_white().to_string(),
+        branch: agent.branch.cyan().to_string(),
+        provider: agent.provider.to_string(),
+        depends_on: join_ids(&agent.depends_on),
+        result,
+        task,
+    }
+}
+
+/// Launch error for the result column
+fn error_text(error: &Error) -> String {
+    // git stderr spans several lines; keep the table compact
+    error
+        .to_string()
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .red()
+        .to_string()
+}
+
+fn print_rows(rows: Vec<BatchRow>) {
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Padding::new(1, 1, 0, 0))
+        .to_string();
+    println!("{table}");
+}
+
+/// Launch the same task once per provider, `replicas` times each, as one
+/// attempt group. Like a batch, a failed launch does not stop the others.
+async fn run_group(
+    task: String,
+    providers: Vec<Provider>,
+    replicas: u32,
+    depends_on: Vec<String>,
+    defaults: LaunchDefaults,
+) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    let providers = if providers.is_empty() {
+        vec![defaults
+            .provider
+            .unwrap_or(orchestrator.config().provider.value)]
+    } else {
+        providers
+    };
+    let depends_on: Vec<AgentId> = depends_on.into_iter().map(AgentId).collect();
+    let group = orchestrator.new_group()?;
+    let total = providers.len() * replicas as usize;
+    let mut failed = 0;
+    let mut rows = Vec::with_capacity(total);
+
+    let launches = providers
+        .iter()
+        .flat_map(|p| std::iter::repeat_n(*p, replicas as usize));
+    for (index, provider) in launches.enumerate() {
+        let summary = truncate_task(&task, TASK_MAX_LEN);
+        let mut request = defaults.request(
+            orchestrator.config(),
+            task.clone(),
+            None,
+            None,
+            Some(provider),
+            Vec::new(),
+        );
+        request.group = Some(group.clone());
+        if !depends_on.is_empty() {
+            request.base = defaults.base.clone();
+            request.depends_on = depends_on.clone();
+        }
+
+        let row = match orchestrator.launch(request).await {
+            Ok(id) => launched_row(index + 1, &orchestrator, &id, summary),
+            Err(e) => {
+                failed += 1;
+                BatchRow {
+                    index: index + 1,
+                    id: "-".to_string(),
+                    branch: "-".to_string(),
+                    provider: provider.to_string(),
+                    depends_on: join_ids(&depends_on),
+                    result: error_text(&e),
+                    task: summary,
+                }
+            }
+        };
+        rows.push(row);
+    }
+
+    print_rows(rows);
+    println!(
+        "Launched {} of {total} agent(s) in attempt group {group}",
+        total - failed
+    );
+    println!("Use 'wta compare {group}' to compare them");
+
+    if failed > 0 {
+        return Err(Error::BatchLaunchFailed { failed, total });
     }
 
     Ok(())
 }
+
+/// Launch the entries of an approved `wta plan` as a batch
+pub(crate) async fn run_plan(
+    entries: Vec<TaskEntry>,
+    base: Option<String>,
+    provider: Option<Provider>,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let defaults = LaunchDefaults {
+        base,
+        provider,
+        dangerously_allow_all: None,
+        enable_edits: None,
+        provider_args: Vec::new(),
+        timeout,
+        approve_plan: false,
+    };
+    run_batch(entries, defaults).await
+}
+
+/// Launch every manifest entry, parents before the entries that depend on them.
+/// A failed entry is reported in the summary but does not stop or roll back the
+/// others; entries depending on it are not launched.
+async fn run_batch(entries: Vec<TaskEntry>, defaults: LaunchDefaults) -> Result<()> {
+    let order = manifest::launch_order(&entries)?;
+    let mut orchestrator = Orchestrator::new()?;
+    let total = entries.len();
+    let mut failed = 0;
+    let mut rows = Vec::with_capacity(total);
+    // Manifest id -> launched agent, or None if that entry failed
+    let mut launched: HashMap<String, Option<AgentId>> = HashMap::new();
+
+    for index in order {
+        let entry = entries[index].clone();
+        let task = truncate_task(&entry.task, TASK_MAX_LEN);
+
+        let depends_on: std::result::Result<Vec<AgentId>, Error> = entry
+            .depends_on
+            .iter()
+            .map(|dep| match launched.get(dep) {
+                Some(Some(id)) => Ok(id.clone()),
+                Some(None) => Err(Error::DependencyFailed(dep.clone())),
+                // Not a manifest entry: an existing agent ID
+                None => Ok(AgentId(dep.clone())),
+            })
+            .collect();
+
+        let mut request = defaults.request(
+            orchestrator.config(),
+            entry.task,
+            entry.branch.clone(),
+            entry.base.clone(),
+            entry.provider,
+            entry.provider_args,
+        );
+        if entry.timeout.is_some() {
+            request.timeout = entry.timeout;
+        }
+        let provider = request.provider.to_string();
+
+        let result = match depends_on {
+            Ok(depends_on) => {
+                if !depends_on.is_empty() {
+                    // Dependent entries start from their dependency unless a base is given
+                    request.base = entry.base.or_else(|| defaults.base.clone());
+                    request.depends_on = depends_on;
+                }
+                orchestrator.launch(request).await
+            }
+            Err(e) => Err(e),
+        };
+
+        let row = match result {
+            Ok(id) => {
+                let row = launched_row(index + 1, &orchestrator, &id, task);
+                if let Some(entry_id) = entry.id {
+                    launched.insert(entry_id, Some(id));
+                }
+                row
+            }
+            Err(e) => {
+                failed += 1;
+                if let Some(entry_id) = entry.id {
+                    launched.insert(entry_id, None);
+                }
+                BatchRow {
+                    index: index + 1,
+                    id: "-".to_string(),
+                    branch: entry.branch.unwrap_or_else(|| "-".to_string()),
+                    provider,
+                    depends_on: entry.depends_on.join(", "),
+                    result: error_text(&e),
+                    task,
+                }
+            }
+        };
+        rows.push(row);
+    }
+
+    print_rows(rows);
+    println!("Launched {} of {total} agent(s)", total - failed);
+
+    if failed > 0 {
+        return Err(Error::BatchLaunchFailed { failed, total });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> LaunchDefaults {
+        LaunchDefaults {
+            base: None,
+            provider: None,
+            dangerously_allow_all: None,
+            enable_edits: None,
+            provider_args: Vec::new(),
+            timeout: None,
+            approve_plan: false,
+        }
+    }
+
+    #[test]
+    fn test_request_uses_config_when_nothing_specified() {
+        let config = Config::default();
+        let request = defaults().request(&config, "Task".into(), None, None, None, Vec::new());
+
+        assert_eq!(request.provider, Provider::Claude);
+        assert!(request.base.is_none());
+        assert!(request.provider_args.is_empty());
+    }
+
+    #[test]
+    fn test_request_entry_overrides_cli_flags() {
+        let config = Config::default();
+        let cli = LaunchDefaults {
+            base: Some("develop".into()),
+            provider: Some(Provider::Gemini),
+            ..defaults()
+        };
+
+        let request = cli.request(
+            &config,
+            "Task".into(),
+            Some("feature/x".into()),
+            Some("release".into()),
+            Some(Provider::Codex),
+            vec!["--model".into(), "o3".into()],
+        );
+
+        assert_eq!(request.provider, Provider::Codex);
+        assert_eq!(request.base.as_deref(), Some("release"));
+        assert_eq!(request.branch.as_deref(), Some("feature/x"));
+        assert_eq!(request.provider_args, vec!["--model", "o3"]);
+
+        let request = cli.request(&config, "Task".into(), None, None, None, Vec::new());
+        assert_eq!(request.provider, Provider::Gemini);
+        assert_eq!(request.base.as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn test_request_timeout_flag_overrides_config() {
+        let mut config = Config::default();
+        config.timeout.value = Some(Duration::from_secs(3600));
+
+        let request = defaults().request(&config, "Task".into(), None, None, None, Vec::new());
+        assert_eq!(request.timeout, Some(Duration::from_secs(3600)));
+
+        let cli = LaunchDefaults {
+            timeout: Some(Duration::from_secs(45 * 60)),
+            ..defaults()
+        };
+        let request = cli.request(&config, "Task".into(), None, None, None, Vec::new());
+        assert_eq!(request.timeout, Some(Duration::from_secs(45 * 60)));
+    }
+
+    #[test]
+    fn test_request_permission_flags_only_for_supported_providers() {
+        let config = Config::default();
+        let cli = LaunchDefaults {
+            dangerously_allow_all: Some(true),
+            enable_edits: Some(true),
+            provider_args: vec!["--verbose".into()],
+            ..defaults()
+        };
+
+        let claude = cli.request(&config, "Task".into(), None, None, None, Vec::new());
+        assert_eq!(
+            claude.provider_args,
+            vec!["--enable-edits", "--dangerously-allow-all", "--verbose"]
+        );
+
+        let codex = cli.request(
+            &config,
+            "Task".into(),
+            None,
+            None,
+            Some(Provider::Codex),
+            Vec::new(),
+        );
+        assert_eq!(codex.provider_args, vec!["--verbose"]);
+    }
+
+    #[test]
+    fn test_request_permission_flags_override_config() {
+        let mut config = Config::default();
+        config.dangerously_allow_all.value = true;
+        config.enable_edits.value = true;
+
+        let request = defaults().request(&config, "Task".into(), None, None, None, Vec::new());
+        assert_eq!(
+            request.provider_args,
+            vec!["--enable-edits", "--dangerously-allow-all"]
+        );
+
+        let cli = LaunchDefaults {
+            dangerously_allow_all: Some(false),
+            enable_edits: Some(false),
+            ..defaults()
+        };
+        let request = cli.request(&config, "Task".into(), None, None, None, Vec::new());
+        assert!(request.provider_args.is_empty());
+    }
+}
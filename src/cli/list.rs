@@ -1,12 +1,18 @@
-use crate::cli::truncate_task;
-use crate::orchestrator::{AgentStatus, Orchestrator};
+use crate::cli::{colorize_severity, colorize_status, progress_line, report_text, truncate_task};
+use crate::orchestrator::{Agent, Orchestrator, Severity};
+use crate::watcher::StatusWatcher;
 use crate::Result;
 use colored::Colorize;
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
 use tabled::settings::style::Style;
 use tabled::settings::Padding;
 use tabled::{Table, Tabled};
 
 const TASK_MAX_LEN: usize = 50;
+/// Fallback refresh in watch mode, for changes the filesystem cannot report
+const WATCH_FALLBACK: Duration = Duration::from_secs(5);
+const WATCH_HISTORY: usize = 10;
 
 #[derive(Tabled)]
 struct AgentRow {
@@ -16,61 +22,318 @@ struct AgentRow {
     branch: String,
     #[tabled(rename = "STATUS")]
     status: String,
+    #[tabled(rename = "DEPENDS ON")]
+    depends_on: String,
     #[tabled(rename = "TASK")]
     task: String,
+    #[tabled(rename = "PROGRESS")]
+    progress: String,
 }
 
-fn colorize_status(status: &AgentStatus) -> String {
-    match status {
-        AgentStatus::Running => status.to_string().bright_blue().bold().to_string(),
-        AgentStatus::Completed => status.to_string().magenta().to_string(),
-        AgentStatus::Failed => status.to_string().red().bold().to_string(),
-        AgentStatus::Merged => status.to_string().green().to_string(),
-        AgentStatus::Conflict => status.to_string().yellow().bold().to_string(),
+/// Row for `--wide`, adding what the agent reported
+#[derive(Tabled)]
+struct WideAgentRow {
+    #[tabled(inline)]
+    row: AgentRow,
+    #[tabled(rename = "FILES")]
+    files: String,
+    #[tabled(rename = "REPORT")]
+    report: String,
+}
+
+/// First dependency of an agent, or the agent it was forked from or reviews,
+/// if it is still listed
+fn parent_id<'a>(agent: &'a Agent, ids: &HashSet<&str>) -> Option<&'a str> {
+    agent
+        .depends_on
+        .first()
+        .or(agent.parent.as_ref())
+        .or(agent.review_of.as_ref())
+        .map(|d| d.0.as_str())
+        .filter(|d| ids.contains(d))
+}
+
+/// Order agents as a forest: each agent is listed under its first dependency,
+/// forks under the agent they were forked from, and reviewers under the agent
+/// they review.
+/// Returns each agent with its depth in the tree.
+fn graph_order<'a>(agents: &[&'a Agent]) -> Vec<(usize, &'a Agent)> {
+    fn visit<'a>(
+        agent: &'a Agent,
+        depth: usize,
+        agents: &[&'a Agent],
+        ids: &HashSet<&str>,
+        seen: &mut HashSet<&'a str>,
+        out: &mut Vec<(usize, &'a Agent)>,
+    ) {
+        if !seen.insert(agent.id.0.as_str()) {
+            return;
+        }
+        out.push((depth, agent));
+        for child in agents
+            .iter()
+            .filter(|c| parent_id(c, ids) == Some(agent.id.0.as_str()))
+        {
+            visit(child, depth + 1, agents, ids, seen, out);
+        }
+    }
+
+    let ids: HashSet<&str> = agents.iter().map(|a| a.id.0.as_str()).collect();
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(agents.len());
+    for root in agents.iter().filter(|a| parent_id(a, &ids).is_none()) {
+        visit(root, 0, agents, &ids, &mut seen, &mut out);
     }
+    out
 }
 
-pub async fn run() -> Result<()> {
+pub async fn run(watch: bool, wide: bool) -> Result<()> {
     let mut orchestrator = Orchestrator::new()?;
 
-    // Collect agent IDs that need status refresh (those currently showing as Running)
-    let running_ids: Vec<String> = orchestrator
-        .list()
-        .iter()
-        .filter(|a| a.status == AgentStatus::Running)
-        .map(|a| a.id.0.clone())
-        .collect();
+    if watch {
+        return run_watch(orchestrator, wide).await;
+    }
+
+    // Refresh running and queued agents, unless the daemon already does
+    orchestrator.sync_status()?;
+
+    println!("{}", render(&mut orchestrator, wide));
 
-    // Refresh status for running agents (checks status file and tmux window existence)
-    for id in &running_ids {
-        let _ = orchestrator.check_status(id);
+    Ok(())
+}
+
+/// Redraw the table whenever agent files change, with a log of recent transitions
+async fn run_watch(mut orchestrator: Orchestrator, wide: bool) -> Result<()> {
+    let mut watcher = StatusWatcher::new(&orchestrator)?;
+    let mut history = VecDeque::with_capacity(WATCH_HISTORY);
+
+    loop {
+        for change in orchestrator.sync_status()? {
+            if history.len() == WATCH_HISTORY {
+                history.pop_front();
+            }
+            history.push_back(format!(
+                "{}  agent {}: {} → {}",
+                chrono::Local::now().format("%H:%M:%S"),
+                change.id.0.bright_white(),
+                colorize_status(&change.from),
+                colorize_status(&change.to)
+            ));
+        }
+
+        // Clear the screen and move the cursor home
+        print!("\x1b[2J\x1b[H");
+        println!("{}", render(&mut orchestrator, wide));
+        println!("Watching for changes (Ctrl-C to exit)");
+        for line in &history {
+            println!("{line}");
+        }
+
+        tokio::select! {
+            _ = watcher.wait(WATCH_FALLBACK) => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
     }
 
-    // Now get the updated list
+    Ok(())
+}
+
+fn render(orchestrator: &mut Orchestrator, wide: bool) -> String {
+    let overlaps = orchestrator.overlaps();
     let agents = orchestrator.list();
 
     if agents.is_empty() {
-        println!("No agents running.");
-        return Ok(());
+        return "No agents running.".to_string();
     }
 
-    let mut rows = Vec::with_capacity(agents.len());
-    for a in agents {
-        let task = truncate_task(&a.task, TASK_MAX_LEN);
+    let now = chrono::Utc::now();
+    let order = graph_order(&agents);
+    let mut rows = Vec::with_capacity(order.len());
+    for &(depth, a) in &order {
+        let mut task = truncate_task(a.task(), TASK_MAX_LEN);
+        if a.tasks.len() > 1 {
+            task = format!("{task} (+{})", a.tasks.len() - 1);
+        }
+        let tree = if depth == 0 {
+            String::new()
+        } else {
+            format!("{}└─ ", "   ".repeat(depth - 1))
+        };
+        let group = a
+            .group
+            .as_ref()
+            .map(|g| format!(" ({g})").dimmed().to_string())
+            .unwrap_or_default();
+        let depends_on = a
+            .depends_on
+            .iter()
+            .map(|d| d.0.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // An open question replaces the progress line until it is answered
+        let (id, progress) = match a.open_question().filter(|_| a.status.is_active()) {
+            Some(q) => (
+                a.id.0.black().on_bright_yellow().to_string(),
+                truncate_task(&format!("? {}", q.question), TASK_MAX_LEN)
+                    .bright_yellow()
+                    .bold()
+                    .to_string(),
+            ),
+            None => (
+                a.id.0.bright_white().to_string(),
+                a.progress
+                    .as_ref()
+                    .map(|p| truncate_task(&progress_line(p, now), TASK_MAX_LEN))
+                    .unwrap_or_default(),
+            ),
+        };
 
         rows.push(AgentRow {
-            id: a.id.0.bright_white().to_string(),
+            id: format!("{tree}{id}{group}"),
             branch: a.branch.cyan().to_string(),
             status: colorize_status(&a.status),
+            depends_on,
             task: task.white().to_string(),
+            progress,
         });
     }
 
-    let table = Table::new(rows)
+    let mut table = if wide {
+        Table::new(rows.into_iter().zip(&order).map(|(row, &(_, a))| {
+            WideAgentRow {
+                row,
+                files: a
+                    .report
+                    .as_ref()
+                    .map(|r| r.files_changed.len().to_string())
+                    .unwrap_or_default(),
+                report: report_text(a, TASK_MAX_LEN),
+            }
+        }))
+    } else {
+        Table::new(rows)
+    };
+
+    let mut out = table
         .with(Style::rounded())
         .with(Padding::new(1, 1, 0, 0))
         .to_string();
-    println!("{table}");
 
-    Ok(())
+    // Low overlaps (same file, far apart) are left to `wta overlaps`
+    for overlap in overlaps.iter().filter(|o| o.severity >= Severity::Medium) {
+        let (a, b) = &overlap.agents;
+        let files: Vec<&str> = overlap.files.iter().map(|f| f.path.as_str()).collect();
+        out.push_str(&format!(
+            "\n{} agents {a} and {b} both edit {} ({}; see 'wta overlaps')",
+            "Overlap:".yellow().bold(),
+            truncate_task(&files.join(", "), TASK_MAX_LEN),
+            colorize_severity(overlap.severity)
+        ));
+    }
+
+    for integration in orchestrator.integrations() {
+        let ids = |merged: bool| -> Vec<&str> {
+            integration
+                .agents
+                .iter()
+                .filter(|a| a.is_merged() == merged)
+                .map(|a| a.id.0.as_str())
+                .collect()
+        };
+        let left_out = ids(false);
+        out.push_str(&format!(
+            "\n{} {} combines agents {}{}",
+            "Integration:".cyan().bold(),
+            integration.branch.cyan(),
+            ids(true).join(", "),
+            if left_out.is_empty() {
+                String::new()
+            } else {
+                format!("; {} conflicted", left_out.join(", "))
+                    .red()
+                    .to_string()
+            }
+        ));
+    }
+
+    let asking: Vec<&str> = order
+        .iter()
+        .filter(|(_, a)| a.status.is_active() && a.open_question().is_some())
+        .map(|(_, a)| a.id.0.as_str())
+        .collect();
+    if !asking.is_empty() {
+        out.push_str(&format!(
+            "\n{} waiting for an answer: wta answer <id> \"...\" ('wta status <id>' shows the question)",
+            format!("Agent(s) {}", asking.join(", ")).bright_yellow().bold()
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::AgentId;
+
+    fn agent(id: u128, depends_on: &[&str]) -> Agent {
+        let mut agent = Agent::create_test_agent(id);
+        agent.depends_on = depends_on.iter().map(|d| AgentId(d.to_string())).collect();
+        agent
+    }
+
+    fn order(agents: &[Agent]) -> Vec<(usize, String)> {
+        let refs: Vec<&Agent> = agents.iter().collect();
+        graph_order(&refs)
+            .into_iter()
+            .map(|(depth, a)| (depth, a.id.0.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_graph_order_nests_dependents_under_first_dependency() {
+        let agents = [
+            agent(1, &[]),
+            agent(2, &[]),
+            agent(3, &["1"]),
+            agent(4, &["3", "2"]),
+        ];
+
+        assert_eq!(
+            order(&agents),
+            vec![
+                (0, "1".to_string()),
+                (1, "3".to_string()),
+                (2, "4".to_string()),
+                (0, "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_graph_order_nests_forks_under_parent() {
+        let mut fork = agent(3, &[]);
+        fork.parent = Some(AgentId("1".to_string()));
+        let agents = [agent(1, &[]), agent(2, &[]), fork];
+
+        assert_eq!(
+            order(&agents),
+            vec![
+                (0, "1".to_string()),
+                (1, "3".to_string()),
+                (0, "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_graph_order_treats_missing_dependency_as_root() {
+        // Agent 1 was removed; 2 still lists it
+        let agents = [agent(2, &["1"]), agent(3, &[])];
+
+        assert_eq!(
+            order(&agents),
+            vec![(0, "2".to_string()), (0, "3".to_string())]
+        );
+    }
 }
@@ -1,15 +1,25 @@
 use crate::error::Error;
-use crate::orchestrator::{MergeStrategy, Orchestrator};
+use crate::merge_queue::{land, Landing};
+use crate::orchestrator::{MergeStrategy, Mergeability, Orchestrator};
 use crate::Result;
 use colored::Colorize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Lines of a failed check's output shown by the merge queue
+const CHECK_TAIL_LINES: usize = 10;
 
 pub async fn run(
     id: String,
     target: Option<String>,
-    strategy: MergeStrategy,
+    into_parent: bool,
+    strategy: Option<MergeStrategy>,
     force: bool,
+    update_checkout: Option<bool>,
 ) -> Result<()> {
     let mut orchestrator = Orchestrator::new()?;
+    let strategy = strategy.unwrap_or(orchestrator.config().merge_strategy.value);
+    let update_checkout = update_checkout.unwrap_or(orchestrator.config().update_checkout.value);
 
     // Check status (updates from status file if exists)
     orchestrator.check_status(&id)?;
@@ -17,8 +27,22 @@ pub async fn run(
     // Get agent info before merge for error messages
     let agent = orchestrator.get_agent(&id)?;
     let branch = agent.branch.clone();
+    let worktree = agent.worktree_path.clone();
+    let retry = match &target {
+        _ if into_parent => format!("wta merge {id} --into-parent"),
+        Some(target) => format!("wta merge {id} --target {target}"),
+        None => format!("wta merge {id}"),
+    };
+    let target = if into_parent {
+        Some(orchestrator.parent_branch(&id)?)
+    } else {
+        target
+    };
 
-    let result = match orchestrator.merge(&id, target, strategy, force).await {
+    let result = match orchestrator
+        .merge(&id, target, strategy, force, update_checkout)
+        .await
+    {
         Ok(result) => result,
         Err(Error::MergeConflict(conflicts)) => {
             println!("{}", "Merge conflict detected!".red().bold());
@@ -28,11 +52,13 @@ pub async fn run(
                 println!("  {} {}", "-".red(), file.display());
             }
             println!();
+            println!("Nothing was changed; the agent's worktree is kept.");
             println!(
-                "{} Resolve conflicts in {}, then run: {}",
+                "{} Resolve conflicts in {} (worktree {}), then run: {}",
                 "Fix:".yellow().bold(),
                 branch.cyan(),
-                format!("wta merge {id}").green()
+                worktree.display(),
+                retry.green()
             );
             return Ok(());
         }
@@ -53,3 +79,157 @@ pub async fn run(
 
     Ok(())
 }
+
+/// Show whether the agent's branch would merge cleanly, without merging
+pub fn run_dry(
+    id: String,
+    target: Option<String>,
+    into_parent: bool,
+    strategy: Option<MergeStrategy>,
+) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    let strategy = strategy.unwrap_or(orchestrator.config().merge_strategy.value);
+    orchestrator.check_status(&id)?;
+
+    let target = if into_parent {
+        Some(orchestrator.parent_branch(&id)?)
+    } else {
+        target
+    };
+    let check = orchestrator.mergeability(&id, target)?;
+    print_mergeability(&check);
+
+    if strategy == MergeStrategy::Rebase && !check.is_clean() {
+        println!(
+            "Checked as a merge; a rebase replays each commit and may stop on different conflicts."
+        );
+    }
+    if orchestrator.get_agent(&id)?.status.holds_session() {
+        println!("The agent is still running; only its committed work was checked.");
+    }
+    Ok(())
+}
+
+fn print_mergeability(check: &Mergeability) {
+    if check.is_clean() {
+        println!(
+            "{} merges cleanly into {}",
+            check.branch.cyan(),
+            check.target.cyan()
+        );
+        return;
+    }
+
+    println!(
+        "{} {} conflicts with {} in {} file(s):",
+        "Conflicts:".red().bold(),
+        check.branch.cyan(),
+        check.target.cyan(),
+        check.conflicts.len()
+    );
+    for conflict in &check.conflicts {
+        println!();
+        println!("  {} {}", "-".red(), conflict.path.display());
+        if let Some(reason) = &conflict.reason {
+            println!("    {}", reason.dimmed());
+        }
+        for hunk in &conflict.hunks {
+            println!("    {}", format!("@ line {}", hunk.line).dimmed());
+            println!("    {}", format!("<<<<<<< {}", check.target).dimmed());
+            for line in hunk.ours.lines() {
+                println!("    {}", line.red());
+            }
+            println!("    {}", "=======".dimmed());
+            for line in hunk.theirs.lines() {
+                println!("    {}", line.green());
+            }
+            println!("    {}", format!(">>>>>>> {}", check.branch).dimmed());
+        }
+    }
+}
+
+/// Land several agents one at a time, each rebased onto the target left by
+/// the previous one and checked before it is merged
+pub async fn run_queue(
+    ids: Vec<String>,
+    target: Option<String>,
+    strategy: Option<MergeStrategy>,
+    update_checkout: Option<bool>,
+) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    let strategy = strategy.unwrap_or(orchestrator.config().merge_strategy.value);
+    orchestrator.sync_status()?;
+    let target = orchestrator.merge_target(target)?;
+    let check = orchestrator.config().check_command.value.clone();
+    let orchestrator = tokio::sync::Mutex::new(orchestrator);
+
+    // Checks run in their own process group, out of reach of Ctrl-C
+    let cancel = Arc::new(AtomicBool::new(false));
+    let on_interrupt = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            on_interrupt.store(true, Ordering::Relaxed);
+        }
+    });
+
+    let total = ids.len();
+    let mut failed = 0;
+    for (i, id) in ids.iter().enumerate() {
+        match &check {
+            Some(command) => println!(
+                "[{}/{total}] Agent {id}: rebasing onto {target} and running '{command}'...",
+                i + 1
+            ),
+            None => println!("[{}/{total}] Agent {id}: rebasing onto {target}...", i + 1),
+        }
+
+        let landing = land(
+            &orchestrator,
+            id,
+            Some(target.clone()),
+            strategy,
+            update_checkout,
+            cancel.clone(),
+        )
+        .await;
+        if !landing.is_merged() {
+            failed += 1;
+        }
+        match landing {
+            Landing::Merged { message, .. } => println!("  {}", message.green()),
+            Landing::Conflict { files } => {
+                println!(
+                    "  {} with {target}; marked conflict",
+                    "Conflicts".red().bold()
+                );
+                for file in &files {
+                    println!("    {} {}", "-".red(), file.display());
+                }
+            }
+            Landing::ChecksFailed { check } => {
+                println!(
+                    "  {} ('{}'); marked checks-failed",
+                    "Check failed".red().bold(),
+                    check.command
+                );
+                let lines: Vec<&str> = check.output.lines().collect();
+                for line in &lines[lines.len().saturating_sub(CHECK_TAIL_LINES)..] {
+                    println!("    {}", line.dimmed());
+                }
+            }
+            Landing::Failed { error } => println!("  {} {error}", "Skipped:".yellow().bold()),
+            Landing::Cancelled => {
+                println!("  {}", "Interrupted".yellow().bold());
+                failed += total - i - 1;
+                break;
+            }
+        }
+    }
+
+    println!();
+    println!("Landed {} of {total} agent(s) on {target}", total - failed);
+    if failed > 0 {
+        return Err(Error::MergeQueueFailed { failed, total });
+    }
+    Ok(())
+}
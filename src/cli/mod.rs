@@ -1,17 +1,49 @@
+pub mod answer;
+pub mod approve;
+pub mod ask;
 pub mod attach;
 pub mod claude_skill;
+pub mod compare;
+pub mod config;
+pub mod continue_task;
+pub mod daemon;
 pub mod diff;
+pub mod fork;
 pub mod init;
+pub mod integrate;
 pub mod launch;
 pub mod list;
 pub mod merge;
+pub mod overlaps;
+pub mod plan;
 pub mod pr;
 pub mod prune;
 pub mod quickstart;
+pub mod reject;
 pub mod remove;
+pub mod report;
+pub mod retry;
+pub mod review;
+pub mod send;
 pub mod status;
 pub mod worktree;
 
+use crate::orchestrator::{
+    Agent, AgentStatus, Orchestrator, ProgressEvent, ReportStatus, Severity,
+};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+
+/// Value of a `--flag`/`--no-flag` pair; `None` when neither is given, so the
+/// config decides
+pub fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
 /// Truncates a task string to `max_len` characters, adding "..." suffix when truncated.
 pub fn truncate_task(task: &str, max_len: usize) -> String {
     if task.len() > max_len {
@@ -21,10 +53,127 @@ pub fn truncate_task(task: &str, max_len: usize) -> String {
     }
 }
 
+/// Time since `at` in its largest whole unit, e.g. "45s", "12m", "3h", "2d"
+pub fn format_age(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - at).num_seconds().max(0);
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Latest progress line of an agent, with its percentage and age
+pub fn progress_line(progress: &ProgressEvent, now: DateTime<Utc>) -> String {
+    let percent = progress
+        .percent
+        .map(|p| format!("{p}% "))
+        .unwrap_or_default();
+    format!(
+        "{percent}{} ({} ago)",
+        progress.message,
+        format_age(progress.at, now)
+    )
+}
+
+/// Status text in the colour `wta list` uses for it
+pub fn colorize_status(status: &AgentStatus) -> String {
+    match status {
+        AgentStatus::Queued => status.to_string().dimmed().to_string(),
+        AgentStatus::Running => status.to_string().bright_blue().bold().to_string(),
+        AgentStatus::Stalled => status.to_string().yellow().to_string(),
+        AgentStatus::NeedsInput => status.to_string().bright_yellow().bold().to_string(),
+        AgentStatus::Completed => status.to_string().magenta().to_string(),
+        AgentStatus::Failed => status.to_string().red().bold().to_string(),
+        AgentStatus::Merged => status.to_string().green().to_string(),
+        AgentStatus::Conflict => status.to_string().yellow().bold().to_string(),
+        AgentStatus::ChecksFailed => status.to_string().red().bold().to_string(),
+        AgentStatus::InvalidReport => status.to_string().red().to_string(),
+        AgentStatus::TimedOut => status.to_string().red().to_string(),
+        AgentStatus::AwaitingApproval => status.to_string().bright_cyan().bold().to_string(),
+    }
+}
+
+/// Severity colored by how urgent it is
+pub fn colorize_severity(severity: Severity) -> String {
+    match severity {
+        Severity::Low => severity.to_string().dimmed().to_string(),
+        Severity::Medium => severity.to_string().yellow().to_string(),
+        Severity::High => severity.to_string().red().bold().to_string(),
+    }
+}
+
+/// Summary of an agent's report, or why its report was rejected
+pub fn report_text(agent: &Agent, max_len: usize) -> String {
+    if let Some(error) = &agent.report_error {
+        return truncate_task(error, max_len).red().to_string();
+    }
+    let Some(report) = &agent.report else {
+        return String::new();
+    };
+    match (&report.error, &report.summary) {
+        (Some(error), _) if report.status == ReportStatus::Failed => {
+            truncate_task(error, max_len).red().to_string()
+        }
+        (_, Some(summary)) => truncate_task(summary, max_len),
+        _ => match &report.plan {
+            Some(plan) if report.status == ReportStatus::Plan => {
+                let first = plan.lines().next().unwrap_or_default();
+                truncate_task(&format!("plan: {first}"), max_len)
+            }
+            _ => String::new(),
+        },
+    }
+}
+
+/// Explain what a queued agent is waiting for
+pub fn queue_reason(orchestrator: &Orchestrator, id: &str) -> crate::Result<String> {
+    let waiting = orchestrator.unfinished_dependencies(id)?;
+    if !waiting.is_empty() {
+        let ids: Vec<&str> = waiting.iter().map(|d| d.0.as_str()).collect();
+        return Ok(format!(
+            "Waiting for agent(s) {} to complete",
+            ids.join(", ")
+        ));
+    }
+
+    Ok(match orchestrator.config().max_running.value {
+        Some(max) => format!("Waiting for a free slot (max_running = {max})"),
+        None => "Waiting to start".to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_age_units() {
+        let now = Utc::now();
+        assert_eq!(format_age(now, now), "0s");
+        assert_eq!(format_age(now - chrono::Duration::seconds(59), now), "59s");
+        assert_eq!(format_age(now - chrono::Duration::minutes(12), now), "12m");
+        assert_eq!(format_age(now - chrono::Duration::hours(3), now), "3h");
+        assert_eq!(format_age(now - chrono::Duration::days(2), now), "2d");
+        // Clock skew between processes must not produce negative ages
+        assert_eq!(format_age(now + chrono::Duration::seconds(5), now), "0s");
+    }
+
+    #[test]
+    fn test_progress_line() {
+        let now = Utc::now();
+        let mut progress = ProgressEvent {
+            at: now - chrono::Duration::minutes(2),
+            message: "writing tests".to_string(),
+            percent: Some(60),
+        };
+        assert_eq!(progress_line(&progress, now), "60% writing tests (2m ago)");
+
+        progress.percent = None;
+        assert_eq!(progress_line(&progress, now), "writing tests (2m ago)");
+    }
+
     #[test]
     fn test_truncate_task_shorter_than_max() {
         let task = "Short task";
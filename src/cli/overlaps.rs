@@ -0,0 +1,66 @@
+use crate::cli::colorize_severity;
+use crate::orchestrator::{format_ranges, Orchestrator};
+use crate::Result;
+use colored::Colorize;
+use tabled::settings::style::Style;
+use tabled::settings::Padding;
+use tabled::{Table, Tabled};
+
+#[derive(Tabled)]
+struct OverlapRow {
+    #[tabled(rename = "AGENTS")]
+    agents: String,
+    #[tabled(rename = "SEVERITY")]
+    severity: String,
+    #[tabled(rename = "FILE")]
+    file: String,
+    #[tabled(rename = "LINES")]
+    lines: String,
+}
+
+/// Show pairs of active agents whose branches change the same files
+pub async fn run() -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    orchestrator.sync_status()?;
+
+    let overlaps = orchestrator.overlaps();
+    if overlaps.is_empty() {
+        println!("No overlapping changes between active agents.");
+        return Ok(());
+    }
+
+    let mut rows = Vec::new();
+    for overlap in &overlaps {
+        let (a, b) = &overlap.agents;
+        for (i, file) in overlap.files.iter().enumerate() {
+            rows.push(OverlapRow {
+                agents: if i == 0 {
+                    format!("{} ↔ {}", a.0.bright_white(), b.0.bright_white())
+                } else {
+                    String::new()
+                },
+                severity: colorize_severity(file.severity),
+                file: file.path.cyan().to_string(),
+                lines: match &file.lines {
+                    Some((ours, theirs)) => {
+                        format!("{} / {}", format_ranges(ours), format_ranges(theirs))
+                    }
+                    None => "different bases".dimmed().to_string(),
+                },
+            });
+        }
+    }
+
+    println!(
+        "{}",
+        Table::new(rows)
+            .with(Style::rounded())
+            .with(Padding::new(1, 1, 0, 0))
+    );
+    println!("high: changed lines overlap or touch, and will conflict when both are merged");
+    println!("medium: changes within a few lines, or branches with different bases");
+    println!("low: the same file, changed in different places");
+    println!("Only committed changes are compared; line numbers are those of the base branch.");
+
+    Ok(())
+}
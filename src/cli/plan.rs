@@ -0,0 +1,143 @@
+use crate::cli::launch;
+use crate::editor::edit_text;
+use crate::error::Error;
+use crate::manifest;
+use crate::orchestrator::Orchestrator;
+use crate::plan;
+use crate::provider::Provider;
+use crate::Result;
+use std::time::Duration;
+
+/// Start of the comment an invalid plan is reopened with
+const INVALID_PLAN_NOTE: &str = "# Invalid plan: ";
+
+pub struct PlanOptions {
+    pub task: String,
+    pub provider: Option<Provider>,
+    pub base: Option<String>,
+    pub editor: Option<String>,
+    pub yes: bool,
+    pub timeout: Option<Duration>,
+}
+
+/// Ask the provider to split a task into subtasks, let the user edit the
+/// plan, and launch what is left as a batch
+pub async fn run(options: PlanOptions) -> Result<()> {
+    let PlanOptions {
+        task,
+        provider,
+        base,
+        editor,
+        yes,
+        timeout,
+    } = options;
+
+    let orchestrator = Orchestrator::new()?;
+    let planner = provider.unwrap_or(orchestrator.config().provider.value);
+
+    println!("Planning with {planner}...");
+    let proposed = plan::generate(planner, &task, orchestrator.repo_root())?;
+    let content = plan::to_toml(&task, &proposed)?;
+
+    let entries = if yes {
+        manifest::parse(&content, true).map_err(Error::InvalidPlan)?
+    } else {
+        edit_plan(editor, content)?
+    };
+
+    launch::run_plan(entries, base, provider, timeout).await
+}
+
+/// Let the user edit the plan until it parses, reopening the editor with the
+/// error on top. Saving it unchanged gives up, keeping the edits in a file.
+fn edit_plan(editor: Option<String>, mut content: String) -> Result<Vec<manifest::TaskEntry>> {
+    loop {
+        let edited = edit_text(editor.clone(), "toml", &content)?;
+        if plan::is_empty(&edited) {
+            return Err(Error::EditorAborted("Empty plan".to_string()));
+        }
+        let message = match manifest::parse(&edited, true) {
+            Ok(entries) => return Ok(entries),
+            Err(message) => message,
+        };
+
+        if edited == content {
+            let kept = std::env::temp_dir().join(format!("wta-plan-{}.toml", std::process::id()));
+            std::fs::write(&kept, &edited)?;
+            return Err(Error::InvalidPlan(format!(
+                "{message}\nThe plan is kept in {}; fix it and run 'wta launch --from {}'",
+                kept.display(),
+                kept.display()
+            )));
+        }
+        eprintln!("Invalid plan: {message}");
+        let plan: Vec<&str> = edited
+            .lines()
+            .skip_while(|l| l.starts_with(INVALID_PLAN_NOTE.trim_end()) || l.starts_with("#   "))
+            .collect();
+        let note: String = message
+            .lines()
+            .enumerate()
+            .map(|(i, line)| match i {
+                0 => format!("{INVALID_PLAN_NOTE}{line}\n"),
+                _ => format!("#   {line}\n"),
+            })
+            .collect();
+        content = format!("{note}{}\n", plan.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_edit_plan_reopens_invalid_plan_with_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let seen = temp_dir.path().join("seen.toml");
+        // Breaks the plan on the first edit, and fixes it on the second,
+        // keeping what the editor was reopened with
+        let script = temp_dir.path().join("editor.sh");
+        std::fs::write(
+            &script,
+            format!(
+                "if [ -e {seen} ]; then cp \"$1\" {seen}; sed -i 's/^tsk/task/' \"$1\"; \
+                 else touch {seen}; sed -i 's/^task/tsk/' \"$1\"; fi\n",
+                seen = seen.display()
+            ),
+        )
+        .unwrap();
+
+        let entries = edit_plan(
+            Some(format!("sh {}", script.display())),
+            "[[tasks]]\ntask = \"Write the API\"\n".to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        let reopened = std::fs::read_to_string(&seen).unwrap();
+        assert!(reopened.starts_with(INVALID_PLAN_NOTE));
+        assert!(reopened.contains("tsk = \"Write the API\""));
+    }
+
+    #[test]
+    fn test_edit_plan_keeps_plan_saved_unchanged() {
+        let err = edit_plan(Some("true".to_string()), "[[tasks]]\ntsk = 1\n".to_string())
+            .unwrap_err()
+            .to_string();
+
+        let kept = err
+            .split("kept in ")
+            .nth(1)
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(kept).unwrap(),
+            "[[tasks]]\ntsk = 1\n"
+        );
+        std::fs::remove_file(kept).unwrap();
+    }
+}
@@ -38,7 +38,7 @@ pub async fn run(all: bool, status: Option<AgentStatus>, delete_branch: bool) ->
 
     let mut rows = Vec::with_capacity(pruned.len());
     for agent in &pruned {
-        let task = truncate_task(&agent.task, TASK_MAX_LEN);
+        let task = truncate_task(agent.task(), TASK_MAX_LEN);
 
         rows.push(PrunedAgentRow {
             id: agent.id.0.clone(),
@@ -31,6 +31,13 @@ pub async fn run() -> Result<()> {
     print_command("wta launch --task \"Add auth\" --branch feature/auth");
     println!("            Launch with custom branch name\n");
 
+    print_command("wta launch --task \"Add auth\" --timeout 45m");
+    println!("            Stop the agent if it runs longer, keeping its worktree\n");
+
+    print_command("wta launch --task \"Migrate billing\" --approve-plan");
+    println!("            Agent proposes a plan and waits for wta approve <id> [--edit]");
+    println!("            or wta reject <id>\n");
+
     print_command("wta launch --editor");
     println!("            Opens your editor to compose multi-line task descriptions");
     println!(
@@ -40,9 +47,17 @@ pub async fn run() -> Result<()> {
         "$EDITOR".green()
     );
 
+    print_command("wta plan \"Add OAuth login\"");
+    println!("            Splits a large task into parallel subtasks, opens the plan in");
+    println!("            your editor, then launches what you keep as a batch\n");
+
     // MONITORING AGENTS
     println!("{}", "MONITORING AGENTS".yellow().bold());
     print_command_desc("wta list", "List all agents with status and task");
+    print_command_desc(
+        "wta list --watch",
+        "Redraw the list as agent statuses change",
+    );
     print_command_desc("wta status <id>", "Show detailed status and recent output");
     print_command_desc(
         "wta status <id> -l 100",
@@ -56,6 +71,32 @@ pub async fn run() -> Result<()> {
         "wta attach <id> --code",
         "Attach and open VS Code in worktree",
     );
+    print_command_desc(
+        "wta send <id> \"Also add tests\"",
+        "Send follow-up instructions (--file for multi-line)",
+    );
+    print_command_desc(
+        "wta daemon start",
+        "Track status, start queued agents, notify in background",
+    );
+    print_command_desc("wta daemon stop", "Stop the background daemon");
+
+    print_command_desc(
+        "wta continue <id> --task \"...\"",
+        "Give a finished agent a follow-up task on its branch",
+    );
+    print_command_desc(
+        "wta fork <id> --task \"...\"",
+        "New agent from another agent's branch (merge back with --into-parent)",
+    );
+    print_command_desc(
+        "wta answer <id> \"...\"",
+        "Reply to a question the agent asked with wta ask",
+    );
+    print_command_desc(
+        "wta retry <id>",
+        "Run a failed or timed-out agent again (--fresh to start over)",
+    );
     println!();
 
     // AI PROVIDERS
@@ -73,6 +114,10 @@ pub async fn run() -> Result<()> {
         "wta launch --task \"...\" --provider deepagents",
         "DeepAgents",
     );
+    print_command_desc(
+        "wta launch --task \"...\" --providers claude,codex",
+        "Same task once per provider, as an attempt group",
+    );
     println!();
     println!("  Pass extra args to provider:");
     print_command("wta launch --task \"...\" -- --model opus");
@@ -81,6 +126,14 @@ pub async fn run() -> Result<()> {
 
     // REVIEWING WORK
     println!("{}", "REVIEWING WORK".yellow().bold());
+    print_command_desc(
+        "wta compare <group> --check",
+        "Attempts side by side (--keep <id> to drop the rest)",
+    );
+    print_command_desc(
+        "wta review <id>",
+        "Read-only reviewer agent; findings show in wta status <id>",
+    );
     print_command_desc(
         "wta diff <id>",
         "View changes between agent's branch and base",
@@ -108,6 +161,22 @@ pub async fn run() -> Result<()> {
         "wta merge <id> --force",
         "Force merge even if agent still running",
     );
+    print_command_desc(
+        "wta merge --queue 3 5 7",
+        "Rebase, check and merge each in turn",
+    );
+    print_command_desc(
+        "wta overlaps",
+        "Show running agents whose changes touch the same files",
+    );
+    print_command_desc(
+        "wta integrate 3 4 6 --branch integration/x",
+        "Combine agents on a new branch to test them together",
+    );
+    print_command_desc(
+        "wta merge <id> --dry-run",
+        "Show whether it merges cleanly, with any conflicts",
+    );
     println!();
 
     // CREATING PULL REQUESTS
@@ -187,17 +256,30 @@ pub async fn run() -> Result<()> {
     // AGENT LIFECYCLE
     println!("{}", "AGENT LIFECYCLE".yellow().bold());
     println!(
-        "  {} → {} → {} → {}",
+        "  {} → {} → {} → {} → {}",
         "Launched".white(),
+        "Queued".dimmed(),
         "Running".bright_blue(),
         "Completed/Failed".magenta(),
         "Merged/Removed".green()
     );
     println!();
+    println!(
+        "  {}     Waiting for dependencies or a free max_running slot",
+        "Queued".dimmed()
+    );
     println!(
         "  {}    Agent actively working in tmux window",
         "Running".bright_blue()
     );
+    println!(
+        "  {}    No output, commits or progress for 10 minutes",
+        "Stalled".yellow()
+    );
+    println!(
+        "  {} Waiting at a prompt (answer with wta attach)",
+        "Needs-input".bright_yellow()
+    );
     println!("  {}  Agent finished successfully", "Completed".magenta());
     println!("  {}     Agent encountered an error", "Failed".red());
     println!("  {}     Work merged back to base branch", "Merged".green());
@@ -218,6 +300,10 @@ pub async fn run() -> Result<()> {
         "  ├── {}                  Completion status files",
         "status/".white()
     );
+    println!(
+        "  ├── {}                Agent progress logs",
+        "progress/".white()
+    );
     println!(
         "  └── {}                 Task instructions",
         "prompts/".white()
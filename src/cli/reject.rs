@@ -0,0 +1,20 @@
+use crate::orchestrator::Orchestrator;
+use crate::Result;
+
+/// End an agent whose proposed plan was not approved
+pub async fn run(id: String, reason: Option<String>) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
+
+    orchestrator.reject(&id, reason)?;
+
+    println!("Rejected the plan of agent {id}; the agent was stopped");
+    println!("Use 'wta retry {id}' to have it propose another plan");
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use crate::cli::send::read_message;
+use crate::orchestrator::{
+    Finding, Orchestrator, ProgressEvent, ReportStatus, Severity, StatusReport, SCHEMA_VERSION,
+};
+use crate::Result;
+use clap::Subcommand;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    /// Record progress on the task; also serves as a heartbeat
+    Progress {
+        /// What the agent is doing now
+        message: String,
+
+        /// Estimated completion, 0-100
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        percent: Option<u8>,
+    },
+
+    /// Record a problem found while reviewing another agent's work
+    Finding {
+        /// What is wrong and why it matters
+        message: String,
+
+        /// How much the problem matters
+        #[arg(long, value_enum, default_value = "medium")]
+        severity: Severity,
+
+        /// File the problem is in
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Line in the file
+        #[arg(long, requires = "file")]
+        line: Option<u32>,
+    },
+
+    /// Propose a plan and wait for approval (agents launched with --approve-plan)
+    Plan {
+        /// The plan
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        plan: Option<String>,
+
+        /// Read the plan from a file (- for stdin)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+}
+
+/// Record the current agent's result, identifying it from the working directory.
+/// Findings recorded with `wta report finding` are included in the report.
+pub async fn run(
+    status: ReportStatus,
+    summary: Option<String>,
+    files: Vec<String>,
+    error: Option<String>,
+) -> Result<()> {
+    let orchestrator = Orchestrator::new()?;
+    let agent = orchestrator.agent_for_path(&std::env::current_dir()?)?;
+
+    let report = StatusReport {
+        schema_version: SCHEMA_VERSION,
+        status,
+        summary,
+        files_changed: files,
+        error,
+        plan: None,
+        findings: orchestrator.findings(&agent.id.0)?,
+    };
+    orchestrator.write_report(&agent.id.0, &report)?;
+
+    println!("Reported agent {} as {status}", agent.id);
+
+    Ok(())
+}
+
+/// Append a progress update for the current agent
+pub async fn run_progress(message: String, percent: Option<u8>) -> Result<()> {
+    let orchestrator = Orchestrator::new()?;
+    let agent = orchestrator.agent_for_path(&std::env::current_dir()?)?;
+
+    let event = ProgressEvent {
+        at: chrono::Utc::now(),
+        message,
+        percent,
+    };
+    orchestrator.append_progress(&agent.id.0, &event)?;
+
+    Ok(())
+}
+
+/// Append a review finding for the current agent
+pub async fn run_finding(
+    message: String,
+    severity: Severity,
+    file: Option<String>,
+    line: Option<u32>,
+) -> Result<()> {
+    let orchestrator = Orchestrator::new()?;
+    let agent = orchestrator.agent_for_path(&std::env::current_dir()?)?;
+
+    let finding = Finding {
+        severity,
+        message,
+        file,
+        line,
+    };
+    orchestrator.append_finding(&agent.id.0, &finding)?;
+
+    Ok(())
+}
+
+/// Report the current agent's proposed plan, pausing it until it is approved
+pub async fn run_plan(plan: Option<String>, file: Option<PathBuf>) -> Result<()> {
+    let plan = match (plan, file) {
+        (Some(plan), _) => plan,
+        (None, Some(file)) => read_message(&file)?,
+        // clap requires one of the two
+        (None, None) => unreachable!("a plan or --file is required"),
+    };
+
+    let orchestrator = Orchestrator::new()?;
+    let agent = orchestrator.agent_for_path(&std::env::current_dir()?)?;
+
+    let report = StatusReport {
+        schema_version: SCHEMA_VERSION,
+        status: ReportStatus::Plan,
+        summary: None,
+        files_changed: Vec::new(),
+        error: None,
+        plan: Some(plan.trim().to_string()),
+        findings: Vec::new(),
+    };
+    orchestrator.write_report(&agent.id.0, &report)?;
+
+    println!(
+        "Reported the plan of agent {}; stop here and wait for approval",
+        agent.id
+    );
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::cli::queue_reason;
+use crate::orchestrator::{AgentStatus, Orchestrator, RetryRequest};
+use crate::provider::Provider;
+use crate::Result;
+
+/// Run a failed or timed-out agent again, keeping its ID and history
+pub async fn run(
+    id: String,
+    task_append: Option<String>,
+    provider: Option<Provider>,
+    fresh: bool,
+) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
+
+    orchestrator.retry(
+        &id,
+        RetryRequest {
+            task_append,
+            provider,
+            fresh,
+        },
+    )?;
+
+    let agent = orchestrator.get_agent(&id)?;
+    let attempt = agent.attempts;
+    let branch = agent.branch.clone();
+    let provider = agent.provider;
+    let from = if fresh {
+        format!("reset to {}", agent.base_branch)
+    } else {
+        "continuing from the previous attempt".to_string()
+    };
+
+    match agent.status {
+        AgentStatus::Queued => {
+            println!("Queued attempt {attempt} of agent {id} on branch {branch} ({from})");
+            println!("{}", queue_reason(&orchestrator, &id)?);
+        }
+        AgentStatus::Running => {
+            println!("Started attempt {attempt} of agent {id} on branch {branch} ({from})");
+            println!("Provider: {provider}");
+            println!();
+            println!("Use 'wta attach {id}' to watch the agent");
+        }
+        status => println!("Attempt {attempt} of agent {id} could not be started: {status}"),
+    }
+
+    Ok(())
+}
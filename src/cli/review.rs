@@ -0,0 +1,30 @@
+use crate::cli::queue_reason;
+use crate::orchestrator::{AgentStatus, Orchestrator};
+use crate::provider::Provider;
+use crate::Result;
+
+/// Launch a read-only agent that reviews another agent's changes
+pub async fn run(id: String, provider: Option<Provider>) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
+
+    let reviewer = orchestrator.review(&id, provider).await?;
+    let agent = orchestrator.get_agent(&reviewer.0)?;
+
+    if agent.status == AgentStatus::Queued {
+        println!("Queued reviewer agent {reviewer} for agent {id}");
+        println!("{}", queue_reason(&orchestrator, &reviewer.0)?);
+    } else {
+        println!("Launched reviewer agent {reviewer} for agent {id}");
+    }
+    println!("Provider: {}", agent.provider);
+    println!();
+    println!("Its findings will be shown by 'wta status {id}'");
+
+    Ok(())
+}
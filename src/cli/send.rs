@@ -0,0 +1,58 @@
+use crate::orchestrator::Orchestrator;
+use crate::Result;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Send follow-up instructions to a running agent
+pub async fn run(id: String, message: Option<String>, file: Option<PathBuf>) -> Result<()> {
+    let text = match (message, file) {
+        (Some(message), _) => message,
+        (None, Some(file)) => read_message(&file)?,
+        // clap requires one of the two
+        (None, None) => unreachable!("a message or --file is required"),
+    };
+
+    let mut orchestrator = Orchestrator::new()?;
+
+    // Make sure the agent has not finished since it was last checked
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
+    orchestrator.send_message(&id, &text)?;
+
+    let lines = text.trim().lines().count();
+    println!("Sent {lines} line(s) to agent {id}");
+
+    Ok(())
+}
+
+/// Read a message from a file, or from stdin for `-`
+pub(crate) fn read_message(path: &Path) -> Result<String> {
+    if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        return Ok(text);
+    }
+    Ok(std::fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_message_from_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("followup.md");
+        std::fs::write(&path, "Also update the docs\n\n- README\n").unwrap();
+
+        assert_eq!(
+            read_message(&path).unwrap(),
+            "Also update the docs\n\n- README\n"
+        );
+        assert!(read_message(&dir.path().join("missing.md")).is_err());
+    }
+}
@@ -1,18 +1,206 @@
-use crate::orchestrator::Orchestrator;
+use crate::cli::{format_age, progress_line, queue_reason};
+use crate::duration::format_duration;
+use crate::orchestrator::{AgentStatus, Orchestrator};
 use crate::Result;
+use std::time::Duration;
 
 pub async fn run(id: String, lines: usize) -> Result<()> {
     let mut orchestrator = Orchestrator::new()?;
 
-    // Check and update status from status file
-    let status = orchestrator.check_status(&id)?;
+    // Check and update status from status file, unless the daemon already does
+    if orchestrator.daemon_pid().is_some() {
+        orchestrator.reload_state()?;
+    } else {
+        orchestrator.check_status(&id)?;
+    }
     let agent = orchestrator.get_agent(&id)?;
+    let status = agent.status;
 
     println!("Agent: {}", agent.id);
     println!("Branch: {}", agent.branch);
     println!("Status: {status}");
-    println!("Task: {}", agent.task);
+    println!("Task: {}", agent.task());
+    for (i, task) in agent.tasks.iter().enumerate().skip(1) {
+        println!("Follow-up {i}: {task}");
+    }
+    if !agent.depends_on.is_empty() {
+        let depends_on: Vec<&str> = agent.depends_on.iter().map(|d| d.0.as_str()).collect();
+        println!("Depends on: {}", depends_on.join(", "));
+    }
+    if let Some(parent) = &agent.parent {
+        println!("Forked from: agent {parent} ({})", agent.base_branch);
+    }
+    if let Some(target) = &agent.review_of {
+        println!("Reviewing: agent {target} (read-only)");
+    }
+    if agent.approve_plan {
+        println!("Plan approval: required before editing");
+    }
+    if let Some(group) = &agent.group {
+        println!("Attempt group: {group} (wta compare {group})");
+    }
+    if agent.attempts > 1 {
+        println!("Attempt: {}", agent.attempts);
+    }
+    if let Some(timeout) = agent.timeout_secs {
+        println!("Timeout: {}", format_duration(Duration::from_secs(timeout)));
+    }
+    if let Some(progress) = &agent.progress {
+        println!("Progress: {}", progress_line(progress, chrono::Utc::now()));
+    }
+    if let Some(error) = &agent.report_error {
+        println!("Report error: {error}");
+    }
+    if let Some(report) = &agent.report {
+        if let Some(summary) = &report.summary {
+            println!("Summary: {summary}");
+        }
+        if let Some(error) = &report.error {
+            println!("Error: {error}");
+        }
+        if !report.files_changed.is_empty() {
+            println!("Files changed:");
+            for file in &report.files_changed {
+                println!("  {file}");
+            }
+        }
+        if let Some(plan) = &report.plan {
+            if status == AgentStatus::TimedOut && agent.awaits_approval() {
+                println!("Timed out while awaiting approval; approving restarts the timeout");
+                println!("Proposed plan ('wta approve {id} [--edit]' or 'wta reject {id}'):");
+            } else if agent.awaits_approval() {
+                println!("Proposed plan ('wta approve {id} [--edit]' or 'wta reject {id}'):");
+            } else {
+                println!("Proposed plan:");
+            }
+            for line in plan.lines() {
+                println!("  {line}");
+            }
+        }
+    }
+    if let Some(check) = &agent.check {
+        let result = if check.passed { "passed" } else { "failed" };
+        println!(
+            "Check: {result} ({}, {} ago)",
+            check.command,
+            format_age(check.at, chrono::Utc::now())
+        );
+        if !check.passed {
+            for line in check.output.lines() {
+                println!("  {line}");
+            }
+        }
+    }
+    for integration in orchestrator.integrations() {
+        let Some(entry) = integration.agents.iter().find(|a| a.id == agent.id) else {
+            continue;
+        };
+        if entry.is_merged() {
+            println!("Integrated into: {}", integration.branch);
+        } else {
+            let files: Vec<String> = entry
+                .conflicts
+                .iter()
+                .map(|f| f.display().to_string())
+                .collect();
+            println!(
+                "Left out of {}: conflicts in {}",
+                integration.branch,
+                files.join(", ")
+            );
+        }
+    }
+    for review in &agent.reviews {
+        let detail = review
+            .error
+            .as_ref()
+            .or(review.summary.as_ref())
+            .map(|d| format!(": {d}"))
+            .unwrap_or_default();
+        println!(
+            "Review by agent {} ({}, {}, {} finding(s)){detail}",
+            review.reviewer,
+            review.provider,
+            review.status,
+            review.findings.len()
+        );
+        for finding in &review.findings {
+            let location = finding
+                .location()
+                .map(|l| format!("{l}: "))
+                .unwrap_or_default();
+            println!("  [{}] {location}{}", finding.severity, finding.message);
+        }
+    }
+    if !agent.previous_attempts.is_empty() {
+        println!("Previous attempts:");
+        for attempt in &agent.previous_attempts {
+            let detail = attempt
+                .report
+                .as_ref()
+                .and_then(|r| r.error.as_ref().or(r.summary.as_ref()))
+                .or(attempt.report_error.as_ref())
+                .map(|d| format!(": {d}"))
+                .unwrap_or_default();
+            println!(
+                "  {}. {} ({}){detail}",
+                attempt.number, attempt.status, attempt.provider
+            );
+        }
+    }
+    if !agent.questions.is_empty() {
+        println!("Questions:");
+        for question in &agent.questions {
+            println!("  {}. {}", question.number, question.question);
+            match &question.answer {
+                Some(answer) => println!("     Answer: {answer}"),
+                None => println!(
+                    "     Waiting for an answer: wta answer {} \"...\"",
+                    agent.id
+                ),
+            }
+        }
+    }
+    if !agent.messages.is_empty() {
+        let now = chrono::Utc::now();
+        println!("Messages sent:");
+        for message in &agent.messages {
+            let mut lines = message.text.lines();
+            let first = lines.next().unwrap_or_default();
+            let more = if lines.next().is_some() { " ..." } else { "" };
+            println!("  {} ago: {first}{more}", format_age(message.at, now));
+        }
+    }
+    if status == AgentStatus::TimedOut {
+        println!(
+            "Stopped after exceeding its timeout; the worktree is kept at {}",
+            agent.worktree_path.display()
+        );
+        println!(
+            "Final output saved to {}",
+            orchestrator.output_file(&id).display()
+        );
+    }
+    if let Some(output) = &agent.stall_output {
+        println!();
+        match status {
+            AgentStatus::NeedsInput => println!(
+                "Waiting at a prompt; answer it with `wta attach {}`:",
+                agent.id
+            ),
+            _ => println!("No output, commits or progress for a while. Last output:"),
+        }
+        for line in output.lines() {
+            println!("  {line}");
+        }
+    }
     println!();
+
+    // A queued agent has no tmux window yet
+    if status == AgentStatus::Queued {
+        println!("{}", queue_reason(&orchestrator, &id)?);
+        return Ok(());
+    }
     println!("--- Recent output (last {lines} lines) ---");
     println!();
 
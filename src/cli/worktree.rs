@@ -1,8 +1,9 @@
+use crate::config::Config;
 use crate::git::WorktreeManager;
 use crate::Result;
 use clap::Subcommand;
 use std::io::IsTerminal;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tabled::{Table, Tabled};
 
 const WTA_DIRECTIVE_FILE_ENV: &str = "WTA_DIRECTIVE_FILE";
@@ -64,6 +65,16 @@ fn get_repo_root() -> Result<PathBuf> {
     Ok(PathBuf::from(path))
 }
 
+/// Worktree manager honoring the configured `worktree_dir`
+fn worktree_manager(repo_root: &Path) -> Result<WorktreeManager> {
+    let config = Config::load(repo_root)?;
+    let manager = WorktreeManager::new(repo_root);
+    Ok(match config.worktree_parent_dir(repo_root) {
+        Some(dir) => manager.with_parent_dir(dir),
+        None => manager,
+    })
+}
+
 fn get_current_branch() -> Result<String> {
     let output = std::process::Command::new("git")
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
@@ -88,7 +99,7 @@ pub async fn run(command: WorktreeCommands) -> Result<()> {
 
 async fn run_list() -> Result<()> {
     let repo_root = get_repo_root()?;
-    let manager = WorktreeManager::new(&repo_root);
+    let manager = worktree_manager(&repo_root)?;
 
     let worktrees = manager.list()?;
 
@@ -113,7 +124,7 @@ async fn run_list() -> Result<()> {
 
 async fn run_add(branch: String, base: Option<String>, id: Option<String>) -> Result<()> {
     let repo_root = get_repo_root()?;
-    let manager = WorktreeManager::new(&repo_root);
+    let manager = worktree_manager(&repo_root)?;
 
     // Determine base branch
     let base_branch = match base {
@@ -137,10 +148,10 @@ async fn run_add(branch: String, base: Option<String>, id: Option<String>) -> Re
 
 async fn run_remove(name: String) -> Result<()> {
     let repo_root = get_repo_root()?;
-    let manager = WorktreeManager::new(&repo_root);
+    let manager = worktree_manager(&repo_root)?;
 
     // The name should be the worktree ID (the suffix after -wta-)
-    manager.remove(&name)?;
+    manager.remove(&manager.worktree_path(&name))?;
 
     println!("Removed worktree: {name}");
 
@@ -171,15 +182,12 @@ async fn run_prune() -> Result<()> {
 
 async fn run_switch(name: String) -> Result<()> {
     let repo_root = get_repo_root()?;
-    let manager = WorktreeManager::new(&repo_root);
+    let manager = worktree_manager(&repo_root)?;
     let repo_name = repo_root
         .file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("repo");
-    let parent_dir = repo_root
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| repo_root.clone());
+    let parent_dir = manager.parent_dir().to_path_buf();
 
     let worktrees = manager.list()?;
 
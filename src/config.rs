@@ -0,0 +1,412 @@
+use crate::error::{Error, Result};
+use crate::orchestrator::MergeStrategy;
+use crate::provider::Provider;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Project-level config file, committed at the repository root
+pub const PROJECT_CONFIG_FILE: &str = ".wta.toml";
+const USER_CONFIG_DIR: &str = "wta";
+const USER_CONFIG_FILE: &str = "config.toml";
+pub const DEFAULT_DASHBOARD_PORT: u16 = 3847;
+const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Where a resolved setting came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    User(PathBuf),
+    Project(PathBuf),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::User(path) => write!(f, "user ({})", path.display()),
+            ConfigSource::Project(path) => write!(f, "project ({})", path.display()),
+        }
+    }
+}
+
+/// A resolved value together with the layer that provided it
+#[derive(Debug, Clone)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: ConfigSource,
+}
+
+impl<T> Setting<T> {
+    fn default(value: T) -> Self {
+        Self {
+            value,
+            source: ConfigSource::Default,
+        }
+    }
+
+    fn set(&mut self, value: Option<T>, source: &ConfigSource) {
+        if let Some(value) = value {
+            self.value = value;
+            self.source = source.clone();
+        }
+    }
+}
+
+/// Raw contents of a single config file. Every key is optional so layers can be merged.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    provider: Option<Provider>,
+    base: Option<String>,
+    merge_strategy: Option<MergeStrategy>,
+    update_checkout: Option<bool>,
+    worktree_dir: Option<PathBuf>,
+    dashboard_port: Option<u16>,
+    dangerously_allow_all: Option<bool>,
+    enable_edits: Option<bool>,
+    max_running: Option<usize>,
+    notify_command: Option<String>,
+    check_command: Option<String>,
+    #[serde(default, deserialize_with = "crate::duration::deserialize_optional")]
+    check_timeout: Option<Duration>,
+    #[serde(default, deserialize_with = "crate::duration::deserialize_optional")]
+    timeout: Option<Duration>,
+    /// Extra provider arguments keyed by provider name, e.g. `claude = ["--model", "opus"]`
+    #[serde(default)]
+    provider_args: BTreeMap<String, Vec<String>>,
+}
+
+impl ConfigFile {
+    fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content, path).map(Some)
+    }
+
+    fn parse(content: &str, path: &Path) -> Result<Self> {
+        let file: Self = toml::from_str(content).map_err(|e| Error::ConfigInvalid {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })?;
+
+        if file.max_running == Some(0) {
+            return Err(Error::ConfigInvalid {
+                path: path.to_path_buf(),
+                message: "max_running must be at least 1".to_string(),
+            });
+        }
+
+        Ok(file)
+    }
+}
+
+/// Launch, merge and dashboard defaults resolved from built-in defaults,
+/// the user config (`~/.config/wta/config.toml`) and the project `.wta.toml`,
+/// in increasing order of precedence. CLI flags override all of these.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub provider: Setting<Provider>,
+    pub base: Setting<Option<String>>,
+    pub merge_strategy: Setting<MergeStrategy>,
+    /// Fast-forward a merge target that is checked out, changing that checkout
+    pub update_checkout: Setting<bool>,
+    pub worktree_dir: Setting<Option<PathBuf>>,
+    pub dashboard_port: Setting<u16>,
+    pub dangerously_allow_all: Setting<bool>,
+    pub enable_edits: Setting<bool>,
+    /// Maximum number of agents running at once; further launches are queued
+    pub max_running: Setting<Option<usize>>,
+    /// Shell command the daemon runs on every agent status change
+    pub notify_command: Setting<Option<String>>,
+    /// Shell command that checks an agent's work in its worktree, e.g. `cargo test`
+    pub check_command: Setting<Option<String>>,
+    /// How long `check_command` may run before it is killed and counted as failed
+    pub check_timeout: Setting<Duration>,
+    /// Wall-clock limit after which an agent is stopped
+    pub timeout: Setting<Option<Duration>>,
+    pub provider_args: BTreeMap<String, Setting<Vec<String>>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            provider: Setting::default(Provider::default()),
+            base: Setting::default(None),
+            merge_strategy: Setting::default(MergeStrategy::Merge),
+            update_checkout: Setting::default(false),
+            worktree_dir: Setting::default(None),
+            dashboard_port: Setting::default(DEFAULT_DASHBOARD_PORT),
+            dangerously_allow_all: Setting::default(false),
+            enable_edits: Setting::default(false),
+            max_running: Setting::default(None),
+            notify_command: Setting::default(None),
+            check_command: Setting::default(None),
+            check_timeout: Setting::default(DEFAULT_CHECK_TIMEOUT),
+            timeout: Setting::default(None),
+            provider_args: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the layered config for the repository at `repo_root`
+    pub fn load(repo_root: &Path) -> Result<Self> {
+        let mut layers = Vec::new();
+
+        if let Some(path) = Self::user_config_path() {
+            if let Some(file) = ConfigFile::load(&path)? {
+                layers.push((ConfigSource::User(path), file));
+            }
+        }
+
+        let project_path = repo_root.join(PROJECT_CONFIG_FILE);
+        if let Some(file) = ConfigFile::load(&project_path)? {
+            layers.push((ConfigSource::Project(project_path), file));
+        }
+
+        Ok(Self::from_layers(layers))
+    }
+
+    /// Path of the user-level config, honoring `XDG_CONFIG_HOME`
+    pub fn user_config_path() -> Option<PathBuf> {
+        let config_home = match std::env::var("XDG_CONFIG_HOME") {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var("HOME").ok()?).join(".config"),
+        };
+        Some(config_home.join(USER_CONFIG_DIR).join(USER_CONFIG_FILE))
+    }
+
+    /// Apply layers in order, later layers overriding earlier ones
+    fn from_layers(layers: Vec<(ConfigSource, ConfigFile)>) -> Self {
+        let mut config = Self::default();
+
+        for (source, file) in layers {
+            config.provider.set(file.provider, &source);
+            config.base.set(file.base.map(Some), &source);
+            config.merge_strategy.set(file.merge_strategy, &source);
+            config.update_checkout.set(file.update_checkout, &source);
+            config
+                .worktree_dir
+                .set(file.worktree_dir.map(Some), &source);
+            config.dashboard_port.set(file.dashboard_port, &source);
+            config
+                .dangerously_allow_all
+                .set(file.dangerously_allow_all, &source);
+            config.enable_edits.set(file.enable_edits, &source);
+            config.max_running.set(file.max_running.map(Some), &source);
+            config
+                .notify_command
+                .set(file.notify_command.map(Some), &source);
+            config
+                .check_command
+                .set(file.check_command.map(Some), &source);
+            config.check_timeout.set(file.check_timeout, &source);
+            config.timeout.set(file.timeout.map(Some), &source);
+
+            for (provider, args) in file.provider_args {
+                config.provider_args.insert(
+                    provider,
+                    Setting {
+                        value: args,
+                        source: source.clone(),
+                    },
+                );
+            }
+        }
+
+        config
+    }
+
+    /// Configured extra arguments for a provider (empty if none)
+    pub fn provider_args_for(&self, provider: Provider) -> Vec<String> {
+        self.provider_args
+            .get(&provider.to_string())
+            .map(|s| s.value.clone())
+            .unwrap_or_default()
+    }
+
+    /// Directory in which agent worktrees are created, if overridden.
+    /// Relative paths are resolved against the repository root.
+    pub fn worktree_parent_dir(&self, repo_root: &Path) -> Option<PathBuf> {
+        self.worktree_dir
+            .value
+            .as_ref()
+            .map(|dir| repo_root.join(dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> ConfigFile {
+        ConfigFile::parse(content, Path::new(PROJECT_CONFIG_FILE)).unwrap()
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config = Config::from_layers(Vec::new());
+        assert_eq!(config.provider.value, Provider::Claude);
+        assert_eq!(config.provider.source, ConfigSource::Default);
+        assert_eq!(config.merge_strategy.value, MergeStrategy::Merge);
+        assert_eq!(config.dashboard_port.value, DEFAULT_DASHBOARD_PORT);
+        assert!(config.base.value.is_none());
+        assert!(config.worktree_dir.value.is_none());
+        assert!(!config.enable_edits.value);
+        assert!(config.max_running.value.is_none());
+        assert!(config.timeout.value.is_none());
+        assert_eq!(config.check_timeout.value, DEFAULT_CHECK_TIMEOUT);
+    }
+
+    #[test]
+    fn test_config_parse_all_keys() {
+        let file = parse(
+            r#"
+provider = "codex"
+base = "develop"
+merge_strategy = "squash"
+worktree_dir = "../agents"
+dashboard_port = 4000
+dangerously_allow_all = true
+enable_edits = true
+max_running = 4
+notify_command = "notify-send wta \"$WTA_AGENT_ID $WTA_STATUS\""
+check_command = "cargo test"
+check_timeout = "10m"
+timeout = "1h30m"
+
+[provider_args]
+codex = ["--model", "o3"]
+"#,
+        );
+        let config = Config::from_layers(vec![(ConfigSource::Default, file)]);
+        assert_eq!(config.provider.value, Provider::Codex);
+        assert_eq!(config.base.value.as_deref(), Some("develop"));
+        assert_eq!(config.merge_strategy.value, MergeStrategy::Squash);
+        assert_eq!(config.worktree_dir.value, Some(PathBuf::from("../agents")));
+        assert_eq!(config.dashboard_port.value, 4000);
+        assert!(config.dangerously_allow_all.value);
+        assert!(config.enable_edits.value);
+        assert_eq!(config.max_running.value, Some(4));
+        assert_eq!(
+            config.notify_command.value.as_deref(),
+            Some("notify-send wta \"$WTA_AGENT_ID $WTA_STATUS\"")
+        );
+        assert_eq!(config.check_command.value.as_deref(), Some("cargo test"));
+        assert_eq!(config.check_timeout.value, Duration::from_secs(10 * 60));
+        assert_eq!(config.timeout.value, Some(Duration::from_secs(90 * 60)));
+        assert_eq!(
+            config.provider_args_for(Provider::Codex),
+            vec!["--model", "o3"]
+        );
+        assert!(config.provider_args_for(Provider::Claude).is_empty());
+    }
+
+    #[test]
+    fn test_config_project_overrides_user() {
+        let user_source = ConfigSource::User(PathBuf::from("/home/u/.config/wta/config.toml"));
+        let project_source = ConfigSource::Project(PathBuf::from("/repo/.wta.toml"));
+        let user = parse("provider = \"gemini\"\nbase = \"develop\"\ndashboard_port = 5000");
+        let project = parse("provider = \"codex\"");
+
+        let config = Config::from_layers(vec![
+            (user_source.clone(), user),
+            (project_source.clone(), project),
+        ]);
+
+        assert_eq!(config.provider.value, Provider::Codex);
+        assert_eq!(config.provider.source, project_source);
+        assert_eq!(config.base.value.as_deref(), Some("develop"));
+        assert_eq!(config.base.source, user_source);
+        assert_eq!(config.dashboard_port.value, 5000);
+        assert_eq!(config.merge_strategy.source, ConfigSource::Default);
+    }
+
+    #[test]
+    fn test_config_provider_args_merge_per_provider() {
+        let user = parse("[provider_args]\nclaude = [\"--verbose\"]\ncodex = [\"-q\"]");
+        let project = parse("[provider_args]\nclaude = [\"--model\", \"opus\"]");
+
+        let config = Config::from_layers(vec![
+            (ConfigSource::User(PathBuf::from("user.toml")), user),
+            (ConfigSource::Project(PathBuf::from(".wta.toml")), project),
+        ]);
+
+        assert_eq!(
+            config.provider_args_for(Provider::Claude),
+            vec!["--model", "opus"]
+        );
+        assert_eq!(config.provider_args_for(Provider::Codex), vec!["-q"]);
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_keys() {
+        let result = ConfigFile::parse("provder = \"claude\"", Path::new(".wta.toml"));
+        match result {
+            Err(Error::ConfigInvalid { path, message }) => {
+                assert_eq!(path, PathBuf::from(".wta.toml"));
+                assert!(message.contains("provder"));
+            }
+            other => panic!("Expected ConfigInvalid, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_invalid_provider() {
+        let result = ConfigFile::parse("provider = \"gpt\"", Path::new(".wta.toml"));
+        assert!(matches!(result, Err(Error::ConfigInvalid { .. })));
+    }
+
+    #[test]
+    fn test_config_rejects_zero_max_running() {
+        match ConfigFile::parse("max_running = 0", Path::new(".wta.toml")) {
+            Err(Error::ConfigInvalid { message, .. }) => {
+                assert_eq!(message, "max_running must be at least 1")
+            }
+            other => panic!("Expected ConfigInvalid, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_invalid_timeout() {
+        match ConfigFile::parse("timeout = \"soon\"", Path::new(".wta.toml")) {
+            Err(Error::ConfigInvalid { message, .. }) => {
+                assert!(message.contains("invalid duration 'soon'"))
+            }
+            other => panic!("Expected ConfigInvalid, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_load_missing_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = ConfigFile::load(&temp_dir.path().join(PROJECT_CONFIG_FILE)).unwrap();
+        assert!(file.is_none());
+    }
+
+    #[test]
+    fn test_worktree_parent_dir_resolves_relative_to_repo() {
+        let file = parse("worktree_dir = \"../agents\"");
+        let config = Config::from_layers(vec![(ConfigSource::Default, file)]);
+        assert_eq!(
+            config.worktree_parent_dir(Path::new("/home/user/project")),
+            Some(PathBuf::from("/home/user/project/../agents"))
+        );
+        assert!(Config::default()
+            .worktree_parent_dir(Path::new("/repo"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_config_source_display() {
+        assert_eq!(ConfigSource::Default.to_string(), "default");
+        assert_eq!(
+            ConfigSource::Project(PathBuf::from("/repo/.wta.toml")).to_string(),
+            "project (/repo/.wta.toml)"
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use crate::error::{Error, Result};
+use crate::orchestrator::{Orchestrator, StatusChange};
+use crate::watcher::StatusWatcher;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+
+/// Pidfile written by a running daemon, relative to the state directory
+pub const PID_FILE: &str = "daemon.pid";
+/// Output of a daemon started with `wta daemon start`
+pub const LOG_FILE: &str = "daemon.log";
+pub const DEFAULT_INTERVAL_SECS: u64 = 2;
+
+/// How long `start` and `stop` wait for the daemon to come up or go away
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_STEP: Duration = Duration::from_millis(100);
+
+/// PID of the daemon supervising `state_dir`, if its pidfile names a live process
+pub fn running_pid(state_dir: &Path) -> Option<u32> {
+    let pid = std::fs::read_to_string(state_dir.join(PID_FILE))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    process_alive(pid).then_some(pid)
+}
+
+fn process_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Pidfile held for the lifetime of the daemon and removed on drop
+struct PidFile {
+    path: std::path::PathBuf,
+}
+
+impl PidFile {
+    fn acquire(state_dir: &Path) -> Result<Self> {
+        if let Some(pid) = running_pid(state_dir) {
+            return Err(Error::DaemonAlreadyRunning(pid));
+        }
+
+        // Left behind by a daemon that did not shut down cleanly
+        let path = state_dir.join(PID_FILE);
+        let _ = std::fs::remove_file(&path);
+
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                // Another daemon won the race
+                let pid = running_pid(state_dir).unwrap_or_default();
+                return Err(Error::DaemonAlreadyRunning(pid));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        write!(file, "{}", std::process::id())?;
+
+        Ok(Self { path })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn log(message: &str) {
+    println!(
+        "{} {message}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+}
+
+/// Supervise agents in the foreground until SIGINT or SIGTERM. Agents are
+/// re-checked whenever their status files or worktrees change, and at least
+/// every `interval`. Each check reloads state (other wta commands keep
+/// writing it), records status transitions, starts queued agents and runs
+/// the notify hook.
+pub async fn run(interval: Duration) -> Result<()> {
+    let mut orchestrator = Orchestrator::new()?;
+    let _pidfile = PidFile::acquire(&orchestrator.state_dir())?;
+    let mut watcher = StatusWatcher::new(&orchestrator)?;
+
+    log(&format!(
+        "Supervising agents in {} (pid {}, fallback check every {}s)",
+        orchestrator.repo_root().display(),
+        std::process::id(),
+        interval.as_secs_f32()
+    ));
+
+    let mut sigterm = signal(SignalKind::terminate())?;
+
+    loop {
+        if let Err(e) = tick(&mut orchestrator) {
+            log(&format!("Warning: refresh failed: {e}"));
+        }
+
+        tokio::select! {
+            _ = watcher.wait(interval) => {}
+            _ = tokio::signal::ctrl_c() => break,
+            _ = sigterm.recv() => break,
+        }
+    }
+
+    log("Shutting down");
+    Ok(())
+}
+
+fn tick(orchestrator: &mut Orchestrator) -> Result<()> {
+    orchestrator.reload_state()?;
+
+    for change in orchestrator.refresh()? {
+        log(&format!(
+            "Agent {}: {} -> {}",
+            change.id, change.from, change.to
+        ));
+        notify(orchestrator, &change);
+    }
+
+    Ok(())
+}
+
+/// Run the configured `notify_command` for a status change without waiting for it
+fn notify(orchestrator: &Orchestrator, change: &StatusChange) {
+    let Some(command) = orchestrator.config().notify_command.value.clone() else {
+        return;
+    };
+    let Ok(agent) = orchestrator.get_agent(&change.id.0) else {
+        return;
+    };
+
+    let child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .current_dir(orchestrator.repo_root())
+        .env("WTA_AGENT_ID", &change.id.0)
+        .env("WTA_STATUS", change.to.to_string())
+        .env("WTA_PREVIOUS_STATUS", change.from.to_string())
+        .env("WTA_BRANCH", &agent.branch)
+        .env("WTA_TASK", agent.current_task())
+        .stdin(Stdio::null())
+        .spawn();
+
+    match child {
+        Ok(mut child) => {
+            tokio::spawn(async move {
+                if let Ok(status) = child.wait().await {
+                    if !status.success() {
+                        log(&format!("Warning: notify_command exited with {status}"));
+                    }
+                }
+            });
+        }
+        Err(e) => log(&format!("Warning: could not run notify_command: {e}")),
+    }
+}
+
+/// Start `wta daemon run` in the background, logging to `daemon.log`.
+/// Returns the PID once the daemon has written its pidfile.
+pub fn spawn(orchestrator: &Orchestrator, interval: Duration) -> Result<u32> {
+    let state_dir = orchestrator.state_dir();
+    if let Some(pid) = running_pid(&state_dir) {
+        return Err(Error::DaemonAlreadyRunning(pid));
+    }
+
+    let log_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(state_dir.join(LOG_FILE))?;
+
+    let mut child = Command::new(std::env::current_exe()?)
+        .args(["daemon", "run", "--interval"])
+        .arg(interval.as_secs().to_string())
+        .current_dir(orchestrator.repo_root())
+        .stdin(Stdio::null())
+        .stdout(log_file.try_clone()?)
+        .stderr(log_file)
+        // Own process group, so Ctrl-C in the launching terminal does not reach it
+        .process_group(0)
+        .spawn()?;
+
+    let mut waited = Duration::ZERO;
+    while waited < STARTUP_TIMEOUT {
+        if let Some(pid) = running_pid(&state_dir) {
+            return Ok(pid);
+        }
+        if let Some(status) = child.try_wait()? {
+            return Err(Error::ExternalProcessFailed(format!(
+                "Daemon exited during startup ({status}); see {}",
+                state_dir.join(LOG_FILE).display()
+            )));
+        }
+        std::thread::sleep(POLL_STEP);
+        waited += POLL_STEP;
+    }
+
+    Err(Error::ExternalProcessFailed(
+        "Timed out waiting for the daemon to start".to_string(),
+    ))
+}
+
+/// Ask the daemon to shut down and wait for it to exit. Returns its PID.
+pub fn stop(state_dir: &Path) -> Result<u32> {
+    let pid = running_pid(state_dir).ok_or(Error::DaemonNotRunning)?;
+
+    let status = Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .status()?;
+    if !status.success() {
+        return Err(Error::ExternalProcessFailed(format!(
+            "Failed to signal daemon (pid {pid})"
+        )));
+    }
+
+    let mut waited = Duration::ZERO;
+    while process_alive(pid) {
+        if waited >= STARTUP_TIMEOUT {
+            return Err(Error::ExternalProcessFailed(format!(
+                "Daemon (pid {pid}) did not exit"
+            )));
+        }
+        std::thread::sleep(POLL_STEP);
+        waited += POLL_STEP;
+    }
+
+    Ok(pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_running_pid_without_pidfile() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(running_pid(temp_dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_running_pid_ignores_garbage_pidfile() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(PID_FILE), "not a pid").unwrap();
+        assert!(running_pid(temp_dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_pidfile_acquire_and_release() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(PID_FILE);
+
+        let pidfile = PidFile::acquire(temp_dir.path()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            std::process::id().to_string()
+        );
+        assert_eq!(running_pid(temp_dir.path()), Some(std::process::id()));
+
+        // A second daemon for the same state dir is refused
+        assert!(matches!(
+            PidFile::acquire(temp_dir.path()),
+            Err(Error::DaemonAlreadyRunning(_))
+        ));
+
+        drop(pidfile);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_pidfile_replaces_stale_pidfile() {
+        let temp_dir = TempDir::new().unwrap();
+        // PIDs are capped well below this, so it cannot name a live process
+        std::fs::write(temp_dir.path().join(PID_FILE), "4194305").unwrap();
+
+        let _pidfile = PidFile::acquire(temp_dir.path()).unwrap();
+        assert_eq!(running_pid(temp_dir.path()), Some(std::process::id()));
+    }
+}
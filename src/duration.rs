@@ -0,0 +1,122 @@
+use serde::{Deserialize, Deserializer};
+use std::time::Duration;
+
+/// Parse a duration such as `45m`, `2h`, `1h30m` or `90s`. A bare number is
+/// taken as minutes.
+pub fn parse_duration(input: &str) -> std::result::Result<Duration, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("empty duration".to_string());
+    }
+    if let Ok(minutes) = input.parse::<u64>() {
+        return non_zero(minutes.saturating_mul(60), input);
+    }
+
+    let mut secs = 0u64;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            _ => return Err(format!("invalid duration '{input}': unknown unit '{c}'")),
+        };
+        let value: u64 = number
+            .parse()
+            .map_err(|_| format!("invalid duration '{input}': expected a number before '{c}'"))?;
+        secs = secs.saturating_add(value.saturating_mul(unit));
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(format!(
+            "invalid duration '{input}': missing unit after '{number}' (use s, m, h or d)"
+        ));
+    }
+
+    non_zero(secs, input)
+}
+
+fn non_zero(secs: u64, input: &str) -> std::result::Result<Duration, String> {
+    if secs == 0 {
+        return Err(format!(
+            "invalid duration '{input}': must be greater than zero"
+        ));
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Format a duration in the form accepted by `parse_duration`, e.g. `1h30m`
+pub fn format_duration(duration: Duration) -> String {
+    let mut rest = duration.as_secs();
+    if rest == 0 {
+        return "0s".to_string();
+    }
+
+    let mut out = String::new();
+    for (unit, size) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        let count = rest / size;
+        rest %= size;
+        if count > 0 {
+            out.push_str(&format!("{count}{unit}"));
+        }
+    }
+    out
+}
+
+/// Deserialize an optional duration written as a string, e.g. `timeout = "45m"`
+pub fn deserialize_optional<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| parse_duration(&s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("45m").unwrap(), Duration::from_secs(45 * 60));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(2 * 3600));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+        assert_eq!(
+            parse_duration("1h30m").unwrap(),
+            Duration::from_secs(90 * 60)
+        );
+        // Bare numbers are minutes
+        assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_invalid_input() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("1h30").is_err());
+    }
+
+    #[test]
+    fn test_format_duration_roundtrips() {
+        for input in ["45s", "45m", "2h", "1h30m", "1d2h", "2h0m5s"] {
+            let duration = parse_duration(input).unwrap();
+            assert_eq!(
+                parse_duration(&format_duration(duration)).unwrap(),
+                duration
+            );
+        }
+        assert_eq!(format_duration(Duration::from_secs(90 * 60)), "1h30m");
+        assert_eq!(format_duration(Duration::from_secs(3600 + 5)), "1h5s");
+    }
+}
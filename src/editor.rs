@@ -2,6 +2,7 @@ use std::env;
 use std::fs;
 use std::io::Write;
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::error::{Error, Result};
 
@@ -16,8 +17,23 @@ const TASK_TEMPLATE: &str = "\
 /// If `custom_editor` is provided, it overrides all other editor settings.
 /// Returns the task string or an error if aborted.
 pub fn open_editor_for_task(custom_editor: Option<String>) -> Result<String> {
+    let contents = edit_text(custom_editor, "txt", TASK_TEMPLATE)?;
+
+    // Process the contents: remove comment lines and trim
+    let task = process_editor_content(&contents);
+
+    if task.is_empty() {
+        return Err(Error::EditorAborted("Empty task description".to_string()));
+    }
+
+    Ok(task)
+}
+
+/// Opens the user's preferred editor on `initial` in a temporary file with
+/// the given extension, and returns the file's contents once the editor exits
+pub fn edit_text(custom_editor: Option<String>, extension: &str, initial: &str) -> Result<String> {
     let editor = normalize_editor(custom_editor.unwrap_or_else(get_editor));
-    let temp_path = create_temp_file()?;
+    let temp_path = create_temp_file(extension, initial)?;
 
     // Open editor and wait for it to close
     // Use shell to handle editors with arguments (e.g., "code --wait")
@@ -46,14 +62,7 @@ pub fn open_editor_for_task(custom_editor: Option<String>) -> Result<String> {
     // Clean up temp file
     let _ = fs::remove_file(&temp_path);
 
-    // Process the contents: remove comment lines and trim
-    let task = process_editor_content(&contents);
-
-    if task.is_empty() {
-        return Err(Error::EditorAborted("Empty task description".to_string()));
-    }
-
-    Ok(task)
+    Ok(contents)
 }
 
 /// Get the editor following git's precedence:
@@ -118,16 +127,22 @@ fn normalize_editor(editor: String) -> String {
     }
 }
 
-fn create_temp_file() -> Result<String> {
+fn create_temp_file(extension: &str, initial: &str) -> Result<String> {
     let temp_dir = env::temp_dir();
-    let temp_path = temp_dir.join(format!("wta_task_{}.txt", std::process::id()));
+    // Unique per call, as one process may edit several texts at once
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let temp_path = temp_dir.join(format!(
+        "wta_task_{}_{}.{extension}",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
     let temp_path_str = temp_path
         .to_str()
         .ok_or_else(|| Error::InvalidUtf8Path(temp_path.clone()))?
         .to_string();
 
     let mut file = fs::File::create(&temp_path)?;
-    file.write_all(TASK_TEMPLATE.as_bytes())?;
+    file.write_all(initial.as_bytes())?;
 
     Ok(temp_path_str)
 }
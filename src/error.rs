@@ -48,6 +48,9 @@ pub enum Error {
     #[error("Not a git repository")]
     NotAGitRepository,
 
+    #[error("Not inside an agent worktree: {0}")]
+    NotInAgentWorktree(PathBuf),
+
     #[error("State file corrupted: {0}")]
     StateCorrupted(String),
 
@@ -67,6 +70,106 @@ pub enum Error {
     #[error("Editor aborted: {0}")]
     EditorAborted(String),
 
+    #[error("Invalid config file {path}: {message}")]
+    ConfigInvalid { path: PathBuf, message: String },
+
+    #[error("Invalid task manifest {path}: {message}")]
+    InvalidManifest { path: PathBuf, message: String },
+
+    #[error("{0} has no print mode to plan with; use --provider claude, codex or gemini")]
+    PlanUnsupported(String),
+
+    #[error("Planning failed: {0}")]
+    PlanFailed(String),
+
+    #[error("Invalid plan: {0}")]
+    InvalidPlan(String),
+
+    #[error("Task dependency cycle: {}", .0.join(", "))]
+    DependencyCycle(Vec<String>),
+
+    #[error("Dependency {0} failed or timed out; not launching")]
+    DependencyFailed(String),
+
+    #[error("Agent is queued and has not started: {0}")]
+    AgentQueued(String),
+
+    #[error("Agent is not running: {0}")]
+    AgentNotRunning(String),
+
+    #[error("Agent has already been merged: {0}")]
+    AgentMerged(String),
+
+    #[error("Agent {0} is {1}; only failed, timed-out and invalid-report agents can be retried")]
+    CannotRetry(String, String),
+
+    #[error("Agent {0} is {1}, not awaiting approval of a plan")]
+    NotAwaitingApproval(String, String),
+
+    #[error("Agent {0} was not forked from another agent")]
+    NotForked(String),
+
+    #[error("Target branch is checked out by agent {0}, which is still running")]
+    TargetAgentRunning(String),
+
+    #[error("Cannot update {branch}, which is checked out at {}: {reason}", path.display())]
+    TargetCheckedOut {
+        branch: String,
+        path: PathBuf,
+        reason: String,
+    },
+
+    #[error("Attempt group not found: {0}")]
+    GroupNotFound(String),
+
+    #[error("Agent {0} is not in attempt group {1}")]
+    NotInGroup(String, String),
+
+    #[error("No check_command configured; set one in .wta.toml")]
+    NoCheckCommand,
+
+    #[error("Agent {0} has no open question")]
+    NoOpenQuestion(String),
+
+    #[error("Question {1} of agent {0} is not open")]
+    QuestionNotOpen(String, u32),
+
+    #[error("Question {1} of agent {0} was removed before it was answered")]
+    QuestionRemoved(String, u32),
+
+    #[error("No answer to question {0} after {1}; run the same 'wta ask' again to keep waiting")]
+    AskTimedOut(u32, String),
+
+    #[error("Message is empty")]
+    EmptyMessage,
+
+    #[error("File watcher error: {0}")]
+    Watch(#[from] notify::Error),
+
+    #[error("Daemon already running (pid {0})")]
+    DaemonAlreadyRunning(u32),
+
+    #[error("Daemon is not running")]
+    DaemonNotRunning,
+
+    #[error("{failed} of {total} batch launches failed")]
+    BatchLaunchFailed { failed: usize, total: usize },
+
+    #[error("{failed} of {total} queued merges did not land")]
+    MergeQueueFailed { failed: usize, total: usize },
+
+    #[error("{0} kept moving while checks ran; queue the agent again")]
+    TargetKeptMoving(String),
+
+    #[error("Agent {0} is {1}; only completed, conflict and checks-failed agents can be landed")]
+    NotLandable(String, String),
+
+    #[error("Agent not found in the merge queue: {0}")]
+    NotInMergeQueue(String),
+
+    #[error("Check cancelled")]
+    CheckCancelled,
+
     #[error("Too many orphaned worktrees found ({0} consecutive). Run 'wta prune' to clean up.")]
     TooManyOrphanedWorktrees(u32),
 }
@@ -1,11 +1,16 @@
 use crate::error::{Error, Result};
-use crate::orchestrator::{MergeResult, MergeStrategy};
+use crate::orchestrator::{ConflictHunk, FileConflict, MergeResult, MergeStrategy};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 
 const GIT: &str = "git";
 const CONFLICT_UPPER: &str = "CONFLICT";
 const CONFLICT_LOWER: &str = "conflict";
+const INTEGRATION_PREFIX: &str = "wta-merge-";
+const MARKER_OURS: &str = "<<<<<<<";
+const MARKER_BASE: &str = "|||||||";
+const MARKER_SPLIT: &str = "=======";
+const MARKER_THEIRS: &str = ">>>>>>>";
 
 fn run_git(repo_root: &Path, args: &[&str]) -> Result<Output> {
     Command::new(GIT)
@@ -31,45 +36,105 @@ fn has_conflict(stderr: &str) -> bool {
     stderr.contains(CONFLICT_UPPER) || stderr.contains(CONFLICT_LOWER)
 }
 
-fn checkout(repo_root: &Path, branch: &str) -> Result<()> {
-    run_git_checked(repo_root, &["checkout", branch], "git checkout")?;
-    Ok(())
+/// A detached worktree in which a merge is done, so the user's checkout and
+/// index are never touched. Removed when dropped.
+struct IntegrationWorktree {
+    repo_root: PathBuf,
+    path: PathBuf,
 }
 
-/// Merge a branch back into the base branch
-pub fn merge_branch(
-    repo_root: &Path,
-    branch: &str,
-    base_branch: &str,
-    strategy: MergeStrategy,
-) -> Result<MergeResult> {
-    checkout(repo_root, base_branch)?;
+impl IntegrationWorktree {
+    fn create(repo_root: &Path, commit: &str) -> Result<Self> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or_default();
+        let path = std::env::temp_dir().join(format!(
+            "{INTEGRATION_PREFIX}{}-{nanos}",
+            std::process::id()
+        ));
+        let path_str = path.to_string_lossy().to_string();
+        run_git_checked(
+            repo_root,
+            &["worktree", "add", "--detach", &path_str, commit],
+            "git worktree add",
+        )?;
+        Ok(Self {
+            repo_root: repo_root.to_path_buf(),
+            path,
+        })
+    }
+
+    fn head(&self) -> Result<String> {
+        rev_parse(&self.path, "HEAD")
+    }
+}
 
-    match strategy {
-        MergeStrategy::Merge => do_merge(repo_root, branch),
-        MergeStrategy::Rebase => do_rebase(repo_root, branch, base_branch),
-        MergeStrategy::Squash => do_squash_merge(repo_root, branch),
+impl Drop for IntegrationWorktree {
+    fn drop(&mut self) {
+        let path = self.path.to_string_lossy().to_string();
+        let removed = run_git(&self.repo_root, &["worktree", "remove", "--force", &path]);
+        if !removed.is_ok_and(|o| o.status.success()) {
+            let _ = std::fs::remove_dir_all(&self.path);
+            let _ = run_git(&self.repo_root, &["worktree", "prune"]);
+        }
     }
 }
 
-fn do_merge(repo_root: &Path, branch: &str) -> Result<MergeResult> {
-    let output = run_git(repo_root, &["merge", branch, "--no-edit"])?;
+fn rev_parse(dir: &Path, rev: &str) -> Result<String> {
+    let output = run_git_checked(
+        dir,
+        &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+        "git rev-parse",
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Commit a local branch points to
+pub fn branch_commit(repo_root: &Path, branch: &str) -> Result<String> {
+    rev_parse(repo_root, &format!("refs/heads/{branch}"))
+}
 
+/// Rebase the branch checked out in `worktree` onto `target`. Conflicts abort
+/// the rebase, leaving the branch as it was.
+pub fn rebase_worktree(worktree: &Path, target: &str) -> Result<()> {
+    let output = run_git(worktree, &["rebase", target])?;
     if output.status.success() {
-        return Ok(MergeResult {
-            success: true,
-            message: format!("Successfully merged {branch}"),
-            conflicts: Vec::new(),
-        });
+        return Ok(());
     }
 
     let stderr = String::from_utf8_lossy(&output.stderr);
-    if has_conflict(&stderr) {
-        let conflicts = get_conflict_files(repo_root)?;
-        let _ = run_git(repo_root, &["merge", "--abort"]);
-        return Err(Error::MergeConflict(conflicts));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if has_conflict(&stderr) || has_conflict(&stdout) {
+        let conflicts = get_conflict_files(worktree);
+        let _ = run_git(worktree, &["rebase", "--abort"]);
+        return Err(Error::MergeConflict(conflicts?));
+    }
+    Err(Error::CommandFailed {
+        command: "git rebase".to_string(),
+        code: output.status.code(),
+        stderr: stderr.to_string(),
+    })
+}
+
+/// Merge `branch` into the branch checked out in `worktree` with a merge
+/// commit. Conflicts abort the merge, leaving the worktree as it was.
+pub fn merge_into_worktree(worktree: &Path, branch: &str, message: &str) -> Result<()> {
+    let output = run_git(
+        worktree,
+        &["merge", "--no-ff", "--no-edit", "-m", message, branch],
+    )?;
+    if output.status.success() {
+        return Ok(());
     }
 
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if has_conflict(&stderr) || has_conflict(&stdout) {
+        let conflicts = get_conflict_files(worktree);
+        let _ = run_git(worktree, &["merge", "--abort"]);
+        return Err(Error::MergeConflict(conflicts?));
+    }
     Err(Error::CommandFailed {
         command: "git merge".to_string(),
         code: output.status.code(),
@@ -77,65 +142,291 @@ fn do_merge(repo_root: &Path, branch: &str) -> Result<MergeResult> {
     })
 }
 
-fn do_rebase(repo_root: &Path, branch: &str, base_branch: &str) -> Result<MergeResult> {
-    checkout(repo_root, branch)?;
+/// Worktree, if any, that has `branch` checked out
+pub fn checked_out_at(repo_root: &Path, branch: &str) -> Result<Option<PathBuf>> {
+    let worktrees = crate::git::WorktreeManager::new(repo_root).list()?;
+    Ok(worktrees
+        .into_iter()
+        .find(|w| w.branch == branch)
+        .map(|w| w.path))
+}
 
-    let output = run_git(repo_root, &["rebase", base_branch])?;
+/// Refuse a merge target that is checked out somewhere, since merging into it
+/// would change that checkout
+pub fn ensure_not_checked_out(repo_root: &Path, branch: &str) -> Result<()> {
+    match checked_out_at(repo_root, branch)? {
+        Some(path) => Err(Error::TargetCheckedOut {
+            branch: branch.to_string(),
+            path,
+            reason: "it was not updated, as that would change the checkout. Switch it to another branch, or pass --update-checkout (or set update_checkout = true) to fast-forward it there".to_string(),
+        }),
+        None => Ok(()),
+    }
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if has_conflict(&stderr) {
-            let conflicts = get_conflict_files(repo_root)?;
-            let _ = run_git(repo_root, &["rebase", "--abort"]);
-            return Err(Error::MergeConflict(conflicts));
-        }
-        return Err(Error::CommandFailed {
-            command: "git rebase".to_string(),
-            code: output.status.code(),
-            stderr: stderr.to_string(),
-        });
+/// Merge a branch back into the base branch.
+///
+/// The merge is done in a temporary detached worktree and the base branch is
+/// only moved once it succeeds. If the base branch is checked out somewhere,
+/// such as the main working tree, the merge is refused unless
+/// `update_checkout` is set, in which case the base branch is fast-forwarded
+/// there, failing without changing anything if local changes would be
+/// overwritten.
+pub fn merge_branch(
+    repo_root: &Path,
+    branch: &str,
+    base_branch: &str,
+    strategy: MergeStrategy,
+    update_checkout: bool,
+) -> Result<MergeResult> {
+    // Refuse before merging, so no temporary worktree is made for nothing
+    if !update_checkout {
+        ensure_not_checked_out(repo_root, base_branch)?;
     }
 
-    checkout(repo_root, base_branch)?;
-    run_git_checked(
-        repo_root,
-        &["merge", "--ff-only", branch],
-        "git merge --ff-only",
-    )?;
+    let base = branch_commit(repo_root, base_branch)?;
+    let tip = branch_commit(repo_root, branch)?;
+
+    let (merged, message) = match strategy {
+        MergeStrategy::Merge => (
+            do_merge(repo_root, &base, branch, base_branch)?,
+            format!("Successfully merged {branch}"),
+        ),
+        MergeStrategy::Rebase => (
+            do_rebase(repo_root, &tip, &base)?,
+            format!("Successfully rebased and merged {branch}"),
+        ),
+        MergeStrategy::Squash => (
+            do_squash_merge(repo_root, &base, branch)?,
+            format!("Successfully squash-merged {branch}"),
+        ),
+    };
+
+    update_base(repo_root, base_branch, &base, &merged)?;
 
     Ok(MergeResult {
         success: true,
-        message: format!("Successfully rebased and merged {branch}"),
+        message,
         conflicts: Vec::new(),
     })
 }
 
-fn do_squash_merge(repo_root: &Path, branch: &str) -> Result<MergeResult> {
-    let output = run_git(repo_root, &["merge", "--squash", branch])?;
+/// Files that would conflict if `branch` were merged into `base_branch`,
+/// computed with `git merge-tree` so no working tree, index or ref changes.
+/// Rebases replay commits one by one and can conflict where this does not.
+pub fn check_merge(repo_root: &Path, branch: &str, base_branch: &str) -> Result<Vec<FileConflict>> {
+    // Fail on missing branches rather than reporting them as conflicts
+    branch_commit(repo_root, base_branch)?;
+    branch_commit(repo_root, branch)?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if has_conflict(&stderr) {
-            let conflicts = get_conflict_files(repo_root)?;
-            let _ = run_git(repo_root, &["reset", "--hard", "HEAD"]);
-            return Err(Error::MergeConflict(conflicts));
+    let output = run_git(
+        repo_root,
+        &[
+            "merge-tree",
+            "--write-tree",
+            "--name-only",
+            base_branch,
+            branch,
+        ],
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match output.status.code() {
+        Some(0) => return Ok(Vec::new()),
+        Some(1) => {}
+        code => {
+            return Err(Error::CommandFailed {
+                command: "git merge-tree --write-tree (git 2.38 or newer)".to_string(),
+                code,
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            })
         }
-        return Err(Error::CommandFailed {
-            command: "git merge --squash".to_string(),
-            code: output.status.code(),
-            stderr: stderr.to_string(),
-        });
     }
 
-    run_git_checked(repo_root, &["commit", "--no-edit"], "git commit")?;
+    // The merged tree, the conflicting files, a blank line, then messages
+    let mut sections = stdout.splitn(2, "\n\n");
+    let mut files = sections.next().unwrap_or_default().lines();
+    let tree = files.next().unwrap_or_default().trim().to_string();
+    let messages: Vec<&str> = sections
+        .next()
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| l.starts_with(CONFLICT_UPPER))
+        .collect();
+
+    let mut conflicts: Vec<FileConflict> = Vec::new();
+    for path in files.map(str::trim).filter(|p| !p.is_empty()) {
+        if conflicts.iter().any(|c| c.path == Path::new(path)) {
+            continue;
+        }
+        let content = run_git(repo_root, &["cat-file", "-p", &format!("{tree}:{path}")])?;
+        conflicts.push(FileConflict {
+            path: PathBuf::from(path),
+            reason: messages
+                .iter()
+                .find(|m| mentions_path(m, path))
+                .map(|m| m.to_string()),
+            hunks: if content.status.success() {
+                parse_conflict_hunks(&String::from_utf8_lossy(&content.stdout))
+            } else {
+                Vec::new()
+            },
+        });
+    }
+    Ok(conflicts)
+}
 
-    Ok(MergeResult {
-        success: true,
-        message: format!("Successfully squash-merged {branch}"),
-        conflicts: Vec::new(),
+/// Whether a conflict message names `path` itself, rather than a path that
+/// contains it such as `src/lib.rs` for `lib.rs`
+fn mentions_path(message: &str, path: &str) -> bool {
+    message.match_indices(path).any(|(start, _)| {
+        let before = message[..start].chars().next_back();
+        // Trailing punctuation ends a sentence, not the path
+        let mut after = message[start + path.len()..]
+            .trim_start_matches(['.', ',', ':', ';'])
+            .chars();
+        before.is_none_or(char::is_whitespace) && after.next().is_none_or(char::is_whitespace)
     })
 }
 
+/// Conflict marker blocks of a merged file
+fn parse_conflict_hunks(content: &str) -> Vec<ConflictHunk> {
+    enum Side {
+        Outside,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut hunks = Vec::new();
+    let mut side = Side::Outside;
+    let mut current = ConflictHunk::default();
+    for (i, line) in content.lines().enumerate() {
+        side = match side {
+            Side::Outside if line.starts_with(MARKER_OURS) => {
+                current = ConflictHunk {
+                    line: i + 1,
+                    ..Default::default()
+                };
+                Side::Ours
+            }
+            Side::Outside => Side::Outside,
+            Side::Ours | Side::Base if line.starts_with(MARKER_SPLIT) => Side::Theirs,
+            Side::Ours if line.starts_with(MARKER_BASE) => Side::Base,
+            Side::Ours => {
+                current.ours.push_str(line);
+                current.ours.push('\n');
+                Side::Ours
+            }
+            Side::Base => Side::Base,
+            Side::Theirs if line.starts_with(MARKER_THEIRS) => {
+                hunks.push(std::mem::take(&mut current));
+                Side::Outside
+            }
+            Side::Theirs => {
+                current.theirs.push_str(line);
+                current.theirs.push('\n');
+                Side::Theirs
+            }
+        };
+    }
+    hunks
+}
+
+/// Move `base_branch` from `old` to `new`, failing if it moved in the meantime.
+/// Where it is checked out, it is fast-forwarded there.
+fn update_base(repo_root: &Path, base_branch: &str, old: &str, new: &str) -> Result<()> {
+    if old == new {
+        return Ok(());
+    }
+    match checked_out_at(repo_root, base_branch)? {
+        Some(path) => {
+            let output = run_git(&path, &["merge", "--ff-only", "--quiet", new])?;
+            if !output.status.success() {
+                return Err(Error::TargetCheckedOut {
+                    branch: base_branch.to_string(),
+                    path,
+                    reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                });
+            }
+        }
+        None => {
+            run_git_checked(
+                repo_root,
+                &[
+                    "update-ref",
+                    "-m",
+                    "wta merge",
+                    &format!("refs/heads/{base_branch}"),
+                    new,
+                    old,
+                ],
+                "git update-ref",
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Turn a failed merge or rebase in `worktree` into a conflict error if it
+/// stopped on conflicts
+fn merge_failure(worktree: &IntegrationWorktree, output: &Output, command: &str) -> Error {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if has_conflict(&stderr) || has_conflict(&stdout) {
+        match get_conflict_files(&worktree.path) {
+            Ok(conflicts) => return Error::MergeConflict(conflicts),
+            Err(e) => return e,
+        }
+    }
+    Error::CommandFailed {
+        command: command.to_string(),
+        code: output.status.code(),
+        stderr: stderr.to_string(),
+    }
+}
+
+fn do_merge(repo_root: &Path, base: &str, branch: &str, base_branch: &str) -> Result<String> {
+    let worktree = IntegrationWorktree::create(repo_root, base)?;
+    let message = format!("Merge branch '{branch}' into {base_branch}");
+    let output = run_git(
+        &worktree.path,
+        &["merge", branch, "--no-edit", "-m", &message],
+    )?;
+
+    if !output.status.success() {
+        return Err(merge_failure(&worktree, &output, "git merge"));
+    }
+    worktree.head()
+}
+
+fn do_rebase(repo_root: &Path, tip: &str, base: &str) -> Result<String> {
+    let worktree = IntegrationWorktree::create(repo_root, tip)?;
+    let output = run_git(&worktree.path, &["rebase", base])?;
+
+    if !output.status.success() {
+        let error = merge_failure(&worktree, &output, "git rebase");
+        let _ = run_git(&worktree.path, &["rebase", "--abort"]);
+        return Err(error);
+    }
+    worktree.head()
+}
+
+fn do_squash_merge(repo_root: &Path, base: &str, branch: &str) -> Result<String> {
+    let worktree = IntegrationWorktree::create(repo_root, base)?;
+    let output = run_git(&worktree.path, &["merge", "--squash", branch])?;
+
+    if !output.status.success() {
+        return Err(merge_failure(&worktree, &output, "git merge --squash"));
+    }
+
+    // Nothing to commit when the branch adds nothing to the base
+    let staged = run_git(&worktree.path, &["diff", "--cached", "--quiet"])?;
+    if !staged.status.success() {
+        run_git_checked(&worktree.path, &["commit", "--no-edit"], "git commit")?;
+    }
+    worktree.head()
+}
+
 fn get_conflict_files(repo_root: &Path) -> Result<Vec<PathBuf>> {
     let output = run_git(repo_root, &["diff", "--name-only", "--diff-filter=U"])?;
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -146,6 +437,260 @@ fn get_conflict_files(repo_root: &Path) -> Result<Vec<PathBuf>> {
 mod tests {
     use super::*;
 
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = run_git_checked(dir, args, "git").unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_file(dir: &Path, name: &str, content: &str) {
+        std::fs::write(dir.join(name), content).unwrap();
+        git(dir, &["add", name]);
+        git(dir, &["commit", "-q", "-m", name]);
+    }
+
+    /// Repository on `main` with a `feature` branch that adds feature.txt
+    fn test_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.name", "test"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        commit_file(root, "shared.txt", "base\n");
+        git(root, &["checkout", "-q", "-b", "feature"]);
+        commit_file(root, "feature.txt", "feature\n");
+        git(root, &["checkout", "-q", "main"]);
+        dir
+    }
+
+    #[test]
+    fn test_merge_branch_leaves_checkout_and_index_alone() {
+        let dir = test_repo();
+        let root = dir.path();
+        git(root, &["checkout", "-q", "-b", "wip"]);
+        std::fs::write(root.join("shared.txt"), "uncommitted\n").unwrap();
+        std::fs::write(root.join("staged.txt"), "staged\n").unwrap();
+        git(root, &["add", "staged.txt"]);
+
+        for strategy in [
+            MergeStrategy::Merge,
+            MergeStrategy::Rebase,
+            MergeStrategy::Squash,
+        ] {
+            let before = git(root, &["rev-parse", "main"]);
+            let result = merge_branch(root, "feature", "main", strategy, false).unwrap();
+            assert!(result.success);
+            assert_ne!(git(root, &["rev-parse", "main"]), before);
+            assert_eq!(git(root, &["show", "main:feature.txt"]), "feature");
+            git(root, &["update-ref", "refs/heads/main", &before]);
+        }
+
+        assert_eq!(git(root, &["branch", "--show-current"]), "wip");
+        assert_eq!(
+            git(root, &["status", "--porcelain"]),
+            "M shared.txt\nA  staged.txt"
+        );
+        assert!(!root.join("feature.txt").exists());
+        assert_eq!(git(root, &["worktree", "list"]).lines().count(), 1);
+    }
+
+    #[test]
+    fn test_merge_branch_conflict_keeps_target() {
+        let dir = test_repo();
+        let root = dir.path();
+        commit_file(root, "feature.txt", "main\n");
+        let before = git(root, &["rev-parse", "main"]);
+
+        for strategy in [
+            MergeStrategy::Merge,
+            MergeStrategy::Rebase,
+            MergeStrategy::Squash,
+        ] {
+            match merge_branch(root, "feature", "main", strategy, true) {
+                Err(Error::MergeConflict(files)) => {
+                    assert_eq!(files, vec![PathBuf::from("feature.txt")]);
+                }
+                other => panic!("expected a conflict, got {other:?}"),
+            }
+        }
+
+        assert_eq!(git(root, &["rev-parse", "main"]), before);
+        assert_eq!(git(root, &["status", "--porcelain"]), "");
+        assert_eq!(git(root, &["worktree", "list"]).lines().count(), 1);
+    }
+
+    #[test]
+    fn test_merge_branch_refuses_checked_out_target() {
+        let dir = test_repo();
+        let root = dir.path();
+        let before = git(root, &["rev-parse", "main"]);
+
+        let err = merge_branch(root, "feature", "main", MergeStrategy::Merge, false).unwrap_err();
+
+        assert!(matches!(err, Error::TargetCheckedOut { .. }));
+        assert_eq!(git(root, &["rev-parse", "main"]), before);
+        assert_eq!(git(root, &["status", "--porcelain"]), "");
+        assert!(!root.join("feature.txt").exists());
+        assert_eq!(git(root, &["worktree", "list"]).lines().count(), 1);
+    }
+
+    #[test]
+    fn test_merge_branch_fast_forwards_checked_out_target_when_asked() {
+        let dir = test_repo();
+        let root = dir.path();
+
+        merge_branch(root, "feature", "main", MergeStrategy::Merge, true).unwrap();
+
+        assert_eq!(git(root, &["branch", "--show-current"]), "main");
+        assert_eq!(git(root, &["status", "--porcelain"]), "");
+        assert!(root.join("feature.txt").exists());
+    }
+
+    #[test]
+    fn test_merge_branch_refuses_to_overwrite_local_changes() {
+        let dir = test_repo();
+        let root = dir.path();
+        std::fs::write(root.join("feature.txt"), "untracked\n").unwrap();
+        let before = git(root, &["rev-parse", "main"]);
+
+        let err = merge_branch(root, "feature", "main", MergeStrategy::Merge, true).unwrap_err();
+
+        assert!(matches!(err, Error::TargetCheckedOut { .. }));
+        assert_eq!(git(root, &["rev-parse", "main"]), before);
+        assert_eq!(
+            std::fs::read_to_string(root.join("feature.txt")).unwrap(),
+            "untracked\n"
+        );
+    }
+
+    #[test]
+    fn test_rebase_worktree_aborts_on_conflict() {
+        let dir = test_repo();
+        let root = dir.path();
+        commit_file(root, "feature.txt", "main\n");
+        let worktree = root.join("wt");
+        git(
+            root,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                worktree.to_str().unwrap(),
+                "feature",
+            ],
+        );
+        let before = git(root, &["rev-parse", "feature"]);
+
+        match rebase_worktree(&worktree, "main") {
+            Err(Error::MergeConflict(files)) => {
+                assert_eq!(files, vec![PathBuf::from("feature.txt")]);
+            }
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+        assert_eq!(git(root, &["rev-parse", "feature"]), before);
+        assert_eq!(git(&worktree, &["status", "--porcelain"]), "");
+
+        git(root, &["reset", "-q", "--hard", "HEAD~"]);
+        commit_file(root, "other.txt", "main\n");
+        rebase_worktree(&worktree, "main").unwrap();
+        assert_eq!(
+            git(root, &["merge-base", "main", "feature"]),
+            git(root, &["rev-parse", "main"])
+        );
+    }
+
+    #[test]
+    fn test_merge_into_worktree_aborts_on_conflict() {
+        let dir = test_repo();
+        let root = dir.path();
+        git(root, &["checkout", "-q", "-b", "other"]);
+        commit_file(root, "feature.txt", "other\n");
+        git(root, &["checkout", "-q", "main"]);
+        let worktree = root.join("wt");
+        git(
+            root,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "integration",
+                worktree.to_str().unwrap(),
+                "main",
+            ],
+        );
+
+        merge_into_worktree(&worktree, "feature", "Merge feature").unwrap();
+        let merged = git(root, &["rev-parse", "integration"]);
+        assert_eq!(
+            git(&worktree, &["log", "-1", "--format=%s"]),
+            "Merge feature"
+        );
+
+        match merge_into_worktree(&worktree, "other", "Merge other") {
+            Err(Error::MergeConflict(files)) => {
+                assert_eq!(files, vec![PathBuf::from("feature.txt")]);
+            }
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+        assert_eq!(git(root, &["rev-parse", "integration"]), merged);
+        assert_eq!(git(&worktree, &["status", "--porcelain"]), "");
+    }
+
+    #[test]
+    fn test_check_merge_lists_conflicting_hunks() {
+        let dir = test_repo();
+        let root = dir.path();
+        assert!(check_merge(root, "feature", "main").unwrap().is_empty());
+
+        commit_file(root, "feature.txt", "main\n");
+        let before = git(root, &["rev-parse", "main"]);
+
+        let conflicts = check_merge(root, "feature", "main").unwrap();
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, PathBuf::from("feature.txt"));
+        assert!(conflicts[0].reason.as_deref().unwrap().contains("CONFLICT"));
+        assert_eq!(
+            conflicts[0].hunks,
+            vec![ConflictHunk {
+                line: 1,
+                ours: "main\n".to_string(),
+                theirs: "feature\n".to_string(),
+            }]
+        );
+        assert_eq!(git(root, &["rev-parse", "main"]), before);
+        assert_eq!(git(root, &["status", "--porcelain"]), "");
+        assert!(check_merge(root, "missing", "main").is_err());
+    }
+
+    #[test]
+    fn test_mentions_path_matches_whole_path() {
+        let message = "CONFLICT (content): Merge conflict in src/lib.rs";
+        assert!(mentions_path(message, "src/lib.rs"));
+        assert!(!mentions_path(message, "lib.rs"));
+        assert!(!mentions_path(message, "src/lib"));
+        assert!(mentions_path(
+            "CONFLICT (modify/delete): a.rs deleted in main and modified in wta/1.",
+            "a.rs"
+        ));
+    }
+
+    #[test]
+    fn test_parse_conflict_hunks_skips_base_section() {
+        let content =
+            "a\n<<<<<<< main\nours\n||||||| base\nold\n=======\ntheirs\nmore\n>>>>>>> wta/1\nb\n";
+
+        assert_eq!(
+            parse_conflict_hunks(content),
+            vec![ConflictHunk {
+                line: 2,
+                ours: "ours\n".to_string(),
+                theirs: "theirs\nmore\n".to_string(),
+            }]
+        );
+        assert!(parse_conflict_hunks("no markers\n").is_empty());
+    }
+
     #[test]
     fn test_has_conflict_detects_uppercase() {
         assert!(has_conflict(
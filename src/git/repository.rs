@@ -1,7 +1,97 @@
 use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use std::path::Path;
 use std::process::Command;
 
+/// Time of the commit checked out in a repository or worktree
+pub fn last_commit_time(path: &Path) -> Option<DateTime<Utc>> {
+    let repo = git2::Repository::open(path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    DateTime::from_timestamp(commit.time().seconds(), 0)
+}
+
+/// Size of a diff, as summarised by `git diff --shortstat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DiffStats {
+    pub additions: usize,
+    pub deletions: usize,
+    pub files_changed: usize,
+}
+
+/// Output of `git diff <range>` run in a repository or worktree
+pub fn diff(path: &Path, range: &str) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["diff", range])
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::CommandFailed {
+            command: format!("git diff {range}"),
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// `git diff -U0 <range>`, with only the hunk headers needed to tell which
+/// lines changed. Renames are listed as a deletion and an addition.
+pub fn diff_lines(path: &Path, range: &str) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args([
+            "diff",
+            "-U0",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            range,
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::CommandFailed {
+            command: format!("git diff -U0 {range}"),
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Stats of `git diff <range>` run in a repository or worktree
+pub fn diff_stats(path: &Path, range: &str) -> Result<DiffStats> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["diff", "--shortstat", range])
+        .output()?;
+    Ok(parse_diff_stats(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_diff_stats(stat_str: &str) -> DiffStats {
+    let mut stats = DiffStats::default();
+
+    // Parse "3 files changed, 10 insertions(+), 5 deletions(-)"
+    for part in stat_str.split(',') {
+        let part = part.trim();
+        if part.contains("file") {
+            if let Some(num) = part.split_whitespace().next() {
+                stats.files_changed = num.parse().unwrap_or(0);
+            }
+        } else if part.contains("insertion") {
+            if let Some(num) = part.split_whitespace().next() {
+                stats.additions = num.parse().unwrap_or(0);
+            }
+        } else if part.contains("deletion") {
+            if let Some(num) = part.split_whitespace().next() {
+                stats.deletions = num.parse().unwrap_or(0);
+            }
+        }
+    }
+
+    stats
+}
+
 /// Get the default branch for a repository
 /// Based on worktrunk's approach with simplified logic
 pub fn default_branch(repo_root: &Path) -> Result<String> {
@@ -160,6 +250,42 @@ fn list_local_branches(repo_root: &Path) -> Result<Vec<String>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_diff_stats_full() {
+        let stat_str = " 3 files changed, 10 insertions(+), 5 deletions(-)";
+        let stats = parse_diff_stats(stat_str);
+        assert_eq!(stats.files_changed, 3);
+        assert_eq!(stats.additions, 10);
+        assert_eq!(stats.deletions, 5);
+    }
+
+    #[test]
+    fn test_parse_diff_stats_no_deletions() {
+        let stat_str = " 1 file changed, 5 insertions(+)";
+        let stats = parse_diff_stats(stat_str);
+        assert_eq!(stats.files_changed, 1);
+        assert_eq!(stats.additions, 5);
+        assert_eq!(stats.deletions, 0);
+    }
+
+    #[test]
+    fn test_parse_diff_stats_no_insertions() {
+        let stat_str = " 2 files changed, 3 deletions(-)";
+        let stats = parse_diff_stats(stat_str);
+        assert_eq!(stats.files_changed, 2);
+        assert_eq!(stats.additions, 0);
+        assert_eq!(stats.deletions, 3);
+    }
+
+    #[test]
+    fn test_parse_diff_stats_empty() {
+        let stat_str = "";
+        let stats = parse_diff_stats(stat_str);
+        assert_eq!(stats.files_changed, 0);
+        assert_eq!(stats.additions, 0);
+        assert_eq!(stats.deletions, 0);
+    }
+
     #[test]
     fn test_common_branch_names() {
         // Just verify the list is in the expected order
@@ -31,8 +31,19 @@ impl WorktreeManager {
         }
     }
 
+    /// Create worktrees under `parent_dir` instead of next to the repository
+    pub fn with_parent_dir(mut self, parent_dir: PathBuf) -> Self {
+        self.parent_dir = parent_dir;
+        self
+    }
+
+    /// Directory in which worktrees are created
+    pub fn parent_dir(&self) -> &Path {
+        &self.parent_dir
+    }
+
     /// Compute the worktree path as a sibling directory: <parent>/<repo_name>-wta-<id>
-    fn worktree_path(&self, id: &str) -> PathBuf {
+    pub fn worktree_path(&self, id: &str) -> PathBuf {
         self.parent_dir
             .join(format!("{}{WORKTREE_SUFFIX}{id}", self.repo_name))
     }
@@ -129,17 +140,28 @@ impl WorktreeManager {
         Ok(worktree_path)
     }
 
-    /// Remove a worktree
-    pub fn remove(&self, id: &str) -> Result<()> {
-        let worktree_path = self.worktree_path(id);
+    /// Reset a worktree's branch to `base`, discarding its commits and any
+    /// uncommitted or untracked files (ignored files are kept)
+    pub fn reset(&self, worktree_path: &Path, base: &str) -> Result<()> {
+        let path_str = worktree_path
+            .to_str()
+            .ok_or_else(|| Error::InvalidUtf8Path(worktree_path.to_path_buf()))?;
+        self.run_git_checked(&["-C", path_str, "reset", "--hard", base], "git reset")?;
+        self.run_git_checked(&["-C", path_str, "clean", "-fd"], "git clean")?;
+        Ok(())
+    }
 
+    /// Remove the worktree at `worktree_path`. Worktrees are removed by the
+    /// path they were created at, which `worktree_dir` no longer gives once
+    /// it is changed.
+    pub fn remove(&self, worktree_path: &Path) -> Result<()> {
         if !worktree_path.exists() {
-            return Err(Error::WorktreeNotFound(worktree_path));
+            return Err(Error::WorktreeNotFound(worktree_path.to_path_buf()));
         }
 
         let path_str = worktree_path
             .to_str()
-            .ok_or_else(|| Error::InvalidUtf8Path(worktree_path.clone()))?;
+            .ok_or_else(|| Error::InvalidUtf8Path(worktree_path.to_path_buf()))?;
         self.run_git_checked(
             &[WORKTREE, "remove", "--force", path_str],
             "git worktree remove",
@@ -220,6 +242,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_worktree_path_with_parent_dir() {
+        let repo_root = PathBuf::from("/home/user/project");
+        let manager =
+            WorktreeManager::new(&repo_root).with_parent_dir(PathBuf::from("/tmp/agents"));
+
+        assert_eq!(manager.parent_dir(), Path::new("/tmp/agents"));
+        assert_eq!(
+            manager.worktree_path("7"),
+            PathBuf::from("/tmp/agents/project-wta-7")
+        );
+    }
+
     #[test]
     fn test_worktree_info_equality() {
         let info1 = WorktreeInfo {
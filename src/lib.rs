@@ -1,10 +1,17 @@
 pub mod cli;
+pub mod config;
+pub mod daemon;
+pub mod duration;
 pub mod editor;
 pub mod error;
 pub mod git;
+pub mod manifest;
+pub mod merge_queue;
 pub mod orchestrator;
+pub mod plan;
 pub mod provider;
 pub mod tmux;
+pub mod watcher;
 pub mod web;
 
 pub use error::{Error, Result};
@@ -1,8 +1,13 @@
 use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::time::Duration;
 use tracing_subscriber::{fmt, EnvFilter};
 use worktree_agent::cli;
+use worktree_agent::cli::config::ConfigCommands;
+use worktree_agent::cli::daemon::DaemonCommands;
+use worktree_agent::cli::report::ReportCommands;
 use worktree_agent::cli::worktree::WorktreeCommands;
-use worktree_agent::orchestrator::{AgentStatus, MergeStrategy};
+use worktree_agent::orchestrator::{AgentStatus, MergeStrategy, ReportStatus};
 use worktree_agent::Provider;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
@@ -33,37 +38,103 @@ enum Commands {
         #[arg(short, long, value_name = "CMD", num_args = 0..=1, default_missing_value = "")]
         editor: Option<String>,
 
+        /// Launch every task in a manifest (.jsonl, or .toml with [[tasks]] entries)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["task", "editor", "branch", "code", "depends_on", "providers", "replicas"])]
+        from: Option<PathBuf>,
+
         /// Branch name (auto-generated if not provided)
         #[arg(short, long)]
         branch: Option<String>,
 
-        /// Base branch to fork from (default: current branch)
+        /// Base branch to fork from (default: config or current branch)
         #[arg(long)]
         base: Option<String>,
 
-        /// AI provider to use (claude, codex, gemini, opencode)
-        #[arg(short, long, value_enum, default_value = "claude")]
-        provider: Provider,
+        /// AI provider to use (claude, codex, gemini, opencode) [default: config or claude]
+        #[arg(short, long, value_enum)]
+        provider: Option<Provider>,
+
+        /// Run the task once per provider, as an attempt group (e.g. claude,codex,gemini)
+        #[arg(long, value_enum, value_delimiter = ',', conflicts_with_all = ["provider", "branch", "code"])]
+        providers: Vec<Provider>,
+
+        /// Run the task this many times (per provider), as an attempt group
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with_all = ["branch", "code"])]
+        replicas: Option<u32>,
 
         /// Open VS Code in the worktree directory
         #[arg(long)]
         code: bool,
 
-        /// Dangerously skip all permission prompts (Claude only)
+        /// Queue the agent until these agents complete, starting from the first one's branch
+        #[arg(long, value_name = "ID", value_delimiter = ',')]
+        depends_on: Vec<String>,
+
+        /// Stop the agent after running this long, e.g. 45m or 1h30m [default: config or none]
+        #[arg(long, value_name = "DURATION", value_parser = worktree_agent::duration::parse_duration)]
+        timeout: Option<Duration>,
+
+        /// Have the agent propose a plan and wait for 'wta approve' before editing
         #[arg(long)]
+        approve_plan: bool,
+
+        /// Dangerously skip all permission prompts (Claude only) [default: config or false]
+        #[arg(long, overrides_with = "no_dangerously_allow_all")]
         dangerously_allow_all: bool,
 
-        /// Enable Edit and Read tool permissions for Claude (allows file editing)
-        #[arg(long)]
+        /// Keep permission prompts even if the config skips them
+        #[arg(long, overrides_with = "dangerously_allow_all")]
+        no_dangerously_allow_all: bool,
+
+        /// Enable Edit and Read tool permissions for Claude (allows file editing) [default: config or false]
+        #[arg(long, overrides_with = "no_enable_edits")]
         enable_edits: bool,
 
+        /// Don't enable Edit and Read tool permissions even if the config does
+        #[arg(long, overrides_with = "enable_edits")]
+        no_enable_edits: bool,
+
         /// Extra arguments to pass to the AI provider
         #[arg(last = true)]
         provider_args: Vec<String>,
     },
 
+    /// Split a large task into subtasks, edit the plan, then launch it as a batch
+    Plan {
+        /// The task to split into subtasks
+        task: String,
+
+        /// AI provider to plan with (claude, codex, gemini), also used for the agents [default: config or claude]
+        #[arg(short, long, value_enum)]
+        provider: Option<Provider>,
+
+        /// Base branch for subtasks without dependencies (default: config or current branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Editor command to review the plan with [default: $VISUAL, $EDITOR, git core.editor or vi]
+        #[arg(short, long, value_name = "CMD")]
+        editor: Option<String>,
+
+        /// Launch the plan as proposed, without opening an editor
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Stop each agent after running this long, e.g. 45m or 1h30m [default: config or none]
+        #[arg(long, value_name = "DURATION", value_parser = worktree_agent::duration::parse_duration)]
+        timeout: Option<Duration>,
+    },
+
     /// List all agents
-    List,
+    List {
+        /// Keep watching and redraw as agent statuses change
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Also show the files changed and summary each agent reported
+        #[arg(long)]
+        wide: bool,
+    },
 
     /// Get status and output of an agent
     Status {
@@ -75,6 +146,142 @@ enum Commands {
         lines: usize,
     },
 
+    /// Record the result of the agent whose worktree you are in (run by agents)
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Report {
+        #[command(subcommand)]
+        command: Option<ReportCommands>,
+
+        /// Outcome of the task
+        #[arg(long, value_enum, required = true)]
+        status: Option<ReportStatus>,
+
+        /// Brief description of what was done
+        #[arg(long)]
+        summary: Option<String>,
+
+        /// Files changed (comma-separated or repeated)
+        #[arg(long, value_delimiter = ',')]
+        files: Vec<String>,
+
+        /// What went wrong, for a failed task
+        #[arg(long)]
+        error: Option<String>,
+    },
+
+    /// Run a failed or timed-out agent again under the same ID and branch
+    Retry {
+        /// Agent ID
+        id: String,
+
+        /// Text added to the end of the task
+        #[arg(long, value_name = "TEXT")]
+        task_append: Option<String>,
+
+        /// Use another provider for this attempt
+        #[arg(short, long, value_enum)]
+        provider: Option<Provider>,
+
+        /// Reset the branch to its base instead of continuing the previous work
+        #[arg(long)]
+        fresh: bool,
+    },
+
+    /// Give a finished agent a follow-up task on its branch
+    Continue {
+        /// Agent ID
+        id: String,
+
+        /// The follow-up task
+        #[arg(short, long)]
+        task: String,
+    },
+
+    /// Ask the user a question and wait for the answer (run by agents from their worktree)
+    Ask {
+        /// The question
+        question: String,
+
+        /// Give up waiting for the answer after this long, e.g. 30m or 2h
+        #[arg(long, value_name = "DURATION", value_parser = worktree_agent::duration::parse_duration, default_value = "1h")]
+        timeout: Duration,
+    },
+
+    /// Answer a question an agent asked with 'wta ask'
+    Answer {
+        /// Agent ID
+        id: String,
+
+        /// The answer
+        reply: String,
+
+        /// Question number [default: the oldest open question]
+        #[arg(short, long, value_name = "N")]
+        question: Option<u32>,
+    },
+
+    /// Approve the plan of an agent awaiting approval and let it implement it
+    Approve {
+        /// Agent ID
+        id: String,
+
+        /// Edit the plan before sending it back, optionally with this editor command
+        #[arg(short, long, value_name = "CMD", num_args = 0..=1, default_missing_value = "")]
+        edit: Option<String>,
+    },
+
+    /// Reject the plan of an agent awaiting approval and end it
+    Reject {
+        /// Agent ID
+        id: String,
+
+        /// Why the plan was rejected, kept as the agent's error
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
+
+    /// Launch a new agent from another agent's branch
+    Fork {
+        /// ID of the agent to fork
+        id: String,
+
+        /// The task for the new agent
+        #[arg(short, long)]
+        task: String,
+
+        /// Branch name (auto-generated if not provided)
+        #[arg(short, long)]
+        branch: Option<String>,
+
+        /// AI provider to use [default: the forked agent's]
+        #[arg(short, long, value_enum)]
+        provider: Option<Provider>,
+    },
+
+    /// Launch a read-only agent that reviews a finished agent's changes
+    Review {
+        /// ID of the agent to review
+        id: String,
+
+        /// AI provider for the reviewer [default: config or claude]
+        #[arg(short, long, value_enum)]
+        provider: Option<Provider>,
+    },
+
+    /// Send follow-up instructions to a running agent
+    Send {
+        /// Agent ID
+        id: String,
+
+        /// Text to send
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        message: Option<String>,
+
+        /// Read the text from a file (- for stdin)
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+
     /// Attach to an agent's tmux window
     Attach {
         /// Agent ID
@@ -88,19 +295,46 @@ enum Commands {
     /// Merge agent's work back to base branch
     Merge {
         /// Agent ID
-        id: String,
+        #[arg(required_unless_present = "queue", conflicts_with = "queue")]
+        id: Option<String>,
 
         /// Target branch to merge into (default: auto-detect main/master)
         #[arg(long)]
         target: Option<String>,
 
-        /// Merge strategy
-        #[arg(long, value_enum, default_value = "merge")]
-        strategy: MergeStrategy,
+        /// Merge a forked agent into the branch of the agent it was forked from
+        #[arg(long, conflicts_with = "target")]
+        into_parent: bool,
+
+        /// Merge strategy [default: config or merge]
+        #[arg(long, value_enum)]
+        strategy: Option<MergeStrategy>,
 
         /// Force merge even if agent status is unknown
         #[arg(short, long)]
         force: bool,
+
+        /// Only check whether the branch merges cleanly and show the conflicts
+        #[arg(long, conflicts_with = "force")]
+        dry_run: bool,
+
+        /// Fast-forward the target where it is checked out, such as your main
+        /// tree, instead of refusing [default: config or false]
+        #[arg(
+            long,
+            overrides_with = "no_update_checkout",
+            conflicts_with = "dry_run"
+        )]
+        update_checkout: bool,
+
+        /// Refuse to move a checked-out target even if the config allows it
+        #[arg(long, overrides_with = "update_checkout", conflicts_with = "dry_run")]
+        no_update_checkout: bool,
+
+        /// Land these agents one at a time: rebase each onto the target, run
+        /// check_command if configured, and merge it only if that passes
+        #[arg(long, num_args = 1.., value_name = "ID", conflicts_with_all = ["into_parent", "force", "dry_run"])]
+        queue: Vec<String>,
     },
 
     /// Create a GitHub PR for agent's work
@@ -131,6 +365,42 @@ enum Commands {
         viewer: DiffViewer,
     },
 
+    /// Show active agents whose branches change the same files
+    Overlaps,
+
+    /// Merge several agents into a new branch and worktree to test them together
+    Integrate {
+        /// Agent IDs, merged in this order
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Name of the integration branch to create
+        #[arg(short, long)]
+        branch: String,
+
+        /// Branch to start from (default: auto-detect main/master)
+        #[arg(long)]
+        base: Option<String>,
+    },
+
+    /// Compare the agents of an attempt group side by side
+    Compare {
+        /// Attempt group ID, e.g. g1
+        group: String,
+
+        /// Run check_command in each finished agent's worktree first
+        #[arg(long)]
+        check: bool,
+
+        /// Keep this agent and remove the others, with their worktrees and branches
+        #[arg(long, value_name = "ID", conflicts_with = "check")]
+        keep: Option<String>,
+
+        /// With --keep, also remove attempts that are still running
+        #[arg(short, long, requires = "keep")]
+        force: bool,
+    },
+
     /// Remove agent, kill window, and cleanup worktree
     Remove {
         /// Agent ID
@@ -151,7 +421,7 @@ enum Commands {
         #[arg(short, long)]
         all: bool,
 
-        /// Only prune agents with this status (merged, completed, failed, running)
+        /// Only prune agents with this status (merged, completed, failed, timed-out, running, queued)
         #[arg(short, long, value_enum)]
         status: Option<AgentStatus>,
 
@@ -172,6 +442,12 @@ enum Commands {
         name: String,
     },
 
+    /// Inspect configuration (.wta.toml and ~/.config/wta/config.toml)
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
     /// Print shell function for wta integration
     Init,
 
@@ -181,11 +457,17 @@ enum Commands {
     /// Install Claude Code skill for wta orchestration
     ClaudeSkill,
 
+    /// Supervise agents in the background: status polling, queue and notifications
+    Daemon {
+        #[command(subcommand)]
+        command: DaemonCommands,
+    },
+
     /// Start the web dashboard
     Dashboard {
-        /// Port to listen on
-        #[arg(short, long, default_value = "3847")]
-        port: u16,
+        /// Port to listen on [default: config or 3847]
+        #[arg(short, long)]
+        port: Option<u16>,
 
         /// Open browser automatically
         #[arg(long)]
@@ -204,40 +486,153 @@ async fn main() -> anyhow::Result<()> {
         Commands::Launch {
             task,
             editor,
+            from,
             branch,
             base,
             provider,
+            providers,
+            replicas,
             code,
+            depends_on,
+            timeout,
+            approve_plan,
             dangerously_allow_all,
+            no_dangerously_allow_all,
             enable_edits,
+            no_enable_edits,
             provider_args,
         } => {
             let options = cli::launch::LaunchOptions {
                 task,
                 editor,
+                from,
                 branch,
                 base,
                 provider,
+                providers,
+                replicas,
                 code,
-                dangerously_allow_all,
-                enable_edits,
+                dangerously_allow_all: cli::flag(dangerously_allow_all, no_dangerously_allow_all),
+                enable_edits: cli::flag(enable_edits, no_enable_edits),
                 provider_args,
+                depends_on,
+                timeout,
+                approve_plan,
             };
             cli::launch::run(options).await?
         }
 
-        Commands::List => cli::list::run().await?,
+        Commands::Plan {
+            task,
+            provider,
+            base,
+            editor,
+            yes,
+            timeout,
+        } => {
+            let options = cli::plan::PlanOptions {
+                task,
+                provider,
+                base,
+                editor,
+                yes,
+                timeout,
+            };
+            cli::plan::run(options).await?
+        }
+
+        Commands::List { watch, wide } => cli::list::run(watch, wide).await?,
 
         Commands::Status { id, lines } => cli::status::run(id, lines).await?,
 
+        Commands::Report {
+            command: Some(ReportCommands::Progress { message, percent }),
+            ..
+        } => cli::report::run_progress(message, percent).await?,
+
+        Commands::Report {
+            command:
+                Some(ReportCommands::Finding {
+                    message,
+                    severity,
+                    file,
+                    line,
+                }),
+            ..
+        } => cli::report::run_finding(message, severity, file, line).await?,
+
+        Commands::Report {
+            command: Some(ReportCommands::Plan { plan, file }),
+            ..
+        } => cli::report::run_plan(plan, file).await?,
+
+        Commands::Report {
+            command: None,
+            status,
+            summary,
+            files,
+            error,
+        } => {
+            // clap requires --status when no subcommand is given
+            let status = status.expect("--status is required");
+            cli::report::run(status, summary, files, error).await?
+        }
+
+        Commands::Retry {
+            id,
+            task_append,
+            provider,
+            fresh,
+        } => cli::retry::run(id, task_append, provider, fresh).await?,
+
+        Commands::Continue { id, task } => cli::continue_task::run(id, task).await?,
+
+        Commands::Ask { question, timeout } => cli::ask::run(question, timeout).await?,
+
+        Commands::Answer {
+            id,
+            reply,
+            question,
+        } => cli::answer::run(id, reply, question).await?,
+
+        Commands::Approve { id, edit } => cli::approve::run(id, edit).await?,
+
+        Commands::Reject { id, reason } => cli::reject::run(id, reason).await?,
+
+        Commands::Fork {
+            id,
+            task,
+            branch,
+            provider,
+        } => cli::fork::run(id, task, branch, provider).await?,
+
+        Commands::Review { id, provider } => cli::review::run(id, provider).await?,
+
+        Commands::Send { id, message, file } => cli::send::run(id, message, file).await?,
+
         Commands::Attach { id, code } => cli::attach::run(id, code).await?,
 
         Commands::Merge {
             id,
             target,
+            into_parent,
             strategy,
             force,
-        } => cli::merge::run(id, target, strategy, force).await?,
+            dry_run,
+            update_checkout,
+            no_update_checkout,
+            queue,
+        } => {
+            let update_checkout = cli::flag(update_checkout, no_update_checkout);
+            match id {
+                None => cli::merge::run_queue(queue, target, strategy, update_checkout).await?,
+                Some(id) if dry_run => cli::merge::run_dry(id, target, into_parent, strategy)?,
+                Some(id) => {
+                    cli::merge::run(id, target, into_parent, strategy, force, update_checkout)
+                        .await?
+                }
+            }
+        }
 
         Commands::Pr {
             id,
@@ -248,6 +643,17 @@ async fn main() -> anyhow::Result<()> {
 
         Commands::Diff { id, viewer } => cli::diff::run(id, viewer == DiffViewer::Git).await?,
 
+        Commands::Compare {
+            group,
+            check,
+            keep,
+            force,
+        } => cli::compare::run(group, check, keep, force).await?,
+
+        Commands::Overlaps => cli::overlaps::run().await?,
+
+        Commands::Integrate { ids, branch, base } => cli::integrate::run(ids, branch, base).await?,
+
         Commands::Remove { id, force, delete } => cli::remove::run(id, force, delete).await?,
 
         Commands::Prune {
@@ -260,15 +666,17 @@ async fn main() -> anyhow::Result<()> {
 
         Commands::Switch { name } => cli::worktree::run(WorktreeCommands::Switch { name }).await?,
 
+        Commands::Config { command } => cli::config::run(command).await?,
+
         Commands::Init => cli::init::run().await?,
 
         Commands::Quickstart => cli::quickstart::run().await?,
 
         Commands::ClaudeSkill => cli::claude_skill::run().await?,
 
-        Commands::Dashboard { port, open } => {
-            worktree_agent::web::run_server(Some(port), open).await?
-        }
+        Commands::Daemon { command } => cli::daemon::run(command).await?,
+
+        Commands::Dashboard { port, open } => worktree_agent::web::run_server(port, open).await?,
     }
 
     Ok(())
@@ -0,0 +1,299 @@
+use crate::error::{Error, Result};
+use crate::provider::Provider;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+
+/// A single task in a launch manifest. Unset fields fall back to the
+/// `wta launch` flags and then to the config.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskEntry {
+    /// Manifest-local name other entries can reference in `depends_on`
+    pub id: Option<String>,
+    pub task: String,
+    /// Entry ids (or IDs of existing agents) that must complete first
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    pub branch: Option<String>,
+    pub base: Option<String>,
+    pub provider: Option<Provider>,
+    #[serde(default)]
+    pub provider_args: Vec<String>,
+    #[serde(default, deserialize_with = "crate::duration::deserialize_optional")]
+    pub timeout: Option<Duration>,
+}
+
+/// TOML manifests list their entries as `[[tasks]]` tables
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlManifest {
+    #[serde(default)]
+    tasks: Vec<TaskEntry>,
+}
+
+/// Load a task manifest. Files ending in `.toml` are parsed as TOML,
+/// anything else as JSON Lines (one task object per line).
+pub fn load(path: &Path) -> Result<Vec<TaskEntry>> {
+    let content = std::fs::read_to_string(path)?;
+    let is_toml = path.extension().and_then(|e| e.to_str()) == Some("toml");
+
+    parse(&content, is_toml).map_err(|message| Error::InvalidManifest {
+        path: path.to_path_buf(),
+        message,
+    })
+}
+
+/// Parse manifest content, rejecting empty manifests and duplicate task ids
+pub fn parse(content: &str, is_toml: bool) -> std::result::Result<Vec<TaskEntry>, String> {
+    let entries = if is_toml {
+        parse_toml(content)
+    } else {
+        parse_jsonl(content)
+    }?;
+
+    if entries.is_empty() {
+        return Err("no tasks found".to_string());
+    }
+
+    let mut ids = HashSet::new();
+    if let Some(id) = entries
+        .iter()
+        .filter_map(|e| e.id.as_deref())
+        .find(|id| !ids.insert(*id))
+    {
+        return Err(format!("duplicate task id '{id}'"));
+    }
+
+    Ok(entries)
+}
+
+/// Order entries so that every entry comes after the entries it depends on.
+/// Dependencies that do not name a manifest entry are left for the caller to
+/// resolve against existing agents. Input order is kept where possible.
+pub fn launch_order(entries: &[TaskEntry]) -> Result<Vec<usize>> {
+    let index_by_id: HashMap<&str, usize> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| e.id.as_deref().map(|id| (id, i)))
+        .collect();
+
+    let deps: Vec<Vec<usize>> = entries
+        .iter()
+        .map(|e| {
+            e.depends_on
+                .iter()
+                .filter_map(|d| index_by_id.get(d.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(entries.len());
+    let mut placed = HashSet::new();
+    while order.len() < entries.len() {
+        let next = (0..entries.len())
+            .find(|i| !placed.contains(i) && deps[*i].iter().all(|d| placed.contains(d)));
+
+        match next {
+            Some(i) => {
+                placed.insert(i);
+                order.push(i);
+            }
+            None => {
+                // Everything left is on, or downstream of, a cycle
+                let remaining = (0..entries.len())
+                    .filter(|i| !placed.contains(i))
+                    .map(|i| {
+                        entries[i]
+                            .id
+                            .clone()
+                            .unwrap_or_else(|| format!("#{}", i + 1))
+                    })
+                    .collect();
+                return Err(Error::DependencyCycle(remaining));
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+fn parse_toml(content: &str) -> std::result::Result<Vec<TaskEntry>, String> {
+    toml::from_str::<TomlManifest>(content)
+        .map(|m| m.tasks)
+        .map_err(|e| e.message().to_string())
+}
+
+fn parse_jsonl(content: &str) -> std::result::Result<Vec<TaskEntry>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_jsonl_entries() {
+        let content = r#"{"task": "Write the API", "branch": "feature/api"}
+
+{"task": "Write the client", "provider": "codex", "provider_args": ["--model", "o3"]}
+"#;
+        let entries = parse_jsonl(content).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].task, "Write the API");
+        assert_eq!(entries[0].branch.as_deref(), Some("feature/api"));
+        assert!(entries[0].provider.is_none());
+        assert_eq!(entries[1].provider, Some(Provider::Codex));
+        assert_eq!(entries[1].provider_args, vec!["--model", "o3"]);
+    }
+
+    #[test]
+    fn test_parse_jsonl_reports_line_number() {
+        let content = "{\"task\": \"ok\"}\n{\"tsk\": \"typo\"}\n";
+        let err = parse_jsonl(content).unwrap_err();
+        assert!(err.starts_with("line 2:"));
+    }
+
+    #[test]
+    fn test_parse_toml_entries() {
+        let content = r#"
+[[tasks]]
+task = "Write the API"
+base = "develop"
+
+[[tasks]]
+task = "Write docs"
+provider = "gemini"
+timeout = "45m"
+"#;
+        let entries = parse_toml(content).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].base.as_deref(), Some("develop"));
+        assert!(entries[0].timeout.is_none());
+        assert_eq!(entries[1].provider, Some(Provider::Gemini));
+        assert_eq!(entries[1].timeout, Some(Duration::from_secs(45 * 60)));
+    }
+
+    fn entry(id: &str, depends_on: &[&str]) -> TaskEntry {
+        TaskEntry {
+            id: Some(id.to_string()),
+            task: format!("Task {id}"),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            branch: None,
+            base: None,
+            provider: None,
+            provider_args: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_jsonl_dependencies() {
+        let content = r#"{"id": "api", "task": "Write the API"}
+{"id": "client", "task": "Write the client", "depends_on": ["api"]}
+"#;
+        let entries = parse_jsonl(content).unwrap();
+        assert_eq!(entries[0].id.as_deref(), Some("api"));
+        assert!(entries[0].depends_on.is_empty());
+        assert_eq!(entries[1].depends_on, vec!["api"]);
+    }
+
+    #[test]
+    fn test_launch_order_keeps_input_order_without_dependencies() {
+        let entries = vec![entry("a", &[]), entry("b", &[]), entry("c", &[])];
+        assert_eq!(launch_order(&entries).unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_launch_order_puts_parents_first() {
+        let entries = vec![
+            entry("client", &["api"]),
+            entry("docs", &["client", "api"]),
+            entry("api", &[]),
+        ];
+        assert_eq!(launch_order(&entries).unwrap(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_launch_order_ignores_external_dependencies() {
+        // "7" is not a manifest entry; it refers to an existing agent
+        let entries = vec![entry("a", &["7"]), entry("b", &["a"])];
+        assert_eq!(launch_order(&entries).unwrap(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_launch_order_rejects_cycle() {
+        let entries = vec![
+            entry("a", &["c"]),
+            entry("b", &["a"]),
+            entry("c", &["b"]),
+            entry("d", &[]),
+        ];
+        match launch_order(&entries) {
+            Err(Error::DependencyCycle(ids)) => assert_eq!(ids, vec!["a", "b", "c"]),
+            other => panic!("Expected DependencyCycle, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_launch_order_rejects_self_dependency() {
+        let entries = vec![entry("a", &["a"])];
+        assert!(matches!(
+            launch_order(&entries),
+            Err(Error::DependencyCycle(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_rejects_duplicate_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tasks.jsonl");
+        std::fs::write(
+            &path,
+            "{\"id\": \"a\", \"task\": \"One\"}\n{\"id\": \"a\", \"task\": \"Two\"}\n",
+        )
+        .unwrap();
+
+        match load(&path) {
+            Err(Error::InvalidManifest { message, .. }) => {
+                assert_eq!(message, "duplicate task id 'a'")
+            }
+            other => panic!("Expected InvalidManifest, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_load_dispatches_on_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        let toml_path = temp_dir.path().join("tasks.toml");
+        let jsonl_path = temp_dir.path().join("tasks.jsonl");
+        std::fs::write(&toml_path, "[[tasks]]\ntask = \"From TOML\"\n").unwrap();
+        std::fs::write(&jsonl_path, "{\"task\": \"From JSONL\"}\n").unwrap();
+
+        assert_eq!(load(&toml_path).unwrap()[0].task, "From TOML");
+        assert_eq!(load(&jsonl_path).unwrap()[0].task, "From JSONL");
+    }
+
+    #[test]
+    fn test_load_rejects_empty_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tasks.jsonl");
+        std::fs::write(&path, "\n\n").unwrap();
+
+        match load(&path) {
+            Err(Error::InvalidManifest { message, .. }) => {
+                assert_eq!(message, "no tasks found")
+            }
+            other => panic!("Expected InvalidManifest, got: {other:?}"),
+        }
+    }
+}
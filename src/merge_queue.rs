@@ -0,0 +1,316 @@
+use crate::error::{Error, Result};
+use crate::orchestrator::{run_check_command, CheckResult, MergeStrategy, Orchestrator};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Times an agent is rebased and checked again when its target moves while
+/// the check runs
+const MAX_ATTEMPTS: usize = 3;
+/// Results kept by a long-running queue
+const RESULT_HISTORY: usize = 20;
+
+/// How one agent of a merge queue ended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "kebab-case")]
+pub enum Landing {
+    Merged {
+        message: String,
+        check: Option<CheckResult>,
+    },
+    Conflict {
+        files: Vec<PathBuf>,
+    },
+    ChecksFailed {
+        check: CheckResult,
+    },
+    /// Not attempted or stopped by an error, leaving the agent as it was
+    Failed {
+        error: String,
+    },
+    /// Removed from the queue while it was being landed
+    Cancelled,
+}
+
+impl Landing {
+    pub fn is_merged(&self) -> bool {
+        matches!(self, Landing::Merged { .. })
+    }
+}
+
+/// Land one agent: rebase it onto `target` (the default branch if None), run
+/// `check_command` if one is configured, and merge it only if that passes.
+/// `update_checkout` (the config's if None) allows a checked-out target to be
+/// fast-forwarded; without it such a target is refused before the rebase.
+/// The orchestrator is only locked around each step, so a slow check does not
+/// hold up other commands. Setting `cancel` stops the landing before its next
+/// step, killing a running check.
+pub async fn land(
+    orchestrator: &Mutex<Orchestrator>,
+    id: &str,
+    target: Option<String>,
+    strategy: MergeStrategy,
+    update_checkout: Option<bool>,
+    cancel: Arc<AtomicBool>,
+) -> Landing {
+    match try_land(orchestrator, id, target, strategy, update_checkout, cancel).await {
+        Ok(landing) => landing,
+        Err(Error::MergeConflict(files)) => Landing::Conflict { files },
+        Err(Error::CheckCancelled) => Landing::Cancelled,
+        Err(e) => Landing::Failed {
+            error: e.to_string(),
+        },
+    }
+}
+
+async fn try_land(
+    orchestrator: &Mutex<Orchestrator>,
+    id: &str,
+    target: Option<String>,
+    strategy: MergeStrategy,
+    update_checkout: Option<bool>,
+    cancel: Arc<AtomicBool>,
+) -> Result<Landing> {
+    let (target, update_checkout) = {
+        let orchestrator = orchestrator.lock().await;
+        let target = orchestrator.merge_target(target)?;
+        let update_checkout =
+            update_checkout.unwrap_or(orchestrator.config().update_checkout.value);
+        // The merge would be refused after the rebase and the check
+        orchestrator.check_target_checkout(&target, update_checkout)?;
+        (target, update_checkout)
+    };
+
+    for _ in 0..MAX_ATTEMPTS {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(Landing::Cancelled);
+        }
+        let plan = orchestrator.lock().await.prepare_landing(id, &target)?;
+
+        let check = match plan.check_command.clone() {
+            Some(command) => {
+                let (worktree, timeout) = (plan.worktree.clone(), plan.check_timeout);
+                let cancel = cancel.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    run_check_command(&command, &worktree, timeout, &|| {
+                        cancel.load(Ordering::Relaxed)
+                    })
+                })
+                .await
+                .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+                Some(result)
+            }
+            None => None,
+        };
+
+        let mut orchestrator = orchestrator.lock().await;
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(Landing::Cancelled);
+        }
+        if let Some(check) = &check {
+            orchestrator.record_landing_check(id, check.clone())?;
+            if !check.passed {
+                return Ok(Landing::ChecksFailed {
+                    check: check.clone(),
+                });
+            }
+        }
+        // Something else landed meanwhile; what was checked is not what
+        // would be merged
+        if orchestrator.target_moved(&plan)? {
+            continue;
+        }
+
+        let result = orchestrator
+            .merge(id, Some(target.clone()), strategy, false, update_checkout)
+            .await?;
+        return Ok(Landing::Merged {
+            message: result.message,
+            check,
+        });
+    }
+
+    Err(Error::TargetKeptMoving(target))
+}
+
+/// How a queued agent ended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueResult {
+    pub agent: String,
+    #[serde(flatten)]
+    pub landing: Landing,
+}
+
+/// Agents waiting to be landed one at a time, with the results of those
+/// already processed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergeQueue {
+    pub pending: VecDeque<String>,
+    /// Agent being landed now
+    pub current: Option<String>,
+    /// Most recent last
+    pub results: VecDeque<QueueResult>,
+    /// Set to stop landing the current agent
+    #[serde(skip)]
+    cancel: Arc<AtomicBool>,
+}
+
+impl MergeQueue {
+    /// Read a queue saved by `save`. An agent that was being landed is put
+    /// back at the front, to be landed again from the start.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        let mut queue: Self = serde_json::from_str(&content)
+            .map_err(|e| Error::StateCorrupted(format!("{}: {e}", path.display())))?;
+        if let Some(id) = queue.current.take() {
+            queue.pending.push_front(id);
+        }
+        Ok(queue)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Add an agent to the end of the queue, unless it is already in it.
+    /// Returns its position, 0 being the agent landed now.
+    pub fn push(&mut self, id: &str) -> usize {
+        if let Some(position) = self.position(id) {
+            return position;
+        }
+        self.pending.push_back(id.to_string());
+        self.pending.len() - usize::from(self.current.is_none())
+    }
+
+    /// Position of an agent in the queue, 0 being the agent landed now
+    pub fn position(&self, id: &str) -> Option<usize> {
+        if self.current.as_deref() == Some(id) {
+            return Some(0);
+        }
+        let offset = usize::from(self.current.is_some());
+        self.pending
+            .iter()
+            .position(|p| p == id)
+            .map(|i| i + offset)
+    }
+
+    /// Take the next agent to land, with the flag that cancels its landing
+    pub fn start_next(&mut self) -> Option<(String, Arc<AtomicBool>)> {
+        self.current = self.pending.pop_front();
+        self.cancel = Arc::new(AtomicBool::new(false));
+        Some((self.current.clone()?, self.cancel.clone()))
+    }
+
+    /// Take an agent out of the queue, cancelling its landing if it is being
+    /// landed now. Returns false if it was not in the queue.
+    pub fn remove(&mut self, id: &str) -> bool {
+        if self.current.as_deref() == Some(id) {
+            self.cancel.store(true, Ordering::Relaxed);
+            return true;
+        }
+        let before = self.pending.len();
+        self.pending.retain(|p| p != id);
+        self.pending.len() != before
+    }
+
+    /// Stop landing the current agent and put it back at the front of the
+    /// queue, to be landed again when the queue next runs
+    pub fn interrupt(&mut self) {
+        if let Some(id) = self.current.take() {
+            self.cancel.store(true, Ordering::Relaxed);
+            self.pending.push_front(id);
+        }
+    }
+
+    /// Record the result of the agent landed now
+    pub fn finish(&mut self, landing: Landing) {
+        if let Some(id) = self.current.take() {
+            if self.results.len() == RESULT_HISTORY {
+                self.results.pop_front();
+            }
+            self.results.push_back(QueueResult { agent: id, landing });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_queue_positions() {
+        let mut queue = MergeQueue::default();
+        assert_eq!(queue.push("3"), 0);
+        assert_eq!(queue.push("5"), 1);
+        // Queuing an agent again keeps its place
+        assert_eq!(queue.push("3"), 0);
+
+        let (id, _) = queue.start_next().unwrap();
+        assert_eq!(id, "3");
+        assert_eq!(queue.position("3"), Some(0));
+        assert_eq!(queue.position("5"), Some(1));
+        assert_eq!(queue.push("7"), 2);
+
+        queue.finish(Landing::Failed {
+            error: "x".to_string(),
+        });
+        assert_eq!(queue.position("3"), None);
+        assert_eq!(queue.position("5"), Some(0));
+        assert_eq!(queue.results.len(), 1);
+        assert_eq!(queue.start_next().unwrap().0, "5");
+    }
+
+    #[test]
+    fn test_merge_queue_remove_cancels_current() {
+        let mut queue = MergeQueue::default();
+        queue.push("3");
+        queue.push("5");
+        queue.push("7");
+        let (_, cancel) = queue.start_next().unwrap();
+
+        assert!(queue.remove("5"));
+        assert!(!queue.remove("5"));
+        assert_eq!(queue.position("7"), Some(1));
+        assert!(!cancel.load(Ordering::Relaxed));
+
+        assert!(queue.remove("3"));
+        assert!(cancel.load(Ordering::Relaxed));
+        queue.finish(Landing::Cancelled);
+        // A new landing starts uncancelled
+        let (id, cancel) = queue.start_next().unwrap();
+        assert_eq!(id, "7");
+        assert!(!cancel.load(Ordering::Relaxed));
+
+        // An interrupted landing goes back to the front, unrecorded
+        queue.push("9");
+        queue.interrupt();
+        assert!(cancel.load(Ordering::Relaxed));
+        assert_eq!(queue.pending, ["7", "9"]);
+        queue.finish(Landing::Cancelled);
+        assert_eq!(queue.results.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_queue_load_requeues_interrupted_landing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("merge-queue.json");
+        assert!(MergeQueue::load(&path).unwrap().pending.is_empty());
+
+        let mut queue = MergeQueue::default();
+        queue.push("3");
+        queue.push("5");
+        queue.start_next();
+        queue.save(&path).unwrap();
+
+        let queue = MergeQueue::load(&path).unwrap();
+        assert_eq!(queue.pending, ["3", "5"]);
+        assert!(queue.current.is_none());
+    }
+}
@@ -1,31 +1,85 @@
+use super::report::{Finding, ProgressEvent, Question, ReportStatus, StatusReport};
+use super::stall::PaneActivity;
 use crate::provider::Provider;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use clap::ValueEnum;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentStatus {
+    /// Waiting for its dependencies to complete before it is started
+    Queued,
     Running,
+    /// Running, but with no output, commits or heartbeats for a while
+    Stalled,
+    /// Running, but waiting at a permission prompt or question
+    #[serde(rename = "needs-input")]
+    #[value(name = "needs-input")]
+    NeedsInput,
     Completed,
     Failed,
     Merged,
     Conflict,
+    /// Failed `check_command` after being rebased in a merge queue
+    #[serde(rename = "checks-failed")]
+    #[value(name = "checks-failed")]
+    ChecksFailed,
+    /// Stopped after exceeding its timeout; the worktree is kept
+    #[serde(rename = "timed-out")]
+    #[value(name = "timed-out")]
+    TimedOut,
+    /// The agent wrote a status file that could not be parsed
+    #[serde(rename = "invalid-report")]
+    #[value(name = "invalid-report")]
+    InvalidReport,
+    /// Proposed a plan and waits for `wta approve` or `wta reject`
+    #[serde(rename = "awaiting-approval")]
+    #[value(name = "awaiting-approval")]
+    AwaitingApproval,
 }
 
 impl std::fmt::Display for AgentStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            AgentStatus::Queued => write!(f, "queued"),
             AgentStatus::Running => write!(f, "running"),
+            AgentStatus::Stalled => write!(f, "stalled"),
+            AgentStatus::NeedsInput => write!(f, "needs-input"),
             AgentStatus::Completed => write!(f, "completed"),
             AgentStatus::Failed => write!(f, "failed"),
             AgentStatus::Merged => write!(f, "merged"),
             AgentStatus::Conflict => write!(f, "conflict"),
+            AgentStatus::ChecksFailed => write!(f, "checks-failed"),
+            AgentStatus::TimedOut => write!(f, "timed-out"),
+            AgentStatus::InvalidReport => write!(f, "invalid-report"),
+            AgentStatus::AwaitingApproval => write!(f, "awaiting-approval"),
         }
     }
 }
 
+impl AgentStatus {
+    /// Whether the agent's process is still working on its task
+    pub fn is_active(self) -> bool {
+        matches!(
+            self,
+            AgentStatus::Running | AgentStatus::Stalled | AgentStatus::NeedsInput
+        )
+    }
+
+    /// Whether the agent's provider session is still open: active, or paused
+    /// for plan approval or a corrected report. Such agents hold a
+    /// `max_running` slot.
+    pub fn holds_session(self) -> bool {
+        self.is_active()
+            || matches!(
+                self,
+                AgentStatus::AwaitingApproval | AgentStatus::InvalidReport
+            )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct AgentId(pub String);
 
@@ -35,10 +89,75 @@ impl std::fmt::Display for AgentId {
     }
 }
 
+/// Launch settings held back until a queued agent's dependencies complete
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingLaunch {
+    /// Explicit base branch; when unset the first dependency's branch is used
+    pub base: Option<String>,
+    #[serde(default)]
+    pub provider_args: Vec<String>,
+    /// Resume the provider's previous session, for a continued agent
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resume: bool,
+    /// The worktree was reset to the base, for a retry with `--fresh`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fresh: bool,
+}
+
+/// An earlier run of an agent that was retried
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub number: u32,
+    pub task: String,
+    pub provider: Provider,
+    pub status: AgentStatus,
+    pub launched_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<StatusReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_error: Option<String>,
+}
+
+/// Follow-up instructions sent to a running agent with `wta send`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentMessage {
+    pub at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// Result of a `wta review` run on an agent's branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    /// The reviewer agent
+    pub reviewer: AgentId,
+    pub provider: Provider,
+    pub at: DateTime<Utc>,
+    pub status: ReportStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+}
+
+/// Outcome of running `check_command` in an agent's worktree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub command: String,
+    pub passed: bool,
+    pub at: DateTime<Utc>,
+    /// Last lines of the command's combined output
+    pub output: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
     pub id: AgentId,
-    pub task: String,
+    /// The launch task followed by any `wta continue` tasks
+    #[serde(alias = "task", deserialize_with = "string_or_list")]
+    pub tasks: Vec<String>,
     pub branch: String,
     pub base_branch: String,
     pub worktree_path: PathBuf,
@@ -50,6 +169,85 @@ pub struct Agent {
     #[serde(alias = "spawned_at")]
     pub launched_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Agents that must complete before this one is started
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<AgentId>,
+    /// Agent whose branch this one was forked from with `wta fork`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<AgentId>,
+    /// Attempt group of agents launched on the same task with `--providers` or `--replicas`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Agent whose branch this read-only reviewer agent reviews
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_of: Option<AgentId>,
+    /// Launched with `--approve-plan`: the agent proposes a plan and waits
+    /// for approval before editing. Cleared once the plan is approved.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub approve_plan: bool,
+    /// Set while the agent is queued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending: Option<PendingLaunch>,
+    /// Last valid status file written by the agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<StatusReport>,
+    /// Why the status file was rejected, while the status is `InvalidReport`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_error: Option<String>,
+    /// Latest progress update from the agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<ProgressEvent>,
+    /// Questions asked with `wta ask` during the current run, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub questions: Vec<Question>,
+    /// Pane contents at the last stall check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pane: Option<PaneActivity>,
+    /// Last pane lines when the agent was flagged stalled or waiting for input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stall_output: Option<String>,
+    /// Wall-clock limit, counted from `launched_at`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Follow-up instructions sent after launch, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<SentMessage>,
+    /// Arguments the provider was started with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provider_args: Vec<String>,
+    /// Number of the current run, counting retries
+    #[serde(default = "first_attempt")]
+    pub attempts: u32,
+    /// Earlier runs, ended by `wta retry` or `wta continue`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_attempts: Vec<Attempt>,
+    /// Latest `check_command` result for the current run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check: Option<CheckResult>,
+    /// Reviews of this agent's branch by reviewer agents, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviews: Vec<Review>,
+}
+
+fn first_attempt() -> u32 {
+    1
+}
+
+/// State files written before follow-up tasks existed have a single `task`
+fn string_or_list<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tasks {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Tasks::deserialize(deserializer)? {
+        Tasks::One(task) => vec![task],
+        Tasks::Many(tasks) => tasks,
+    })
 }
 
 impl Agent {
@@ -66,7 +264,7 @@ impl Agent {
     ) -> Self {
         Self {
             id,
-            task,
+            tasks: vec![task],
             branch,
             base_branch,
             worktree_path,
@@ -76,9 +274,79 @@ impl Agent {
             provider,
             launched_at: Utc::now(),
             completed_at: None,
+            depends_on: Vec::new(),
+            parent: None,
+            group: None,
+            review_of: None,
+            approve_plan: false,
+            pending: None,
+            report: None,
+            report_error: None,
+            progress: None,
+            questions: Vec::new(),
+            pane: None,
+            stall_output: None,
+            timeout_secs: None,
+            messages: Vec::new(),
+            provider_args: Vec::new(),
+            attempts: first_attempt(),
+            previous_attempts: Vec::new(),
+            check: None,
+            reviews: Vec::new(),
         }
     }
 
+    /// The task the agent was launched with
+    pub fn task(&self) -> &str {
+        self.tasks.first().map(String::as_str).unwrap_or_default()
+    }
+
+    /// The task of the current run: the latest follow-up, or the launch task
+    pub fn current_task(&self) -> &str {
+        self.tasks.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// Snapshot of the current run, kept when the agent is retried
+    pub fn attempt(&self) -> Attempt {
+        Attempt {
+            number: self.attempts,
+            task: self.current_task().to_string(),
+            provider: self.provider,
+            status: self.status,
+            launched_at: self.launched_at,
+            completed_at: self.completed_at,
+            report: self.report.clone(),
+            report_error: self.report_error.clone(),
+        }
+    }
+
+    /// Oldest question the agent is waiting to have answered
+    pub fn open_question(&self) -> Option<&Question> {
+        self.questions.iter().find(|q| q.is_open())
+    }
+
+    /// Whether the agent's proposed plan is waiting for `wta approve` or
+    /// `wta reject`: it is awaiting approval, or it timed out while it was.
+    /// Approving starts a new session, so the timeout ending the old one
+    /// does not lose the plan.
+    pub fn awaits_approval(&self) -> bool {
+        match self.status {
+            AgentStatus::AwaitingApproval => true,
+            AgentStatus::TimedOut => self
+                .report
+                .as_ref()
+                .is_some_and(|r| r.status == ReportStatus::Plan),
+            _ => false,
+        }
+    }
+
+    /// When the agent is stopped if it has not finished, if it has a timeout
+    pub fn deadline(&self) -> Option<DateTime<Utc>> {
+        let secs = i64::try_from(self.timeout_secs?).ok()?;
+        self.launched_at
+            .checked_add_signed(TimeDelta::try_seconds(secs)?)
+    }
+
     #[cfg(test)]
     pub fn create_test_agent(id: u128) -> Self {
         Self::new(
@@ -100,11 +368,70 @@ mod tests {
 
     #[test]
     fn test_agent_status_display() {
+        assert_eq!(AgentStatus::Queued.to_string(), "queued");
         assert_eq!(AgentStatus::Running.to_string(), "running");
+        assert_eq!(AgentStatus::Stalled.to_string(), "stalled");
+        assert_eq!(AgentStatus::NeedsInput.to_string(), "needs-input");
         assert_eq!(AgentStatus::Completed.to_string(), "completed");
         assert_eq!(AgentStatus::Failed.to_string(), "failed");
         assert_eq!(AgentStatus::Merged.to_string(), "merged");
         assert_eq!(AgentStatus::Conflict.to_string(), "conflict");
+        assert_eq!(AgentStatus::ChecksFailed.to_string(), "checks-failed");
+        assert_eq!(AgentStatus::TimedOut.to_string(), "timed-out");
+        assert_eq!(AgentStatus::InvalidReport.to_string(), "invalid-report");
+        assert_eq!(
+            AgentStatus::AwaitingApproval.to_string(),
+            "awaiting-approval"
+        );
+    }
+
+    #[test]
+    fn test_agent_status_is_active() {
+        assert!(AgentStatus::Running.is_active());
+        assert!(AgentStatus::Stalled.is_active());
+        assert!(AgentStatus::NeedsInput.is_active());
+        assert!(!AgentStatus::Queued.is_active());
+        assert!(!AgentStatus::Completed.is_active());
+        assert!(!AgentStatus::InvalidReport.is_active());
+        assert!(!AgentStatus::AwaitingApproval.is_active());
+    }
+
+    #[test]
+    fn test_agent_status_holds_session() {
+        assert!(AgentStatus::Running.holds_session());
+        assert!(AgentStatus::NeedsInput.holds_session());
+        assert!(AgentStatus::AwaitingApproval.holds_session());
+        assert!(AgentStatus::InvalidReport.holds_session());
+        assert!(!AgentStatus::Queued.holds_session());
+        assert!(!AgentStatus::Completed.holds_session());
+        assert!(!AgentStatus::Conflict.holds_session());
+    }
+
+    #[test]
+    fn test_agent_awaits_approval_after_timing_out_with_a_plan() {
+        let mut agent = Agent::create_test_agent(1);
+        agent.status = AgentStatus::AwaitingApproval;
+        assert!(agent.awaits_approval());
+
+        agent.status = AgentStatus::TimedOut;
+        assert!(!agent.awaits_approval());
+        agent.report = Some(StatusReport::parse(r#"{"status": "plan", "plan": "Steps"}"#).unwrap());
+        assert!(agent.awaits_approval());
+
+        agent.status = AgentStatus::Failed;
+        assert!(!agent.awaits_approval());
+    }
+
+    #[test]
+    fn test_agent_deadline_counts_from_launch() {
+        let mut agent = Agent::create_test_agent(1);
+        assert!(agent.deadline().is_none());
+
+        agent.timeout_secs = Some(45 * 60);
+        assert_eq!(
+            agent.deadline(),
+            Some(agent.launched_at + TimeDelta::minutes(45))
+        );
     }
 
     #[test]
@@ -124,7 +451,7 @@ mod tests {
     fn test_agent_new_sets_fields_correctly() {
         let agent = Agent::create_test_agent(1);
         assert_eq!(agent.id.0, "1");
-        assert_eq!(agent.task, "Task 1");
+        assert_eq!(agent.task(), "Task 1");
         assert_eq!(agent.branch, "wta/1");
         assert_eq!(agent.base_branch, "main");
         assert_eq!(agent.worktree_path, PathBuf::from(".worktrees/1"));
@@ -139,6 +466,9 @@ mod tests {
 
         let status: AgentStatus = serde_json::from_str("\"completed\"").unwrap();
         assert_eq!(status, AgentStatus::Completed);
+
+        let json = serde_json::to_string(&AgentStatus::InvalidReport).unwrap();
+        assert_eq!(json, "\"invalid-report\"");
     }
 
     #[test]
@@ -148,8 +478,87 @@ mod tests {
         let deserialized: Agent = serde_json::from_str(&json).unwrap();
 
         assert_eq!(deserialized.id.0, agent.id.0);
-        assert_eq!(deserialized.task, agent.task);
+        assert_eq!(deserialized.tasks, agent.tasks);
         assert_eq!(deserialized.branch, agent.branch);
         assert_eq!(deserialized.status, agent.status);
     }
+
+    #[test]
+    fn test_agent_without_dependencies_deserializes() {
+        // State files written before dependencies existed have no depends_on/pending
+        let mut value = serde_json::to_value(Agent::create_test_agent(1)).unwrap();
+        assert!(value.get("depends_on").is_none());
+        assert!(value.get("pending").is_none());
+        value["status"] = "completed".into();
+
+        let agent: Agent = serde_json::from_value(value).unwrap();
+        assert!(agent.depends_on.is_empty());
+        assert!(agent.pending.is_none());
+    }
+
+    #[test]
+    fn test_queued_agent_serialization_roundtrip() {
+        let mut agent = Agent::create_test_agent(2);
+        agent.status = AgentStatus::Queued;
+        agent.depends_on = vec![AgentId("1".to_string())];
+        agent.pending = Some(PendingLaunch {
+            base: None,
+            provider_args: vec!["--verbose".to_string()],
+            resume: false,
+            fresh: false,
+        });
+
+        let json = serde_json::to_string(&agent).unwrap();
+        let deserialized: Agent = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.status, AgentStatus::Queued);
+        assert_eq!(deserialized.depends_on, agent.depends_on);
+        assert_eq!(deserialized.pending, agent.pending);
+    }
+
+    #[test]
+    fn test_agent_without_attempts_is_first_attempt() {
+        // State files written before retries existed have no attempts
+        let mut value = serde_json::to_value(Agent::create_test_agent(1)).unwrap();
+        value.as_object_mut().unwrap().remove("attempts");
+
+        let agent: Agent = serde_json::from_value(value).unwrap();
+        assert_eq!(agent.attempts, 1);
+        assert!(agent.previous_attempts.is_empty());
+    }
+
+    #[test]
+    fn test_attempt_snapshots_current_run() {
+        let mut agent = Agent::create_test_agent(1);
+        agent.status = AgentStatus::TimedOut;
+        agent.report_error = Some("bad json".to_string());
+
+        let attempt = agent.attempt();
+        assert_eq!(attempt.number, 1);
+        assert_eq!(attempt.task, agent.current_task());
+        assert_eq!(attempt.status, AgentStatus::TimedOut);
+        assert_eq!(attempt.launched_at, agent.launched_at);
+        assert_eq!(attempt.report_error.as_deref(), Some("bad json"));
+    }
+
+    #[test]
+    fn test_agent_with_single_task_deserializes() {
+        // State files written before follow-up tasks stored a single `task`
+        let mut value = serde_json::to_value(Agent::create_test_agent(1)).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("tasks");
+        object.insert("task".to_string(), "Fix login".into());
+
+        let mut agent: Agent = serde_json::from_value(value).unwrap();
+        assert_eq!(agent.tasks, vec!["Fix login"]);
+
+        agent.tasks.push("Address review comments".to_string());
+        assert_eq!(agent.task(), "Fix login");
+        assert_eq!(agent.current_task(), "Address review comments");
+
+        let json = serde_json::to_value(&agent).unwrap();
+        assert!(json.get("task").is_none());
+        let roundtrip: Agent = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip.tasks, agent.tasks);
+    }
 }
@@ -0,0 +1,40 @@
+use super::AgentId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A branch combining several agents' work, created by `wta integrate` to
+/// test them together before any of them lands
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Integration {
+    pub branch: String,
+    pub base_branch: String,
+    pub worktree_path: PathBuf,
+    pub created_at: DateTime<Utc>,
+    /// In the order they were merged
+    pub agents: Vec<IntegratedAgent>,
+}
+
+/// One agent of an integration branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegratedAgent {
+    pub id: AgentId,
+    pub branch: String,
+    /// Files that conflicted with the agents merged before it; when not
+    /// empty, the agent was left out of the integration branch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<PathBuf>,
+}
+
+impl IntegratedAgent {
+    pub fn is_merged(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+impl Integration {
+    /// Agents left out because they conflicted
+    pub fn conflicted(&self) -> impl Iterator<Item = &IntegratedAgent> {
+        self.agents.iter().filter(|a| !a.is_merged())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::Result;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How often a busy lock is tried again
+const RETRY: Duration = Duration::from_millis(10);
+/// A lock older than this was left behind by a process that died holding it
+const STALE: Duration = Duration::from_secs(10);
+
+/// Exclusive lock shared by wta processes, held while a file they all write
+/// is read and written. Released when dropped.
+pub struct FileLock(PathBuf);
+
+impl FileLock {
+    pub fn acquire(path: PathBuf) -> Result<Self> {
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self(path)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = std::fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .is_ok_and(|t| t.elapsed().is_ok_and(|age| age > STALE));
+                    if stale {
+                        let _ = std::fs::remove_file(&path);
+                    } else {
+                        std::thread::sleep(RETRY);
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_lock_takes_over_stale_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.lock");
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_modified(std::time::SystemTime::now() - STALE * 2)
+            .unwrap();
+
+        let lock = FileLock::acquire(path.clone()).unwrap();
+        assert!(path.exists());
+        drop(lock);
+        assert!(!path.exists());
+    }
+}
@@ -1,26 +1,64 @@
 mod agent;
+mod integration;
+mod lock;
+mod overlap;
+mod report;
+mod stall;
 mod state;
 
-pub use agent::{Agent, AgentId, AgentStatus};
+pub use agent::{
+    Agent, AgentId, AgentStatus, Attempt, CheckResult, PendingLaunch, Review, SentMessage,
+};
+pub use integration::{IntegratedAgent, Integration};
+pub use overlap::{format_ranges, FileOverlap, Overlap};
+pub use report::{
+    Finding, ProgressEvent, Question, ReportStatus, Severity, StatusReport, SCHEMA_VERSION,
+};
 pub use state::State;
 
+use overlap::{BranchChanges, ChangedFile};
+use report::QuestionEvent;
+
+use crate::config::Config;
 use crate::error::{Error, Result};
 use crate::git::WorktreeManager;
 use crate::provider::Provider;
 use crate::tmux::TmuxManager;
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 const TMUX_SESSION_PREFIX: &str = "wta";
 const STATE_DIR: &str = ".worktree-agents";
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// Lines of pane output saved when an agent times out
+const TIMEOUT_OUTPUT_LINES: usize = 500;
+/// Lines of `check_command` output kept with the result
+const CHECK_OUTPUT_LINES: usize = 50;
+/// How often a running `check_command` is polled for exit, timeout or cancellation
+const CHECK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// IDs skipped at most because orphaned worktrees hold their paths
+const MAX_ID_RETRIES: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MergeStrategy {
     Merge,
     Rebase,
     Squash,
 }
 
+impl std::fmt::Display for MergeStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeStrategy::Merge => write!(f, "merge"),
+            MergeStrategy::Rebase => write!(f, "rebase"),
+            MergeStrategy::Squash => write!(f, "squash"),
+        }
+    }
+}
+
 /// Filter for selecting which agents to prune
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PruneFilter {
@@ -38,14 +76,319 @@ pub struct LaunchRequest {
     pub base: Option<String>,
     pub provider: Provider,
     pub provider_args: Vec<String>,
+    /// Agents that must complete first; the agent is queued until they do
+    pub depends_on: Vec<AgentId>,
+    /// Stop the agent if it runs longer than this
+    pub timeout: Option<Duration>,
+    /// Attempt group the agent belongs to, from `Orchestrator::new_group`
+    pub group: Option<String>,
+    /// Agent to review; the new agent is a read-only reviewer of its branch
+    pub review_of: Option<AgentId>,
+    /// Have the agent propose a plan and wait for approval before editing
+    pub approve_plan: bool,
+}
+
+/// Changes to make when retrying an agent
+#[derive(Debug, Clone, Default)]
+pub struct RetryRequest {
+    /// Added to the end of the task
+    pub task_append: Option<String>,
+    /// Run the retry with another provider, using its configured arguments
+    pub provider: Option<Provider>,
+    /// Reset the branch to its base instead of continuing from the previous attempt's work
+    pub fresh: bool,
+}
+
+/// Settings for a new agent forked from another agent's branch
+#[derive(Debug, Clone, Default)]
+pub struct ForkRequest {
+    pub task: String,
+    pub branch: Option<String>,
+    /// Run the fork with another provider than the parent, using its configured arguments
+    pub provider: Option<Provider>,
+}
+
+/// Which instructions follow the task in an agent's prompt
+enum Instructions<'a> {
+    /// Do the task, commit and report the result
+    Task,
+    /// Propose a plan and wait for `wta approve`
+    Plan,
+    /// Review this agent's branch without changing anything
+    Review(&'a AgentId),
+}
+
+impl<'a> Instructions<'a> {
+    fn new(review_of: Option<&'a AgentId>, approve_plan: bool) -> Self {
+        match review_of {
+            Some(target) => Instructions::Review(target),
+            None if approve_plan => Instructions::Plan,
+            None => Instructions::Task,
+        }
+    }
+}
+
+/// A status transition observed while refreshing agents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusChange {
+    pub id: AgentId,
+    pub from: AgentStatus,
+    pub to: AgentStatus,
+}
+
+/// Where a queued agent stands with respect to its dependencies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DependencyState {
+    /// Every dependency has completed (or been merged)
+    Ready,
+    /// At least one dependency is still in progress
+    Waiting,
+    /// A dependency failed or no longer exists
+    Blocked,
+}
+
+fn dependency_state(agents: &[&Agent], depends_on: &[AgentId]) -> DependencyState {
+    let mut state = DependencyState::Ready;
+    for dep in depends_on {
+        match agents.iter().find(|a| a.id == *dep).map(|a| a.status) {
+            Some(AgentStatus::Completed | AgentStatus::Merged) => {}
+            status if blocks_dependents(status) => return DependencyState::Blocked,
+            _ => state = DependencyState::Waiting,
+        }
+    }
+    state
+}
+
+/// Whether a dependency can no longer complete: it failed, timed out or no
+/// longer exists
+fn blocks_dependents(status: Option<AgentStatus>) -> bool {
+    matches!(
+        status,
+        Some(AgentStatus::Failed | AgentStatus::TimedOut) | None
+    )
+}
+
+/// IDs of the dependencies that block their dependents, for error messages
+fn blocking_dependencies(agents: &[&Agent], depends_on: &[AgentId]) -> String {
+    let blocking: Vec<&str> = depends_on
+        .iter()
+        .filter(|dep| blocks_dependents(agents.iter().find(|a| a.id == **dep).map(|a| a.status)))
+        .map(|dep| dep.0.as_str())
+        .collect();
+    blocking.join(", ")
+}
+
+/// Task text for an agent's prompt. A continued agent is reminded of its
+/// earlier tasks, and a retried one of how its previous run ended, since the
+/// worktree still holds that work.
+fn prompt_task(agent: &Agent, fresh: bool) -> String {
+    let mut prompt = String::new();
+    if let Some((current, earlier)) = agent.tasks.split_last() {
+        if !earlier.is_empty() {
+            prompt.push_str("Earlier tasks on this branch, whose work may be committed:\n");
+            for task in earlier {
+                prompt.push_str(&format!("- {task}\n"));
+            }
+            prompt.push_str("\nFollow-up task:\n");
+        }
+        prompt.push_str(current);
+    }
+
+    let Some(previous) = agent.previous_attempts.last() else {
+        return prompt;
+    };
+    let outcome = match previous.status {
+        // An approved plan comes back as a follow-up task
+        AgentStatus::Completed | AgentStatus::AwaitingApproval => return prompt,
+        AgentStatus::TimedOut => "was stopped after running out of time".to_string(),
+        AgentStatus::InvalidReport => "wrote a status report that could not be read".to_string(),
+        _ => match previous.report.as_ref().and_then(|r| r.error.as_ref()) {
+            Some(error) => format!("failed: {error}"),
+            None => "failed".to_string(),
+        },
+    };
+    let start = if fresh {
+        "Its work was discarded, so start from scratch."
+    } else {
+        "Check `git log` and `git status` for work already done in this worktree."
+    };
+    format!(
+        "{prompt}\n\nThis is attempt {} on this branch; the previous attempt {outcome}. {start}",
+        agent.attempts
+    )
+}
+
+/// Outcome of a `check_command` that produced `output`, keeping the end of
+/// its combined output
+fn check_result(command: String, output: &std::process::Output) -> CheckResult {
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<&str> = combined.trim_end().lines().collect();
+    let tail = lines[lines.len().saturating_sub(CHECK_OUTPUT_LINES)..].join("\n");
+
+    CheckResult {
+        command,
+        passed: output.status.success(),
+        at: chrono::Utc::now(),
+        output: tail,
+    }
+}
+
+/// Run `check_command` with `sh -c` in `dir`. One still running after
+/// `timeout` is killed, together with the processes it started, and counts
+/// as failed. `cancelled` is polled while it runs; once it returns true the
+/// command is killed the same way and `Error::CheckCancelled` returned.
+pub fn run_check_command(
+    command: &str,
+    dir: &Path,
+    timeout: Duration,
+    cancelled: &dyn Fn() -> bool,
+) -> Result<CheckResult> {
+    use std::io::Read;
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Its own process group, so a timeout also stops what it started
+        .process_group(0)
+        .spawn()?;
+
+    // Drain both pipes while waiting, so a verbose command cannot block on them
+    fn drain(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    }
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = std::time::Instant::now() + timeout;
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let stop = cancelled();
+        if stop || std::time::Instant::now() >= deadline {
+            let _ = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("kill -s KILL -- -{}", child.id()))
+                .status();
+            let status = child.wait()?;
+            if stop {
+                return Err(Error::CheckCancelled);
+            }
+            timed_out = true;
+            break status;
+        }
+        std::thread::sleep(CHECK_POLL_INTERVAL);
+    };
+
+    let output = std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    };
+    let mut result = check_result(command.to_string(), &output);
+    if timed_out {
+        result.passed = false;
+        if !result.output.is_empty() {
+            result.output.push('\n');
+        }
+        result.output.push_str(&format!(
+            "Killed after running for {}",
+            crate::duration::format_duration(timeout)
+        ));
+    }
+    Ok(result)
+}
+
+/// An agent rebased onto its target, ready for its check and merge
+#[derive(Debug, Clone)]
+pub struct LandingPlan {
+    pub target: String,
+    /// Target commit the branch was rebased onto
+    pub target_commit: String,
+    pub worktree: PathBuf,
+    pub check_command: Option<String>,
+    pub check_timeout: Duration,
+}
+
+/// A follow-up message checked against its agent, ready to be pasted into
+/// the agent's pane
+#[derive(Debug, Clone)]
+pub struct MessageDelivery {
+    tmux: TmuxManager,
+    window: String,
+    text: String,
+}
+
+impl MessageDelivery {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Paste the message and submit it. This blocks while the agent takes in
+    /// the paste, so async callers should run it without holding locks.
+    pub fn deliver(&self) -> Result<()> {
+        self.tmux.paste(&self.window, &self.text)
+    }
 }
 
+#[derive(Debug)]
 pub struct MergeResult {
     pub success: bool,
     pub message: String,
     pub conflicts: Vec<PathBuf>,
 }
 
+/// Whether an agent's branch would merge cleanly into a target branch
+#[derive(Debug, Clone, Serialize)]
+pub struct Mergeability {
+    pub branch: String,
+    pub target: String,
+    pub conflicts: Vec<FileConflict>,
+}
+
+impl Mergeability {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// A file that would conflict, with the conflicting hunks if it has text conflicts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileConflict {
+    pub path: PathBuf,
+    /// git's description, such as `CONFLICT (modify/delete): ...`
+    pub reason: Option<String>,
+    pub hunks: Vec<ConflictHunk>,
+}
+
+/// One `<<<<<<<` ... `>>>>>>>` block of a conflicting file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConflictHunk {
+    /// Line of the merged file at which the block starts
+    pub line: usize,
+    /// Lines of the target branch
+    pub ours: String,
+    /// Lines of the agent's branch
+    pub theirs: String,
+}
+
 pub struct PrResult {
     pub url: String,
 }
@@ -55,6 +398,13 @@ pub struct PrSummary {
     pub body: String,
 }
 
+/// Instructions appended to a reviewer agent's prompt in place of the usual ones
+const REVIEW_INSTRUCTIONS: &str = "This is a read-only review: do not edit files, commit, or run commands that change the worktree.\n\nRecord every problem you find from the worktree, one command per finding:\n   wta report finding \"what is wrong and why\" --severity high --file src/lib.rs --line 42\n   (severity is high, medium or low; --file and --line are optional)\n\nIMPORTANT: When the review is done, report it:\n   wta report --status completed --summary \"overall assessment\"\nIf you could not complete the review, run instead:\n   wta report --status failed --error \"what went wrong\"";
+/// Instructions appended to the prompt of an agent launched with `--approve-plan`
+const PLAN_INSTRUCTIONS: &str = "IMPORTANT: Before changing anything, propose a plan and wait for approval. Do not edit files or commit yet.\n\nInvestigate the code, then report your plan from the worktree:\n   wta report plan \"step-by-step plan, with the files you will change\"\n   (or write it to a file outside the worktree and run: wta report plan --file /tmp/plan.md)\nThen stop and wait. The approved plan will be sent back to you as a follow-up task.\nIf you cannot make a plan, run instead:\n   wta report --status failed --error \"what went wrong\"";
+/// Diffs longer than this are cut short in a reviewer's prompt
+const REVIEW_DIFF_MAX_BYTES: usize = 100_000;
+
 const PR_SUMMARY_PROMPT: &str = r#"Generate a GitHub PR title and description from this task. Respond with ONLY a JSON object, no markdown code blocks:
 {"title": "short title under 72 chars", "body": "markdown description with ## Summary section"}
 
@@ -62,33 +412,54 @@ Task: "#;
 
 pub struct Orchestrator {
     state: State,
+    config: Config,
     repo_root: PathBuf,
     worktree_manager: WorktreeManager,
     tmux: TmuxManager,
     tmux_session_name: String,
+    /// Changes of active agents' branches, with the base and branch heads
+    /// they were diffed at
+    branch_changes: HashMap<AgentId, ((git2::Oid, git2::Oid), Vec<ChangedFile>)>,
+    /// When each agent's pane was last captured. Only captures that change
+    /// something are saved, so this throttles the rest across state reloads.
+    pane_checks: HashMap<AgentId, chrono::DateTime<chrono::Utc>>,
 }
 
 impl Orchestrator {
     /// Create a new orchestrator for the current directory
     pub fn new() -> Result<Self> {
-        let repo_root = Self::find_repo_root()?;
+        Self::open(Self::find_repo_root()?)
+    }
+
+    /// Create an orchestrator for the repository at `repo_root`
+    fn open(repo_root: PathBuf) -> Result<Self> {
         let state_dir = repo_root.join(STATE_DIR);
 
         // Ensure state directories exist
         std::fs::create_dir_all(&state_dir)?;
         std::fs::create_dir_all(state_dir.join("status"))?;
+        std::fs::create_dir_all(state_dir.join("progress"))?;
+        std::fs::create_dir_all(state_dir.join("findings"))?;
+        std::fs::create_dir_all(state_dir.join("questions"))?;
 
         let state = State::load_or_create(&state_dir)?;
-        let worktree_manager = WorktreeManager::new(&repo_root);
+        let config = Config::load(&repo_root)?;
+        let mut worktree_manager = WorktreeManager::new(&repo_root);
+        if let Some(dir) = config.worktree_parent_dir(&repo_root) {
+            worktree_manager = worktree_manager.with_parent_dir(dir);
+        }
         let tmux_session_name = Self::generate_session_name(&repo_root);
         let tmux = TmuxManager::new(&tmux_session_name);
 
         Ok(Self {
             state,
+            config,
             repo_root,
             worktree_manager,
             tmux,
             tmux_session_name,
+            branch_changes: HashMap::new(),
+            pane_checks: HashMap::new(),
         })
     }
 
@@ -168,13 +539,28 @@ impl Orchestrator {
     }
 
     pub async fn launch(&mut self, request: LaunchRequest) -> Result<AgentId> {
-        const MAX_ID_RETRIES: u32 = 50;
+        for dep in &request.depends_on {
+            self.get_agent(&dep.0)?;
+        }
+        match dependency_state(&self.state.agents(), &request.depends_on) {
+            DependencyState::Ready if self.has_free_slot() => {}
+            DependencyState::Ready | DependencyState::Waiting => return self.enqueue(request),
+            DependencyState::Blocked => {
+                let blocking = blocking_dependencies(&self.state.agents(), &request.depends_on);
+                return Err(Error::DependencyFailed(blocking));
+            }
+        }
+
+        // Dependent agents build on their first dependency's work
+        let base = request
+            .base
+            .clone()
+            .or_else(|| self.dependency_base(&request.depends_on));
 
         // 1. Generate ID with retry logic for orphaned worktrees
         let (id, branch, base_branch, worktree_path) = 'retry: {
             for _ in 0..MAX_ID_RETRIES {
-                let id = AgentId(self.state.next_id().to_string());
-                self.state.save()?;
+                let id = self.allocate_id()?;
 
                 // 2. Determine branch name and whether it's an existing branch
                 match &request.branch {
@@ -183,7 +569,7 @@ impl Orchestrator {
                     {
                         // Existing branch - check it out directly
                         // Get the proper base branch for merge target
-                        let base_branch = match &request.base {
+                        let base_branch = match &base {
                             Some(b) => b.clone(),
                             None => self.get_current_branch()?,
                         };
@@ -210,7 +596,7 @@ impl Orchestrator {
                             .branch
                             .clone()
                             .unwrap_or_else(|| format!("wta/{}", id.0));
-                        let base_branch = match &request.base {
+                        let base_branch = match &base {
                             Some(b) => b.clone(),
                             None => self.get_current_branch()?,
                         };
@@ -227,6 +613,48 @@ impl Orchestrator {
             return Err(Error::TooManyOrphanedWorktrees(MAX_ID_RETRIES));
         };
 
+        self.start_provider(
+            &id,
+            &worktree_path,
+            &request.task,
+            request.provider,
+            &request.provider_args,
+            Instructions::new(request.review_of.as_ref(), request.approve_plan),
+        )?;
+
+        // 13. Register agent in state
+        let mut agent = Agent::new(
+            id.clone(),
+            request.task,
+            branch,
+            base_branch,
+            worktree_path,
+            self.tmux_session_name.clone(),
+            id.0.clone(),
+            request.provider,
+        );
+        agent.depends_on = request.depends_on;
+        agent.timeout_secs = request.timeout.map(|t| t.as_secs());
+        agent.provider_args = request.provider_args;
+        agent.group = request.group;
+        agent.review_of = request.review_of;
+        agent.approve_plan = request.approve_plan;
+
+        self.state.add_agent(agent)?;
+
+        Ok(id)
+    }
+
+    /// Start the provider for an agent whose worktree is ready
+    fn start_provider(
+        &mut self,
+        id: &AgentId,
+        worktree_path: &Path,
+        task: &str,
+        provider: Provider,
+        provider_args: &[String],
+        instructions: Instructions,
+    ) -> Result<()> {
         // 5. Copy .claude settings from main repo to worktree for permission inheritance
         let main_claude_dir = self.repo_root.join(".claude");
         if main_claude_dir.exists() {
@@ -240,66 +668,771 @@ impl Orchestrator {
         self.tmux.ensure_session()?;
 
         // 7. Create tmux window
-        self.tmux.create_window(&id.0, &worktree_path)?;
-
-        // 8. Build status file path for the agent to write
-        let status_file = self
-            .repo_root
-            .join(STATE_DIR)
-            .join("status")
-            .join(format!("{}.json", id.0));
-
-        // 9. Build command with task and status file instructions
-        let task_with_instructions = format!(
-            "{}\n\n---\nIMPORTANT: When you complete this task:\n1. Commit your changes (do NOT include Co-Authored-By in commits)\n2. Write a JSON status file to: {}\n   Format: {{\"status\": \"completed\"|\"failed\", \"summary\": \"brief description\", \"files_changed\": [\"file1\", \"file2\"], \"error\": null}}",
-            request.task,
-            status_file.display()
-        );
+        self.tmux.create_window(&id.0, worktree_path)?;
+
+        // 8. Build command with task and reporting instructions
+        let task_with_instructions = match instructions {
+            Instructions::Review(target) => format!(
+                "{}\n\n---\n{REVIEW_INSTRUCTIONS}",
+                self.review_prompt(target, task)?
+            ),
+            Instructions::Plan => format!("{task}\n\n---\n{PLAN_INSTRUCTIONS}"),
+            Instructions::Task => format!(
+                "{task}\n\n---\nWhile working, report progress from the worktree every few steps:\n   wta report progress \"what you are doing\" --percent 40\n\nIf you need a decision from the user, such as which library to use, ask and wait for the reply:\n   wta ask \"your question\"\n   It prints the answer. If the command is interrupted first, run it again to keep waiting.\n\nIMPORTANT: When you complete this task:\n1. Commit your changes (do NOT include Co-Authored-By in commits)\n2. Report the result by running this from the worktree:\n   wta report --status completed --summary \"brief description\" --files file1,file2\n   If you could not complete the task, run instead:\n   wta report --status failed --error \"what went wrong\""
+            ),
+        };
 
-        // 10. Write prompt to a file (avoids shell quoting issues with newlines)
+        // 9. Write prompt to a file (avoids shell quoting issues with newlines)
         let prompts_dir = self.repo_root.join(STATE_DIR).join("prompts");
         std::fs::create_dir_all(&prompts_dir)?;
         let prompt_file = prompts_dir.join(format!("{}.txt", id.0));
         std::fs::write(&prompt_file, &task_with_instructions)?;
 
-        // 11. Build provider-specific command
-        let provider_cmd = request.provider.build_command(
-            &worktree_path,
-            &prompt_file,
-            &status_file,
-            &request.provider_args,
-        );
+        // 10. Build provider-specific command
+        let provider_cmd = provider.build_command(worktree_path, &prompt_file, provider_args);
 
-        // 12. Send command to tmux
-        self.tmux.send_keys(&id.0, &provider_cmd)?;
+        // 11. Send command to tmux
+        self.tmux.send_keys(&id.0, &provider_cmd)
+    }
 
-        // 13. Register agent in state
-        let agent = Agent::new(
+    /// Number of agents currently holding a running slot
+    fn running_count(&self) -> usize {
+        self.state
+            .agents()
+            .iter()
+            .filter(|a| a.status.holds_session())
+            .count()
+    }
+
+    /// Whether another agent may start without exceeding `max_running`
+    fn has_free_slot(&self) -> bool {
+        self.config
+            .max_running
+            .value
+            .is_none_or(|max| self.running_count() < max)
+    }
+
+    /// Dependencies of an agent that have not completed yet. A queued agent
+    /// with none left is waiting for a free `max_running` slot.
+    pub fn unfinished_dependencies(&self, id: &str) -> Result<Vec<AgentId>> {
+        let agent = self.get_agent(id)?;
+        Ok(agent
+            .depends_on
+            .iter()
+            .filter(|dep| {
+                !matches!(
+                    self.state.get_agent(&dep.0).map(|a| a.status),
+                    Some(AgentStatus::Completed | AgentStatus::Merged)
+                )
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Take the next agent ID, skipping IDs whose worktree path is taken by
+    /// an orphaned worktree
+    fn allocate_id(&mut self) -> Result<AgentId> {
+        for _ in 0..MAX_ID_RETRIES {
+            let id = AgentId(self.state.next_id()?.to_string());
+            if !self.worktree_manager.worktree_path(&id.0).exists() {
+                return Ok(id);
+            }
+        }
+        Err(Error::TooManyOrphanedWorktrees(MAX_ID_RETRIES))
+    }
+
+    /// Register an agent that waits for its dependencies or a free slot. No
+    /// worktree or tmux window is created until it is started.
+    fn enqueue(&mut self, request: LaunchRequest) -> Result<AgentId> {
+        let id = self.allocate_id()?;
+        let branch = request
+            .branch
+            .clone()
+            .unwrap_or_else(|| format!("wta/{}", id.0));
+        let base_branch = match &request.base {
+            Some(b) => b.clone(),
+            None => match self.dependency_base(&request.depends_on) {
+                Some(b) => b,
+                None => self.get_current_branch()?,
+            },
+        };
+
+        let mut agent = Agent::new(
             id.clone(),
             request.task,
             branch,
             base_branch,
-            worktree_path,
+            self.worktree_manager.worktree_path(&id.0),
             self.tmux_session_name.clone(),
             id.0.clone(),
             request.provider,
         );
+        agent.status = AgentStatus::Queued;
+        agent.depends_on = request.depends_on;
+        agent.timeout_secs = request.timeout.map(|t| t.as_secs());
+        agent.group = request.group;
+        agent.review_of = request.review_of;
+        agent.approve_plan = request.approve_plan;
+        agent.pending = Some(PendingLaunch {
+            base: request.base,
+            provider_args: request.provider_args,
+            resume: false,
+            fresh: false,
+        });
 
         self.state.add_agent(agent)?;
 
         Ok(id)
     }
 
+    /// Branch a dependent agent starts from: its first dependency's branch, or
+    /// that dependency's base once it has been merged (and its branch deleted)
+    fn dependency_base(&self, depends_on: &[AgentId]) -> Option<String> {
+        let parent = self.state.get_agent(&depends_on.first()?.0)?;
+        if parent.status == AgentStatus::Merged {
+            Some(parent.base_branch.clone())
+        } else {
+            Some(parent.branch.clone())
+        }
+    }
+
+    /// Start queued agents whose dependencies have completed, in launch order
+    /// and while `max_running` allows, and fail those whose dependencies
+    /// failed. Returns the IDs of the agents started.
+    pub fn start_ready_agents(&mut self) -> Result<Vec<AgentId>> {
+        // Dependencies always have lower IDs than their dependents, so a single
+        // pass in launch order also cascades failures down the graph
+        let queued: Vec<AgentId> = self
+            .state
+            .agents()
+            .iter()
+            .filter(|a| a.status == AgentStatus::Queued)
+            .map(|a| a.id.clone())
+            .collect();
+
+        let mut started = Vec::new();
+        for id in queued {
+            let agent = self.get_agent(&id.0)?;
+            match dependency_state(&self.state.agents(), &agent.depends_on) {
+                DependencyState::Waiting => {}
+                DependencyState::Ready if !self.has_free_slot() => {}
+                DependencyState::Ready => match self.start_queued(&id) {
+                    Ok(()) => started.push(id),
+                    Err(e) => {
+                        eprintln!("Warning: could not start queued agent {id}: {e}");
+                        self.fail_queued(&id)?;
+                    }
+                },
+                DependencyState::Blocked => self.fail_queued(&id)?,
+            }
+        }
+
+        Ok(started)
+    }
+
+    fn start_queued(&mut self, id: &AgentId) -> Result<()> {
+        let agent = self.get_agent(&id.0)?.clone();
+        let pending = agent.pending.clone().unwrap_or_default();
+
+        let base_branch = match pending.base {
+            Some(b) => b,
+            None => match self.dependency_base(&agent.depends_on) {
+                Some(b) => b,
+                None => self.get_current_branch()?,
+            },
+        };
+
+        let worktree_path = if agent.attempts > 1 && agent.worktree_path.exists() {
+            // Retried and continued agents keep the previous run's worktree
+            agent.worktree_path.clone()
+        } else if self.worktree_manager.branch_exists(&agent.branch)? {
+            self.worktree_manager
+                .checkout_existing(&id.0, &agent.branch)?
+        } else {
+            self.worktree_manager
+                .create(&id.0, &agent.branch, &base_branch)?
+        };
+
+        let provider_args = if pending.resume {
+            [
+                agent.provider.resume_args(&worktree_path),
+                pending.provider_args.clone(),
+            ]
+            .concat()
+        } else {
+            pending.provider_args.clone()
+        };
+        self.start_provider(
+            id,
+            &worktree_path,
+            &prompt_task(&agent, pending.fresh),
+            agent.provider,
+            &provider_args,
+            Instructions::new(agent.review_of.as_ref(), agent.approve_plan),
+        )?;
+
+        let agent = self.get_agent_mut(&id.0)?;
+        agent.status = AgentStatus::Running;
+        agent.base_branch = base_branch;
+        agent.worktree_path = worktree_path;
+        agent.provider_args = pending.provider_args;
+        agent.pending = None;
+        agent.launched_at = chrono::Utc::now();
+        self.state.save()
+    }
+
+    /// Run a failed, timed-out or invalid-report agent again under the same ID
+    /// and branch. The agent is queued, and started straight away unless
+    /// `max_running` is reached.
+    pub fn retry(&mut self, id: &str, request: RetryRequest) -> Result<()> {
+        let agent = self.get_agent(id)?;
+        if !matches!(
+            agent.status,
+            AgentStatus::Failed | AgentStatus::TimedOut | AgentStatus::InvalidReport
+        ) {
+            return Err(Error::CannotRetry(id.to_string(), agent.status.to_string()));
+        }
+        if dependency_state(&self.state.agents(), &agent.depends_on) == DependencyState::Blocked {
+            let blocking = blocking_dependencies(&self.state.agents(), &agent.depends_on);
+            return Err(Error::DependencyFailed(blocking));
+        }
+
+        let provider_args = match request.provider {
+            // Reviewers stay read-only under their new provider
+            Some(provider) if provider != agent.provider && agent.review_of.is_some() => [
+                self.config.provider_args_for(provider),
+                provider.read_only_args(),
+            ]
+            .concat(),
+            Some(provider) if provider != agent.provider => self.config.provider_args_for(provider),
+            _ => agent.provider_args.clone(),
+        };
+        let pending = PendingLaunch {
+            base: Some(agent.base_branch.clone()),
+            provider_args,
+            resume: false,
+            fresh: request.fresh,
+        };
+
+        self.rerun(id, request.fresh, pending, |agent| {
+            if let (Some(append), Some(task)) = (request.task_append, agent.tasks.last_mut()) {
+                *task = format!("{task}\n\n{append}");
+            }
+            if let Some(provider) = request.provider {
+                agent.provider = provider;
+            }
+        })
+    }
+
+    /// Give a finished agent a follow-up task on its branch, in a new provider
+    /// session that resumes the previous conversation where the provider
+    /// supports it. Queued like a retry when `max_running` is reached.
+    pub fn continue_agent(&mut self, id: &str, task: String) -> Result<()> {
+        let agent = self.get_agent(id)?;
+        match agent.status {
+            AgentStatus::Queued => return Err(Error::AgentQueued(id.to_string())),
+            AgentStatus::Merged => return Err(Error::AgentMerged(id.to_string())),
+            status if status.holds_session() => {
+                return Err(Error::AgentStillRunning(id.to_string()))
+            }
+            _ => {}
+        }
+
+        let pending = PendingLaunch {
+            base: Some(agent.base_branch.clone()),
+            provider_args: agent.provider_args.clone(),
+            resume: true,
+            fresh: false,
+        };
+
+        self.rerun(id, false, pending, |agent| agent.tasks.push(task))
+    }
+
+    /// Send an agent's approved plan back to it as a follow-up task, in a new
+    /// provider session like `continue_agent`. `plan` replaces the proposed
+    /// plan, e.g. after editing it.
+    pub fn approve(&mut self, id: &str, plan: Option<String>) -> Result<()> {
+        let proposed = self.proposed_plan(id)?;
+        let plan = plan.unwrap_or(proposed);
+        let agent = self.get_agent(id)?;
+        let pending = PendingLaunch {
+            base: Some(agent.base_branch.clone()),
+            provider_args: agent.provider_args.clone(),
+            resume: true,
+            fresh: false,
+        };
+
+        self.rerun(id, false, pending, |agent| {
+            agent.approve_plan = false;
+            agent.tasks.push(format!(
+                "Your plan was approved. Implement it now:\n\n{plan}"
+            ));
+        })
+    }
+
+    /// End an agent whose plan was not approved. It is marked failed, so it
+    /// can be retried to propose another plan.
+    pub fn reject(&mut self, id: &str, reason: Option<String>) -> Result<()> {
+        self.proposed_plan(id)?;
+        let window = self.get_agent(id)?.tmux_window.clone();
+        let _ = self.tmux.kill_window(&window);
+
+        let agent = self.get_agent_mut(id)?;
+        agent.status = AgentStatus::Failed;
+        agent.completed_at = Some(chrono::Utc::now());
+        if let Some(report) = agent.report.as_mut() {
+            report.error = Some(match reason {
+                Some(reason) => format!("Plan rejected: {reason}"),
+                None => "Plan rejected".to_string(),
+            });
+        }
+        self.state.save()?;
+        self.start_ready_agents()?;
+        Ok(())
+    }
+
+    /// Plan an agent awaiting approval proposed
+    pub fn proposed_plan(&self, id: &str) -> Result<String> {
+        let agent = self.get_agent(id)?;
+        if !agent.awaits_approval() {
+            return Err(Error::NotAwaitingApproval(
+                id.to_string(),
+                agent.status.to_string(),
+            ));
+        }
+        Ok(agent
+            .report
+            .as_ref()
+            .and_then(|r| r.plan.clone())
+            .unwrap_or_default())
+    }
+
+    /// Launch a new agent on a branch created from another agent's branch HEAD,
+    /// linked back to it through `parent`. Only committed work is carried over.
+    pub async fn fork(&mut self, id: &str, request: ForkRequest) -> Result<AgentId> {
+        let parent = self.get_agent(id)?;
+        match parent.status {
+            // A queued agent has no branch yet, a merged one no longer has it
+            AgentStatus::Queued => return Err(Error::AgentQueued(id.to_string())),
+            AgentStatus::Merged => return Err(Error::AgentMerged(id.to_string())),
+            _ => {}
+        }
+
+        let provider = request.provider.unwrap_or(parent.provider);
+        let provider_args = if provider == parent.provider {
+            parent.provider_args.clone()
+        } else {
+            self.config.provider_args_for(provider)
+        };
+        let launch = LaunchRequest {
+            task: request.task,
+            branch: request.branch,
+            base: Some(parent.branch.clone()),
+            provider,
+            provider_args,
+            depends_on: Vec::new(),
+            timeout: parent.timeout_secs.map(Duration::from_secs),
+            group: None,
+            review_of: None,
+            approve_plan: false,
+        };
+
+        let fork_id = self.launch(launch).await?;
+        self.get_agent_mut(&fork_id.0)?.parent = Some(AgentId(id.to_string()));
+        self.state.save()?;
+        Ok(fork_id)
+    }
+
+    /// Branch of the agent `id` was forked from, to merge the fork back into
+    pub fn parent_branch(&self, id: &str) -> Result<String> {
+        let agent = self.get_agent(id)?;
+        let parent_id = agent
+            .parent
+            .as_ref()
+            .ok_or_else(|| Error::NotForked(id.to_string()))?;
+        let parent = self.get_agent(&parent_id.0)?;
+        if parent.status == AgentStatus::Merged {
+            return Err(Error::AgentMerged(parent_id.0.clone()));
+        }
+        Ok(parent.branch.clone())
+    }
+
+    /// Allocate an ID for agents launched together on the same task
+    pub fn new_group(&mut self) -> Result<String> {
+        self.state.next_group_id()
+    }
+
+    /// Agents of an attempt group, in launch order
+    pub fn group(&self, group: &str) -> Result<Vec<&Agent>> {
+        let agents: Vec<&Agent> = self
+            .state
+            .agents()
+            .into_iter()
+            .filter(|a| a.group.as_deref() == Some(group))
+            .collect();
+        if agents.is_empty() {
+            return Err(Error::GroupNotFound(group.to_string()));
+        }
+        Ok(agents)
+    }
+
+    /// Keep one agent of an attempt group and remove the others, with their
+    /// worktrees and branches. Others still running are only removed with
+    /// `force`; otherwise nothing is. Returns the IDs of the removed agents.
+    pub async fn keep_attempt(
+        &mut self,
+        group: &str,
+        keep: &str,
+        force: bool,
+    ) -> Result<Vec<AgentId>> {
+        let kept = self.get_agent(keep)?;
+        if kept.group.as_deref() != Some(group) {
+            return Err(Error::NotInGroup(keep.to_string(), group.to_string()));
+        }
+        let kept = kept.id.clone();
+
+        let others: Vec<&Agent> = self
+            .group(group)?
+            .into_iter()
+            .filter(|a| a.id != kept)
+            .collect();
+        if let Some(running) = others.iter().find(|a| a.status.holds_session()) {
+            if !force {
+                return Err(Error::AgentStillRunning(running.id.0.clone()));
+            }
+        }
+
+        let others: Vec<AgentId> = others.into_iter().map(|a| a.id.clone()).collect();
+        for id in &others {
+            self.remove(&id.0, true, true).await?;
+        }
+        Ok(others)
+    }
+
+    /// Run `check_command` in an agent's worktree and record the result. A
+    /// check running longer than `check_timeout` is killed and fails.
+    pub fn run_check(&mut self, id: &str) -> Result<CheckResult> {
+        let command = self
+            .config
+            .check_command
+            .value
+            .clone()
+            .ok_or(Error::NoCheckCommand)?;
+        let agent = self.get_agent(id)?;
+        if !agent.worktree_path.exists() {
+            return Err(Error::WorktreeNotFound(agent.worktree_path.clone()));
+        }
+
+        let result = run_check_command(
+            &command,
+            &agent.worktree_path,
+            self.config.check_timeout.value,
+            &|| false,
+        )?;
+        self.get_agent_mut(id)?.check = Some(result.clone());
+        self.state.save()?;
+        Ok(result)
+    }
+
+    /// An agent that can be landed from a merge queue: completed, or stopped
+    /// by a conflict or failed check, with its worktree. Its branch is
+    /// rebased in that worktree, so no provider session may still be using it.
+    pub fn check_landable(&self, id: &str) -> Result<&Agent> {
+        let agent = self.get_agent(id)?;
+        match agent.status {
+            AgentStatus::Completed | AgentStatus::Conflict | AgentStatus::ChecksFailed => {}
+            AgentStatus::Queued => return Err(Error::AgentQueued(id.to_string())),
+            AgentStatus::Merged => return Err(Error::AgentMerged(id.to_string())),
+            status if status.holds_session() => {
+                return Err(Error::AgentStillRunning(id.to_string()))
+            }
+            status => return Err(Error::NotLandable(id.to_string(), status.to_string())),
+        }
+        if !agent.worktree_path.exists() {
+            return Err(Error::WorktreeNotFound(agent.worktree_path.clone()));
+        }
+        Ok(agent)
+    }
+
+    /// Rebase a finished agent's branch onto `target` in its worktree, the
+    /// first step of landing it from a merge queue. A conflict aborts the
+    /// rebase and marks the agent Conflict.
+    pub fn prepare_landing(&mut self, id: &str, target: &str) -> Result<LandingPlan> {
+        let worktree = self.check_landable(id)?.worktree_path.clone();
+
+        let target_commit = crate::git::merge::branch_commit(&self.repo_root, target)?;
+        if let Err(e) = crate::git::merge::rebase_worktree(&worktree, target) {
+            if matches!(e, Error::MergeConflict(_)) {
+                self.get_agent_mut(id)?.status = AgentStatus::Conflict;
+                self.state.save()?;
+            }
+            return Err(e);
+        }
+
+        Ok(LandingPlan {
+            target: target.to_string(),
+            target_commit,
+            worktree,
+            check_command: self.config.check_command.value.clone(),
+            check_timeout: self.config.check_timeout.value,
+        })
+    }
+
+    /// Whether the target of a landing moved since its branch was rebased
+    pub fn target_moved(&self, plan: &LandingPlan) -> Result<bool> {
+        Ok(crate::git::merge::branch_commit(&self.repo_root, &plan.target)? != plan.target_commit)
+    }
+
+    /// Record the check of an agent rebased for landing; a failure marks it
+    /// ChecksFailed
+    pub fn record_landing_check(&mut self, id: &str, result: CheckResult) -> Result<()> {
+        let agent = self.get_agent_mut(id)?;
+        if !result.passed {
+            agent.status = AgentStatus::ChecksFailed;
+        }
+        agent.check = Some(result);
+        self.state.save()
+    }
+
+    /// Launch a read-only reviewer agent on a finished agent's branch. Its
+    /// report and findings are attached to the reviewed agent.
+    pub async fn review(&mut self, id: &str, provider: Option<Provider>) -> Result<AgentId> {
+        let target = self.get_agent(id)?;
+        match target.status {
+            AgentStatus::Queued => return Err(Error::AgentQueued(id.to_string())),
+            AgentStatus::Merged => return Err(Error::AgentMerged(id.to_string())),
+            status if status.holds_session() => {
+                return Err(Error::AgentStillRunning(id.to_string()))
+            }
+            _ => {}
+        }
+
+        let provider = provider.unwrap_or(self.config.provider.value);
+        let launch = LaunchRequest {
+            task: format!(
+                "Review the changes of agent {} on branch {}",
+                target.id, target.branch
+            ),
+            branch: None,
+            base: Some(target.branch.clone()),
+            provider,
+            provider_args: [
+                self.config.provider_args_for(provider),
+                provider.read_only_args(),
+            ]
+            .concat(),
+            depends_on: Vec::new(),
+            timeout: self.config.timeout.value,
+            group: None,
+            review_of: Some(target.id.clone()),
+            approve_plan: false,
+        };
+        self.launch(launch).await
+    }
+
+    /// A reviewer's task followed by the reviewed agent's task and its
+    /// `base...branch` diff, the range `wta diff` shows
+    fn review_prompt(&self, target: &AgentId, task: &str) -> Result<String> {
+        let target = self.get_agent(&target.0)?;
+        let range = format!("{}...{}", target.base_branch, target.branch);
+        let mut diff = crate::git::repository::diff(&self.repo_root, &range)?;
+        if diff.len() > REVIEW_DIFF_MAX_BYTES {
+            let mut cut = REVIEW_DIFF_MAX_BYTES;
+            while !diff.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            diff.truncate(cut);
+            diff.push_str(&format!(
+                "\n[diff cut short; run `git diff {range}` for the rest]\n"
+            ));
+        }
+
+        Ok(format!(
+            "{task}\n\nThe agent's task was:\n{}\n\nChanges ({range}):\n```diff\n{diff}```",
+            target.tasks.join("\n\n")
+        ))
+    }
+
+    /// Copy a reviewer agent's report onto the agent it reviewed
+    fn attach_review(&mut self, id: &str) -> Result<()> {
+        let reviewer = self.get_agent(id)?;
+        let (Some(target), Some(report)) = (&reviewer.review_of, &reviewer.report) else {
+            return Ok(());
+        };
+        let target = target.clone();
+        let review = Review {
+            reviewer: reviewer.id.clone(),
+            provider: reviewer.provider,
+            at: chrono::Utc::now(),
+            status: report.status,
+            summary: report.summary.clone(),
+            error: report.error.clone(),
+            findings: report.findings.clone(),
+        };
+
+        // The reviewed agent may have been removed in the meantime
+        if let Some(target) = self.state.get_agent_mut(&target.0) {
+            target.reviews.push(review);
+            self.state.save()?;
+        }
+        Ok(())
+    }
+
+    /// Start a new run of a finished agent in its worktree, keeping the
+    /// current run in `previous_attempts`. `change` amends the agent (task,
+    /// provider) before it is queued.
+    fn rerun(
+        &mut self,
+        id: &str,
+        fresh: bool,
+        pending: PendingLaunch,
+        change: impl FnOnce(&mut Agent),
+    ) -> Result<()> {
+        let agent = self.get_agent(id)?.clone();
+
+        // Completed agents keep their window open, and an agent whose report
+        // was rejected may still be running
+        let _ = self.tmux.kill_window(&agent.tmux_window);
+
+        // Agents that failed while queued never had a worktree
+        let worktree_path = if agent.worktree_path.exists() {
+            agent.worktree_path.clone()
+        } else if self.worktree_manager.branch_exists(&agent.branch)? {
+            self.worktree_manager.checkout_existing(id, &agent.branch)?
+        } else {
+            self.worktree_manager
+                .create(id, &agent.branch, &agent.base_branch)?
+        };
+        if fresh {
+            self.worktree_manager
+                .reset(&worktree_path, &agent.base_branch)?;
+        }
+
+        // The previous run's result must not end the new one
+        let _ = std::fs::remove_file(self.status_file(id));
+        let _ = std::fs::remove_file(self.progress_file(id));
+        let _ = std::fs::remove_file(self.output_file(id));
+        let _ = std::fs::remove_file(self.findings_file(id));
+        let _ = std::fs::remove_file(self.questions_file(id));
+
+        let agent = self.get_agent_mut(id)?;
+        let attempt = agent.attempt();
+        agent.previous_attempts.push(attempt);
+        agent.attempts += 1;
+        change(agent);
+        agent.worktree_path = worktree_path;
+        agent.status = AgentStatus::Queued;
+        agent.pending = Some(pending);
+        agent.completed_at = None;
+        agent.report = None;
+        agent.report_error = None;
+        agent.progress = None;
+        agent.questions = Vec::new();
+        agent.pane = None;
+        agent.stall_output = None;
+        agent.check = None;
+        self.state.save()?;
+
+        self.start_ready_agents()?;
+        Ok(())
+    }
+
+    fn fail_queued(&mut self, id: &AgentId) -> Result<()> {
+        let agent = self.get_agent_mut(&id.0)?;
+        agent.status = AgentStatus::Failed;
+        agent.completed_at = Some(chrono::Utc::now());
+        agent.pending = None;
+        self.state.save()
+    }
+
     pub fn list(&self) -> Vec<&Agent> {
         self.state.agents()
     }
 
-    /// Get the path to the parent directory where worktrees are created as siblings
+    /// Layered project/user configuration for this repository
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Root of the main repository
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Directory holding state.json, prompts and status files
+    pub fn state_dir(&self) -> PathBuf {
+        self.repo_root.join(STATE_DIR)
+    }
+
+    /// PID of the `wta daemon` supervising this repository, if one is alive
+    pub fn daemon_pid(&self) -> Option<u32> {
+        crate::daemon::running_pid(&self.state_dir())
+    }
+
+    /// Re-read state.json, picking up changes made by other wta processes
+    pub fn reload_state(&mut self) -> Result<()> {
+        self.state = State::load_or_create(&self.state_dir())?;
+        Ok(())
+    }
+
+    /// Poll status files and tmux windows of running agents and start queued
+    /// agents that are ready. Returns every status transition observed.
+    pub fn refresh(&mut self) -> Result<Vec<StatusChange>> {
+        let before = self.statuses();
+
+        let running: Vec<&AgentId> = before
+            .iter()
+            .filter(|(_, status)| status.holds_session())
+            .map(|(id, _)| id)
+            .collect();
+        if !running.is_empty() {
+            // One tmux call for every agent instead of one has-session each
+            let windows = self.tmux.list_windows().ok();
+            for id in running {
+                let _ = self.check_status_in(&id.0, windows.as_ref());
+            }
+        }
+        self.start_ready_agents()?;
+
+        Ok(self.changes_since(before))
+    }
+
+    /// Bring agent statuses up to date, picking up changes made by other wta
+    /// processes. When a daemon is supervising the repository it owns polling,
+    /// so only its latest state is read back. Returns the transitions seen.
+    pub fn sync_status(&mut self) -> Result<Vec<StatusChange>> {
+        let before = self.statuses();
+
+        self.reload_state()?;
+        if self.daemon_pid().is_none() {
+            self.refresh()?;
+        }
+
+        Ok(self.changes_since(before))
+    }
+
+    fn statuses(&self) -> Vec<(AgentId, AgentStatus)> {
+        self.state
+            .agents()
+            .iter()
+            .map(|a| (a.id.clone(), a.status))
+            .collect()
+    }
+
+    fn changes_since(&self, before: Vec<(AgentId, AgentStatus)>) -> Vec<StatusChange> {
+        before
+            .into_iter()
+            .filter_map(|(id, from)| {
+                let to = self.state.get_agent(&id.0)?.status;
+                (to != from).then_some(StatusChange { id, from, to })
+            })
+            .collect()
+    }
+
+    /// Get the path to the parent directory where worktrees are created
+    /// (siblings of the repo unless `worktree_dir` is configured)
     pub fn worktrees_parent_dir(&self) -> PathBuf {
-        self.repo_root
-            .parent()
-            .map(|p| p.to_path_buf())
-            .unwrap_or_else(|| self.repo_root.clone())
+        self.worktree_manager.parent_dir().to_path_buf()
     }
 
     pub fn get_agent(&self, id: &str) -> Result<&Agent> {
@@ -308,15 +1441,248 @@ impl Orchestrator {
             .ok_or_else(|| Error::AgentNotFound(id.to_string()))
     }
 
+    /// Agent whose worktree contains `path`
+    pub fn agent_for_path(&self, path: &Path) -> Result<&Agent> {
+        let path = path.canonicalize()?;
+        self.state
+            .agents()
+            .into_iter()
+            .find(|a| {
+                a.worktree_path
+                    .canonicalize()
+                    .is_ok_and(|worktree| path.starts_with(worktree))
+            })
+            .ok_or(Error::NotInAgentWorktree(path))
+    }
+
+    /// Record an agent's report in its status file. The file is replaced
+    /// atomically, so watchers never see a partially written report.
+    pub fn write_report(&self, id: &str, report: &StatusReport) -> Result<PathBuf> {
+        let status_file = self.status_file(id);
+        let tmp_file = status_file.with_extension("json.tmp");
+        std::fs::write(&tmp_file, serde_json::to_string_pretty(report)?)?;
+        std::fs::rename(&tmp_file, &status_file)?;
+        Ok(status_file)
+    }
+
+    /// Status file an agent writes when it finishes
+    fn status_file(&self, id: &str) -> PathBuf {
+        self.repo_root
+            .join(STATE_DIR)
+            .join("status")
+            .join(format!("{id}.json"))
+    }
+
+    /// Append a progress update to an agent's progress log
+    pub fn append_progress(&self, id: &str, event: &ProgressEvent) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+        // A single append-mode write, so concurrent updates do not interleave
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.progress_file(id))?;
+        std::io::Write::write_all(&mut file, line.as_bytes())?;
+        Ok(())
+    }
+
+    /// All progress updates an agent has reported, oldest first
+    pub fn progress(&self, id: &str) -> Result<Vec<ProgressEvent>> {
+        match std::fs::read_to_string(self.progress_file(id)) {
+            Ok(content) => Ok(report::parse_progress(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn progress_file(&self, id: &str) -> PathBuf {
+        self.repo_root
+            .join(STATE_DIR)
+            .join("progress")
+            .join(format!("{id}.jsonl"))
+    }
+
+    /// Append a review finding to a reviewer agent's findings log
+    pub fn append_finding(&self, id: &str, finding: &Finding) -> Result<()> {
+        let mut line = serde_json::to_string(finding)?;
+        line.push('\n');
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.findings_file(id))?;
+        std::io::Write::write_all(&mut file, line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Findings a reviewer agent has recorded so far, in order
+    pub fn findings(&self, id: &str) -> Result<Vec<Finding>> {
+        match std::fs::read_to_string(self.findings_file(id)) {
+            Ok(content) => Ok(report::parse_findings(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn findings_file(&self, id: &str) -> PathBuf {
+        self.repo_root
+            .join(STATE_DIR)
+            .join("findings")
+            .join(format!("{id}.jsonl"))
+    }
+
+    /// Record a question from an agent, returning its number. Asking the
+    /// open question again returns it, so an interrupted `wta ask` can wait on.
+    pub fn ask(&self, id: &str, question: &str) -> Result<u32> {
+        let question = question.trim();
+        if question.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        let _lock = lock::FileLock::acquire(self.questions_file(id).with_extension("lock"))?;
+        let questions = self.questions(id)?;
+        if let Some(open) = questions
+            .iter()
+            .find(|q| q.is_open() && q.question == question)
+        {
+            return Ok(open.number);
+        }
+
+        let number = questions.len() as u32 + 1;
+        self.append_question_event(
+            id,
+            &QuestionEvent::Asked {
+                number,
+                at: chrono::Utc::now(),
+                question: question.to_string(),
+            },
+        )?;
+        Ok(number)
+    }
+
+    /// Answer an agent's question: `number`, or the oldest open one
+    pub fn answer(&self, id: &str, number: Option<u32>, answer: &str) -> Result<Question> {
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        let _lock = lock::FileLock::acquire(self.questions_file(id).with_extension("lock"))?;
+        let questions = self.questions(id)?;
+        let question = match number {
+            Some(n) => questions
+                .into_iter()
+                .find(|q| q.number == n && q.is_open())
+                .ok_or_else(|| Error::QuestionNotOpen(id.to_string(), n))?,
+            None => questions
+                .into_iter()
+                .find(|q| q.is_open())
+                .ok_or_else(|| Error::NoOpenQuestion(id.to_string()))?,
+        };
+
+        self.append_question_event(
+            id,
+            &QuestionEvent::Answered {
+                number: question.number,
+                at: chrono::Utc::now(),
+                answer: answer.to_string(),
+            },
+        )?;
+        Ok(question)
+    }
+
+    /// Questions an agent has asked, with any answers, in order
+    pub fn questions(&self, id: &str) -> Result<Vec<Question>> {
+        match std::fs::read_to_string(self.questions_file(id)) {
+            Ok(content) => Ok(report::parse_questions(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn append_question_event(&self, id: &str, event: &QuestionEvent) -> Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.questions_file(id))?;
+        std::io::Write::write_all(&mut file, line.as_bytes())?;
+        Ok(())
+    }
+
+    fn questions_file(&self, id: &str) -> PathBuf {
+        self.repo_root
+            .join(STATE_DIR)
+            .join("questions")
+            .join(format!("{id}.jsonl"))
+    }
+
     pub fn get_agent_mut(&mut self, id: &str) -> Result<&mut Agent> {
         self.state
             .get_agent_mut(id)
             .ok_or_else(|| Error::AgentNotFound(id.to_string()))
     }
 
-    pub fn get_output(&self, id: &str, lines: usize) -> Result<String> {
+    /// Recent pane output. Once a timed-out agent's window has been killed,
+    /// the output saved when it was stopped is returned instead.
+    pub fn get_output(&self, id: &str, lines: usize) -> Result<String> {
+        let agent = self.get_agent(id)?;
+        match self.tmux.capture_pane(&agent.tmux_window, lines) {
+            Err(e) if agent.status == AgentStatus::TimedOut => {
+                let Ok(saved) = std::fs::read_to_string(self.output_file(id)) else {
+                    return Err(e);
+                };
+                let saved: Vec<&str> = saved.lines().collect();
+                let tail = &saved[saved.len().saturating_sub(lines)..];
+                Ok(format!("{}\n", tail.join("\n")))
+            }
+            result => result,
+        }
+    }
+
+    /// Final pane output saved when an agent is stopped
+    pub fn output_file(&self, id: &str) -> PathBuf {
+        self.repo_root
+            .join(STATE_DIR)
+            .join("output")
+            .join(format!("{id}.log"))
+    }
+
+    /// Paste follow-up instructions into an active agent's pane and record
+    /// them in its history
+    pub fn send_message(&mut self, id: &str, text: &str) -> Result<()> {
+        let delivery = self.prepare_message(id, text)?;
+        delivery.deliver()?;
+        self.record_message(id, delivery.text())
+    }
+
+    /// Check that a follow-up message can be sent to an agent, without
+    /// sending it yet
+    pub fn prepare_message(&self, id: &str, text: &str) -> Result<MessageDelivery> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+
         let agent = self.get_agent(id)?;
-        self.tmux.capture_pane(&agent.tmux_window, lines)
+        match agent.status {
+            AgentStatus::Queued => return Err(Error::AgentQueued(id.to_string())),
+            status if !status.is_active() => return Err(Error::AgentNotRunning(id.to_string())),
+            _ => {}
+        }
+        Ok(MessageDelivery {
+            tmux: self.tmux.clone(),
+            window: agent.tmux_window.clone(),
+            text: text.to_string(),
+        })
+    }
+
+    /// Record a delivered follow-up message in the agent's history
+    pub fn record_message(&mut self, id: &str, text: &str) -> Result<()> {
+        let agent = self.get_agent_mut(id)?;
+        agent.messages.push(SentMessage {
+            at: chrono::Utc::now(),
+            text: text.to_string(),
+        });
+        self.state.save()
     }
 
     pub fn attach(&self, id: &str) -> Result<()> {
@@ -335,55 +1701,226 @@ impl Orchestrator {
     }
 
     pub fn check_status(&mut self, id: &str) -> Result<AgentStatus> {
+        // Reviews are attached to an agent when its reviewers report
+        let reviewers: Vec<String> = self
+            .list()
+            .into_iter()
+            .filter(|a| a.status.is_active() && a.review_of.as_ref().is_some_and(|r| r.0 == id))
+            .map(|a| a.id.0.clone())
+            .collect();
+        for reviewer in reviewers {
+            self.check_status_in(&reviewer, None)?;
+        }
+        self.check_status_in(id, None)
+    }
+
+    /// Check an agent's status, using `windows` (from a single `list-windows`
+    /// call) when available instead of asking tmux about this agent's window
+    fn check_status_in(
+        &mut self,
+        id: &str,
+        windows: Option<&HashSet<String>>,
+    ) -> Result<AgentStatus> {
         let agent = self.get_agent(id)?;
 
-        // If agent is already in a terminal state, no need to check further
-        if agent.status != AgentStatus::Running {
+        // Queued agents change state only when their dependencies do
+        if agent.status == AgentStatus::Queued {
+            self.start_ready_agents()?;
+            return Ok(self.get_agent(id)?.status);
+        }
+
+        // If agent is already in a terminal state, no need to check further.
+        // A rejected report or a proposed plan is re-read, since the agent
+        // may rewrite it.
+        if !agent.status.holds_session() {
             return Ok(agent.status);
         }
 
+        // Keep the latest progress update and the questions on the agent
+        let progress = self.progress(id)?.pop();
+        if progress != agent.progress {
+            self.get_agent_mut(id)?.progress = progress;
+            self.state.save()?;
+        }
+        let questions = self.questions(id)?;
+        if questions != self.get_agent(id)?.questions {
+            self.get_agent_mut(id)?.questions = questions;
+            self.state.save()?;
+        }
+
         // Check if status file exists
-        let status_file = self
-            .repo_root
-            .join(STATE_DIR)
-            .join("status")
-            .join(format!("{}.json", id));
+        let status_file = self.status_file(id);
 
         if status_file.exists() {
             let content = std::fs::read_to_string(&status_file)?;
-            let status_data: serde_json::Value = serde_json::from_str(&content)?;
-
-            let new_status = match status_data.get("status").and_then(|s| s.as_str()) {
-                Some("completed") => AgentStatus::Completed,
-                Some("failed") => AgentStatus::Failed,
-                _ => return Ok(agent.status),
-            };
 
             // Note: We do NOT kill the tmux window here - leave it running so the user
             // can inspect the final state. Window will be killed when user removes/prunes.
 
-            // Update agent status
             let agent = self.get_agent_mut(id)?;
-            agent.status = new_status;
-            agent.completed_at = Some(chrono::Utc::now());
-            self.state.save()?;
+            let changed = match StatusReport::parse(&content) {
+                Ok(report) => {
+                    let status = match report.status {
+                        ReportStatus::Completed => AgentStatus::Completed,
+                        ReportStatus::Failed => AgentStatus::Failed,
+                        ReportStatus::Plan => AgentStatus::AwaitingApproval,
+                    };
+                    if agent.status == status && agent.report.as_ref() == Some(&report) {
+                        false
+                    } else {
+                        agent.status = status;
+                        // A proposed plan pauses the agent rather than ending it
+                        if report.status != ReportStatus::Plan {
+                            agent.completed_at = Some(chrono::Utc::now());
+                        }
+                        agent.report = Some(report);
+                        agent.report_error = None;
+                        agent.stall_output = None;
+                        true
+                    }
+                }
+                Err(e) => {
+                    if agent.status == AgentStatus::InvalidReport
+                        && agent.report_error.as_ref() == Some(&e)
+                    {
+                        false
+                    } else {
+                        agent.status = AgentStatus::InvalidReport;
+                        agent.report_error = Some(e);
+                        true
+                    }
+                }
+            };
+            let new_status = agent.status;
+            if changed {
+                self.state.save()?;
+                if new_status != AgentStatus::InvalidReport {
+                    self.attach_review(id)?;
+                }
+                self.start_ready_agents()?;
+            }
 
-            return Ok(new_status);
+            // An agent waiting for approval or rewriting its report keeps its
+            // session, which can still die or run out of time
+            if !new_status.holds_session() {
+                return Ok(new_status);
+            }
         }
+        let agent = self.get_agent(id)?;
 
         // No status file exists - check if tmux window is still running
         // If the window is gone, the agent has exited (possibly crashed or user exited)
-        let window_exists = self.tmux.window_exists(&agent.tmux_window);
+        let window_exists = match windows {
+            Some(windows) => windows.contains(&agent.tmux_window),
+            None => self.tmux.window_exists(&agent.tmux_window),
+        };
         if !window_exists {
             let agent = self.get_agent_mut(id)?;
             agent.status = AgentStatus::Failed;
             agent.completed_at = Some(chrono::Utc::now());
+            agent.stall_output = None;
             self.state.save()?;
+            self.start_ready_agents()?;
 
             return Ok(AgentStatus::Failed);
         }
 
-        Ok(agent.status)
+        if agent.deadline().is_some_and(|d| chrono::Utc::now() >= d) {
+            return self.time_out(id);
+        }
+
+        // A rejected report or a proposed plan waits for the agent or the user
+        if !agent.status.is_active() {
+            return Ok(agent.status);
+        }
+
+        // An agent blocked in `wta ask` waits for the user, not a prompt
+        if agent.open_question().is_some() {
+            if agent.status != AgentStatus::NeedsInput || agent.stall_output.is_some() {
+                let agent = self.get_agent_mut(id)?;
+                agent.status = AgentStatus::NeedsInput;
+                agent.stall_output = None;
+                self.state.save()?;
+            }
+            return Ok(AgentStatus::NeedsInput);
+        }
+
+        self.check_activity(id)
+    }
+
+    /// Stop an agent that ran past its timeout: save its final pane output
+    /// and kill its window, keeping the worktree and branch for inspection
+    fn time_out(&mut self, id: &str) -> Result<AgentStatus> {
+        let agent = self.get_agent(id)?;
+        let window = agent.tmux_window.clone();
+
+        match self.tmux.capture_pane(&window, TIMEOUT_OUTPUT_LINES) {
+            Ok(output) => {
+                let path = self.output_file(id);
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                // Drop the empty rows below the last output
+                std::fs::write(&path, format!("{}\n", output.trim_end()))?;
+            }
+            Err(e) => eprintln!("Warning: could not save output of agent {id}: {e}"),
+        }
+        let _ = self.tmux.kill_window(&window);
+
+        let agent = self.get_agent_mut(id)?;
+        agent.status = AgentStatus::TimedOut;
+        agent.completed_at = Some(chrono::Utc::now());
+        agent.stall_output = None;
+        self.state.save()?;
+        self.start_ready_agents()?;
+
+        Ok(AgentStatus::TimedOut)
+    }
+
+    /// Flag an active agent as stalled or waiting for input, or clear the
+    /// flag once it is working again. The pane is captured at most every
+    /// `PANE_CHECK_INTERVAL`.
+    fn check_activity(&mut self, id: &str) -> Result<AgentStatus> {
+        let agent = self.get_agent(id)?;
+        let now = chrono::Utc::now();
+        if agent.pane.as_ref().is_some_and(|p| !p.due(now))
+            || self
+                .pane_checks
+                .get(&agent.id)
+                .is_some_and(|&at| now - at < stall::PANE_CHECK_INTERVAL)
+        {
+            return Ok(agent.status);
+        }
+
+        let pane = self
+            .tmux
+            .capture_pane(&agent.tmux_window, stall::PANE_LINES)?;
+        let activity = stall::PaneActivity::observe(agent.pane.as_ref(), &pane, now);
+        let status = stall::classify(
+            &pane,
+            &activity,
+            crate::git::repository::last_commit_time(&agent.worktree_path),
+            agent.progress.as_ref().map(|p| p.at),
+            agent.launched_at,
+            now,
+        );
+
+        let stall_output = (status != AgentStatus::Running).then(|| stall::excerpt(&pane));
+        let changed = agent.status != status
+            || agent.stall_output != stall_output
+            || agent.pane.as_ref().map(|p| (p.hash, p.changed_at))
+                != Some((activity.hash, activity.changed_at));
+        self.pane_checks.insert(agent.id.clone(), now);
+
+        let agent = self.get_agent_mut(id)?;
+        agent.pane = Some(activity);
+        agent.stall_output = stall_output;
+        agent.status = status;
+        if changed {
+            self.state.save()?;
+        }
+
+        Ok(status)
     }
 
     pub async fn merge(
@@ -392,32 +1929,54 @@ impl Orchestrator {
         target: Option<String>,
         strategy: MergeStrategy,
         force: bool,
+        update_checkout: bool,
     ) -> Result<MergeResult> {
         let agent = self.get_agent(id)?;
 
-        if agent.status == AgentStatus::Running && !force {
+        if agent.status == AgentStatus::Queued {
+            return Err(Error::AgentQueued(id.to_string()));
+        }
+        if agent.status.holds_session() && !force {
             return Err(Error::AgentStillRunning(id.to_string()));
         }
 
-        // Determine target branch: explicit --target flag, or detect default branch
-        let target_branch = match target {
-            Some(t) => t,
-            None => crate::git::repository::default_branch(&self.repo_root)?,
-        };
+        let target_branch = self.merge_target(target)?;
+
+        // A branch held by another agent's worktree (such as a fork's parent)
+        // is fast-forwarded there, which must not happen under a running agent
+        if let Some(holder) = self.state.agents().into_iter().find(|a| {
+            a.id.0 != id
+                && a.branch == target_branch
+                && a.status.holds_session()
+                && a.worktree_path.exists()
+        }) {
+            return Err(Error::TargetAgentRunning(holder.id.0.clone()));
+        }
 
-        // Remove worktree BEFORE merge - git checkout fails if branch is checked out in a worktree
-        // Ignore WorktreeNotFound (may have been manually removed), but propagate other errors
-        match self.worktree_manager.remove(id) {
-            Ok(()) => {}
-            Err(Error::WorktreeNotFound(_)) => {}
-            Err(e) => return Err(e),
+        // Integration branches collect agents for testing, so merging into
+        // one adds the agent without landing it
+        if let Some(integration) = self
+            .integrations()
+            .into_iter()
+            .find(|i| i.branch == target_branch)
+            .cloned()
+        {
+            return self.add_to_integration(integration, id);
         }
 
+        // An idle agent's worktree, such as a fork's parent, is not the
+        // user's checkout, so it is fast-forwarded without asking
+        let update_checkout = update_checkout || self.checked_out_by_idle_agent(&target_branch)?;
+
+        // The merge runs in a temporary worktree, so neither the user's
+        // checkout nor the agent's worktree changes unless it succeeds, and
+        // a checked-out target only with `update_checkout`
         let result = match crate::git::merge::merge_branch(
             &self.repo_root,
             &agent.branch,
             &target_branch,
             strategy,
+            update_checkout,
         ) {
             Ok(result) => result,
             Err(Error::MergeConflict(conflicts)) => {
@@ -431,33 +1990,298 @@ impl Orchestrator {
         };
 
         if result.success {
+            // A forced merge ends the session along with the worktree
+            if agent.status.holds_session() {
+                let _ = self.tmux.kill_window(&agent.tmux_window);
+            }
+
+            // The branch cannot be deleted while its worktree exists.
+            // Ignore WorktreeNotFound (may have been manually removed), but propagate other errors
+            match self.worktree_manager.remove(&agent.worktree_path) {
+                Ok(()) => {}
+                Err(Error::WorktreeNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+
             let repo = git2::Repository::open(&self.repo_root)?;
             if let Ok(mut branch) = repo.find_branch(&agent.branch, git2::BranchType::Local) {
                 let _ = branch.delete();
             }
 
-            // Remove prompt and status files
+            // Remove prompt, status and progress files
             let prompt_file = self
                 .repo_root
                 .join(STATE_DIR)
                 .join("prompts")
                 .join(format!("{id}.txt"));
-            let status_file = self
-                .repo_root
-                .join(STATE_DIR)
-                .join("status")
-                .join(format!("{id}.json"));
+            let status_file = self.status_file(id);
             let _ = std::fs::remove_file(prompt_file);
             let _ = std::fs::remove_file(status_file);
+            let _ = std::fs::remove_file(self.progress_file(id));
+            let _ = std::fs::remove_file(self.output_file(id));
+            let _ = std::fs::remove_file(self.findings_file(id));
+            let _ = std::fs::remove_file(self.questions_file(id));
 
             let agent = self.get_agent_mut(id)?;
             agent.status = AgentStatus::Merged;
             self.state.save()?;
+            self.start_ready_agents()?;
         }
 
         Ok(result)
     }
 
+    /// Refuse a merge into `target` that `merge` would refuse because the
+    /// target is checked out, without rebasing or checking anything first
+    pub fn check_target_checkout(&self, target: &str, update_checkout: bool) -> Result<()> {
+        if update_checkout || self.checked_out_by_idle_agent(target)? {
+            return Ok(());
+        }
+        crate::git::merge::ensure_not_checked_out(&self.repo_root, target)
+    }
+
+    /// Whether `branch` is checked out in the worktree of an agent that no
+    /// longer holds a session
+    fn checked_out_by_idle_agent(&self, branch: &str) -> Result<bool> {
+        let Some(path) = crate::git::merge::checked_out_at(&self.repo_root, branch)? else {
+            return Ok(false);
+        };
+        let path = path.canonicalize().unwrap_or(path);
+        Ok(self.state.agents().into_iter().any(|a| {
+            !a.status.holds_session()
+                && a.worktree_path
+                    .canonicalize()
+                    .is_ok_and(|worktree| worktree == path)
+        }))
+    }
+
+    /// Pairs of active agents whose committed changes touch the same files.
+    /// Agents whose branch cannot be diffed are left out.
+    pub fn overlaps(&mut self) -> Vec<Overlap> {
+        overlap::find_overlaps(&self.branch_changes(), None)
+    }
+
+    /// Pairs of active agents that include `id`
+    pub fn overlaps_of(&mut self, id: &str) -> Result<Vec<Overlap>> {
+        let agent = self.get_agent(id)?;
+        if !agent.status.is_active() {
+            return Ok(Vec::new());
+        }
+        let id = agent.id.clone();
+        Ok(overlap::find_overlaps(&self.branch_changes(), Some(&id)))
+    }
+
+    /// Changes of each active agent's branch since it left its base. A branch
+    /// is only diffed again once it or its base moves.
+    fn branch_changes(&mut self) -> Vec<BranchChanges> {
+        let Ok(repo) = git2::Repository::open(&self.repo_root) else {
+            return Vec::new();
+        };
+        let head = |name: &str| repo.revparse_single(name).map(|o| o.id()).ok();
+
+        let mut changes = Vec::new();
+        let mut cache = HashMap::new();
+        for agent in self.state.agents() {
+            if !agent.status.is_active() {
+                continue;
+            }
+            let Some(heads) = head(&agent.base_branch).zip(head(&agent.branch)) else {
+                continue;
+            };
+            let files = match self.branch_changes.remove(&agent.id) {
+                Some((cached, files)) if cached == heads => files,
+                _ => {
+                    let range = format!("{}...{}", agent.base_branch, agent.branch);
+                    match crate::git::repository::diff_lines(&self.repo_root, &range) {
+                        Ok(diff) => overlap::parse_diff(&diff),
+                        Err(_) => continue,
+                    }
+                }
+            };
+            changes.push(BranchChanges {
+                id: agent.id.clone(),
+                base_branch: agent.base_branch.clone(),
+                files: files.clone(),
+            });
+            cache.insert(agent.id.clone(), (heads, files));
+        }
+        // Agents that finished drop out
+        self.branch_changes = cache;
+        changes
+    }
+
+    /// Commit the default branch is at, which merges are checked against
+    pub fn default_target_head(&self) -> Option<String> {
+        let target = self.merge_target(None).ok()?;
+        crate::git::merge::branch_commit(&self.repo_root, &target).ok()
+    }
+
+    /// Target branch of a merge: the explicit target, or the default branch
+    pub fn merge_target(&self, target: Option<String>) -> Result<String> {
+        match target {
+            Some(t) => Ok(t),
+            None => crate::git::repository::default_branch(&self.repo_root),
+        }
+    }
+
+    /// Check whether an agent's committed work would merge cleanly into
+    /// `target` (the default branch if None), without changing anything
+    pub fn mergeability(&self, id: &str, target: Option<String>) -> Result<Mergeability> {
+        let agent = self.get_agent(id)?;
+        match agent.status {
+            AgentStatus::Merged => return Err(Error::AgentMerged(id.to_string())),
+            AgentStatus::Queued => return Err(Error::AgentQueued(id.to_string())),
+            _ => {}
+        }
+        let target = self.merge_target(target)?;
+        let conflicts = crate::git::merge::check_merge(&self.repo_root, &agent.branch, &target)?;
+
+        Ok(Mergeability {
+            branch: agent.branch.clone(),
+            target,
+            conflicts,
+        })
+    }
+
+    /// Create `branch` from `base` (the default branch if None) in a new
+    /// worktree and merge the agents' branches into it one after another.
+    /// An agent that conflicts with those merged before it is left out and
+    /// its conflicting files recorded; the agents themselves are not changed.
+    pub fn integrate(
+        &mut self,
+        ids: &[String],
+        branch: &str,
+        base: Option<String>,
+    ) -> Result<Integration> {
+        let mut agents: Vec<(AgentId, String)> = Vec::new();
+        for id in ids {
+            let agent = self.get_agent(id)?;
+            match agent.status {
+                AgentStatus::Queued => return Err(Error::AgentQueued(id.clone())),
+                AgentStatus::Merged => return Err(Error::AgentMerged(id.clone())),
+                status if status.holds_session() => {
+                    return Err(Error::AgentStillRunning(id.clone()))
+                }
+                _ => {}
+            }
+            crate::git::merge::branch_commit(&self.repo_root, &agent.branch)?;
+            if !agents.iter().any(|(a, _)| *a == agent.id) {
+                agents.push((agent.id.clone(), agent.branch.clone()));
+            }
+        }
+
+        let base_branch = self.merge_target(base)?;
+        let name = branch.replace('/', "-");
+        let worktree_path = self.worktree_manager.create(&name, branch, &base_branch)?;
+
+        let integration = Integration {
+            branch: branch.to_string(),
+            base_branch,
+            worktree_path: worktree_path.clone(),
+            created_at: chrono::Utc::now(),
+            agents: Vec::new(),
+        };
+        match self.merge_integration(integration, agents) {
+            Ok(integration) => Ok(integration),
+            Err(e) => {
+                // Nothing records a half-built integration branch, so
+                // leave none behind
+                let _ = self.worktree_manager.remove(&worktree_path);
+                let repo = git2::Repository::open(&self.repo_root)?;
+                if let Ok(mut branch) = repo.find_branch(branch, git2::BranchType::Local) {
+                    let _ = branch.delete();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Merge agents' branches into a new integration worktree in order, and
+    /// record it
+    fn merge_integration(
+        &mut self,
+        mut integration: Integration,
+        agents: Vec<(AgentId, String)>,
+    ) -> Result<Integration> {
+        for (id, agent_branch) in agents {
+            let message = format!(
+                "Merge agent {id} ({agent_branch}) into {}",
+                integration.branch
+            );
+            let conflicts = match crate::git::merge::merge_into_worktree(
+                &integration.worktree_path,
+                &agent_branch,
+                &message,
+            ) {
+                Ok(()) => Vec::new(),
+                Err(Error::MergeConflict(files)) => files,
+                Err(e) => return Err(e),
+            };
+            integration.agents.push(IntegratedAgent {
+                id,
+                branch: agent_branch,
+                conflicts,
+            });
+        }
+
+        self.state.prune_integrations()?;
+        self.state.set_integration(integration.clone())?;
+        Ok(integration)
+    }
+
+    /// Merge an agent into an existing integration branch in its worktree,
+    /// recording it there. The agent and its branch are kept.
+    fn add_to_integration(
+        &mut self,
+        mut integration: Integration,
+        id: &str,
+    ) -> Result<MergeResult> {
+        let agent = self.get_agent(id)?;
+        let (agent_id, agent_branch) = (agent.id.clone(), agent.branch.clone());
+        let message = format!(
+            "Merge agent {agent_id} ({agent_branch}) into {}",
+            integration.branch
+        );
+        let conflicts = match crate::git::merge::merge_into_worktree(
+            &integration.worktree_path,
+            &agent_branch,
+            &message,
+        ) {
+            Ok(()) => Vec::new(),
+            Err(Error::MergeConflict(files)) => files,
+            Err(e) => return Err(e),
+        };
+
+        integration.agents.retain(|a| a.id != agent_id);
+        integration.agents.push(IntegratedAgent {
+            id: agent_id,
+            branch: agent_branch.clone(),
+            conflicts: conflicts.clone(),
+        });
+        let target = integration.branch.clone();
+        self.state.set_integration(integration)?;
+
+        if !conflicts.is_empty() {
+            return Err(Error::MergeConflict(conflicts));
+        }
+        Ok(MergeResult {
+            success: true,
+            message: format!(
+                "Merged {agent_branch} into integration branch {target}; agent {id} is kept"
+            ),
+            conflicts: Vec::new(),
+        })
+    }
+
+    /// Integration branches whose worktree still exists
+    pub fn integrations(&self) -> Vec<&Integration> {
+        self.state
+            .integrations()
+            .iter()
+            .filter(|i| i.worktree_path.exists())
+            .collect()
+    }
+
     /// Generate a PR title and body using Claude CLI
     fn generate_pr_summary(&self, task: &str) -> Option<PrSummary> {
         use std::io::Write;
@@ -503,13 +2327,17 @@ impl Orchestrator {
     ) -> Result<PrResult> {
         let agent = self.get_agent(id)?;
 
-        if agent.status == AgentStatus::Running && !force {
+        if agent.status == AgentStatus::Queued {
+            return Err(Error::AgentQueued(id.to_string()));
+        }
+        if agent.status.holds_session() && !force {
             return Err(Error::AgentStillRunning(id.to_string()));
         }
 
         let branch = agent.branch.clone();
         let base_branch = agent.base_branch.clone();
-        let task = agent.task.clone();
+        // The PR covers the launch task and every follow-up
+        let task = agent.tasks.join("\n\n");
 
         // Generate AI summary if title or body not provided
         let (pr_title, pr_body) = match (&title, &body) {
@@ -579,7 +2407,7 @@ impl Orchestrator {
         // Check both the status AND if tmux window actually exists
         // Agent is only truly running if status says Running AND window exists
         let window_exists = self.tmux.window_exists(&agent.tmux_window);
-        let is_running = agent.status == AgentStatus::Running && window_exists;
+        let is_running = agent.status.holds_session() && window_exists;
 
         if is_running && !force {
             return Err(Error::AgentStillRunning(id.to_string()));
@@ -589,7 +2417,7 @@ impl Orchestrator {
         let _ = self.tmux.kill_window(&agent.tmux_window);
 
         // Remove worktree (warn if already gone)
-        if let Err(e) = self.worktree_manager.remove(id) {
+        if let Err(e) = self.worktree_manager.remove(&agent.worktree_path) {
             eprintln!("Warning: could not remove worktree: {e}");
         }
 
@@ -601,39 +2429,53 @@ impl Orchestrator {
             };
         }
 
-        // Remove prompt and status files if they exist
+        // Remove prompt, status and progress files if they exist
         let prompt_file = self
             .repo_root
             .join(STATE_DIR)
             .join("prompts")
             .join(format!("{id}.txt"));
-        let status_file = self
-            .repo_root
-            .join(STATE_DIR)
-            .join("status")
-            .join(format!("{id}.json"));
+        let status_file = self.status_file(id);
         let _ = std::fs::remove_file(prompt_file);
         let _ = std::fs::remove_file(status_file);
+        let _ = std::fs::remove_file(self.progress_file(id));
+        let _ = std::fs::remove_file(self.output_file(id));
+        let _ = std::fs::remove_file(self.findings_file(id));
+        let _ = std::fs::remove_file(self.questions_file(id));
 
         // Remove agent from state entirely
         self.state.remove_agent(id)?;
 
+        // A removed running agent frees a slot
+        self.start_ready_agents()?;
+
         Ok(())
     }
 
     /// Prune agents matching the filter, cleaning up all associated resources
     /// Returns the list of pruned agents
     pub async fn prune(&mut self, filter: PruneFilter, delete_branch: bool) -> Result<Vec<Agent>> {
+        // Agents that queued agents still wait on are kept (unless pruning
+        // everything), otherwise their dependents would fail
+        let awaited: Vec<AgentId> = self
+            .state
+            .agents()
+            .iter()
+            .filter(|a| a.status == AgentStatus::Queued)
+            .flat_map(|a| a.depends_on.clone())
+            .collect();
+
         // Collect agents to prune based on filter
         let agents_to_prune: Vec<Agent> = self
             .state
             .agents()
             .iter()
+            .filter(|agent| filter == PruneFilter::All || !awaited.contains(&agent.id))
             .filter(|agent| match filter {
                 PruneFilter::All => true,
                 PruneFilter::Status(status) => agent.status == status,
                 PruneFilter::Inactive => {
-                    // Note: Conflict is NOT included - those agents have unresolved work
+                    // Note: Conflict, ChecksFailed and TimedOut are NOT included - those agents have unresolved work
                     matches!(
                         agent.status,
                         AgentStatus::Merged | AgentStatus::Completed | AgentStatus::Failed
@@ -655,6 +2497,9 @@ impl Orchestrator {
             pruned.push(agent);
         }
 
+        // Pruned running agents free their slots
+        self.start_ready_agents()?;
+
         Ok(pruned)
     }
 
@@ -665,7 +2510,7 @@ impl Orchestrator {
         let _ = self.tmux.kill_window(&agent.tmux_window);
 
         // Remove worktree if it exists
-        let _ = self.worktree_manager.remove(&agent.id.0);
+        let _ = self.worktree_manager.remove(&agent.worktree_path);
 
         // Delete branch only if explicitly requested
         if delete_branch {
@@ -676,19 +2521,19 @@ impl Orchestrator {
             }
         }
 
-        // Remove prompt and status files if they exist
+        // Remove prompt, status and progress files if they exist
         let prompt_file = self
             .repo_root
             .join(STATE_DIR)
             .join("prompts")
             .join(format!("{}.txt", agent.id.0));
-        let status_file = self
-            .repo_root
-            .join(STATE_DIR)
-            .join("status")
-            .join(format!("{}.json", agent.id.0));
+        let status_file = self.status_file(&agent.id.0);
         let _ = std::fs::remove_file(prompt_file);
         let _ = std::fs::remove_file(status_file);
+        let _ = std::fs::remove_file(self.progress_file(&agent.id.0));
+        let _ = std::fs::remove_file(self.output_file(&agent.id.0));
+        let _ = std::fs::remove_file(self.findings_file(&agent.id.0));
+        let _ = std::fs::remove_file(self.questions_file(&agent.id.0));
     }
 
     /// Recursively copy a directory and its contents
@@ -712,6 +2557,7 @@ impl Orchestrator {
 }
 
 #[cfg(test)]
+#[allow(clippy::useless_vec)]
 mod tests {
     use super::*;
 
@@ -721,6 +2567,260 @@ mod tests {
         agent
     }
 
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .current_dir(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// Orchestrator for a new repository on `main`, in `repo` under the
+    /// returned directory so agent worktrees are created next to it
+    fn test_orchestrator() -> (tempfile::TempDir, Orchestrator) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        std::fs::create_dir(&root).unwrap();
+        git(&root, &["init", "-q", "-b", "main"]);
+        git(&root, &["config", "user.name", "test"]);
+        git(&root, &["config", "user.email", "test@example.com"]);
+        std::fs::write(root.join(".gitignore"), ".wta/\n").unwrap();
+        git(&root, &["add", ".gitignore"]);
+        git(&root, &["commit", "-q", "-m", "init"]);
+        let orchestrator = Orchestrator::open(root).unwrap();
+        (dir, orchestrator)
+    }
+
+    /// Record an agent whose worktree is checked out from `base`, with one
+    /// commit adding `<id>.txt`
+    fn add_test_agent(
+        orchestrator: &mut Orchestrator,
+        id: u128,
+        status: AgentStatus,
+        base: &str,
+    ) -> Agent {
+        let mut agent = create_test_agent_with_status(id, status);
+        agent.base_branch = base.to_string();
+        agent.worktree_path = orchestrator
+            .worktree_manager
+            .create(&agent.id.0, &agent.branch, base)
+            .unwrap();
+        std::fs::write(agent.worktree_path.join(format!("{id}.txt")), "work\n").unwrap();
+        git(&agent.worktree_path, &["add", "."]);
+        git(&agent.worktree_path, &["commit", "-q", "-m", "work"]);
+        orchestrator.state.add_agent(agent.clone()).unwrap();
+        agent
+    }
+
+    #[test]
+    fn test_concurrent_orchestrators_allocate_different_ids() {
+        let (_dir, mut first) = test_orchestrator();
+        let mut second = Orchestrator::open(first.repo_root.clone()).unwrap();
+
+        let a = first.allocate_id().unwrap();
+        let b = second.allocate_id().unwrap();
+        assert_ne!(a, b);
+        assert_ne!(first.new_group().unwrap(), second.new_group().unwrap());
+
+        first
+            .state
+            .add_agent(create_test_agent_with_status(1, AgentStatus::Queued))
+            .unwrap();
+        second
+            .state
+            .add_agent(create_test_agent_with_status(2, AgentStatus::Queued))
+            .unwrap();
+        first.reload_state().unwrap();
+        assert_eq!(first.list().len(), 2);
+        assert_eq!(first.allocate_id().unwrap(), AgentId("3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_launch_names_timed_out_dependency() {
+        let (_dir, mut orchestrator) = test_orchestrator();
+        add_test_agent(&mut orchestrator, 1, AgentStatus::Completed, "main");
+        add_test_agent(&mut orchestrator, 2, AgentStatus::TimedOut, "main");
+
+        let err = orchestrator
+            .launch(LaunchRequest {
+                task: "Build on both".to_string(),
+                branch: None,
+                base: None,
+                provider: crate::provider::Provider::Claude,
+                provider_args: Vec::new(),
+                depends_on: vec![AgentId("1".to_string()), AgentId("2".to_string())],
+                timeout: None,
+                group: None,
+                review_of: None,
+                approve_plan: false,
+            })
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(&err, Error::DependencyFailed(ids) if ids == "2"),
+            "{err:?}"
+        );
+        assert_eq!(orchestrator.list().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_merge_fork_into_idle_parent() {
+        let (_dir, mut orchestrator) = test_orchestrator();
+        let parent = add_test_agent(&mut orchestrator, 1, AgentStatus::Completed, "main");
+        let mut fork = add_test_agent(&mut orchestrator, 2, AgentStatus::Completed, "wta/1");
+        fork.parent = Some(parent.id.clone());
+        *orchestrator.get_agent_mut("2").unwrap() = fork;
+
+        let target = orchestrator.parent_branch("2").unwrap();
+        let result = orchestrator
+            .merge("2", Some(target), MergeStrategy::Merge, false, false)
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert!(parent.worktree_path.join("2.txt").exists());
+        assert_eq!(git(&parent.worktree_path, &["status", "--porcelain"]), "");
+        assert_eq!(
+            orchestrator.get_agent("2").unwrap().status,
+            AgentStatus::Merged
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_refuses_agent_awaiting_approval_without_force() {
+        let (_dir, mut orchestrator) = test_orchestrator();
+        let agent = add_test_agent(&mut orchestrator, 1, AgentStatus::AwaitingApproval, "main");
+
+        let err = orchestrator
+            .merge("1", None, MergeStrategy::Merge, false, true)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::AgentStillRunning(_)), "{err:?}");
+        assert!(agent.worktree_path.exists());
+        assert_eq!(
+            orchestrator.get_agent("1").unwrap().status,
+            AgentStatus::AwaitingApproval
+        );
+    }
+
+    #[tokio::test]
+    async fn test_landing_refuses_checked_out_target_before_rebasing() {
+        let (dir, mut orchestrator) = test_orchestrator();
+        let agent = add_test_agent(&mut orchestrator, 1, AgentStatus::Completed, "main");
+        let root = dir.path().join("repo");
+        std::fs::write(root.join("main.txt"), "main\n").unwrap();
+        git(&root, &["add", "main.txt"]);
+        git(&root, &["commit", "-q", "-m", "main"]);
+        let head = git(&root, &["rev-parse", &agent.branch]);
+
+        let orchestrator = tokio::sync::Mutex::new(orchestrator);
+        let landing = crate::merge_queue::land(
+            &orchestrator,
+            "1",
+            None,
+            MergeStrategy::Merge,
+            Some(false),
+            Default::default(),
+        )
+        .await;
+
+        match landing {
+            crate::merge_queue::Landing::Failed { error } => {
+                assert!(error.contains("checked out"), "{error}")
+            }
+            other => panic!("expected a refusal, got {other:?}"),
+        }
+        assert_eq!(git(&root, &["rev-parse", &agent.branch]), head);
+    }
+
+    #[tokio::test]
+    async fn test_merge_removes_worktree_after_worktree_dir_changes() {
+        let (dir, mut orchestrator) = test_orchestrator();
+        let agent = add_test_agent(&mut orchestrator, 1, AgentStatus::Completed, "main");
+        orchestrator.worktree_manager = WorktreeManager::new(&orchestrator.repo_root)
+            .with_parent_dir(dir.path().join("elsewhere"));
+
+        orchestrator
+            .merge("1", None, MergeStrategy::Merge, false, true)
+            .await
+            .unwrap();
+
+        assert!(!agent.worktree_path.exists());
+        assert_eq!(
+            git(&orchestrator.repo_root, &["worktree", "list"])
+                .lines()
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_refuses_target_checked_out_in_main_tree() {
+        let (_dir, mut orchestrator) = test_orchestrator();
+        add_test_agent(&mut orchestrator, 1, AgentStatus::Completed, "main");
+
+        let err = orchestrator
+            .merge("1", None, MergeStrategy::Merge, false, false)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::TargetCheckedOut { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn test_run_check_command_records_outcome() {
+        let dir = tempfile::tempdir().unwrap();
+        let never = || false;
+        let passed =
+            run_check_command("echo ok", dir.path(), Duration::from_secs(10), &never).unwrap();
+        assert!(passed.passed);
+        assert_eq!(passed.output, "ok");
+
+        let failed = run_check_command(
+            "echo bad >&2; exit 1",
+            dir.path(),
+            Duration::from_secs(10),
+            &never,
+        )
+        .unwrap();
+        assert!(!failed.passed);
+        assert_eq!(failed.output, "bad");
+    }
+
+    #[test]
+    fn test_run_check_command_kills_after_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let started = std::time::Instant::now();
+        // The pipes stay open until the background sleep is killed too
+        let result = run_check_command(
+            "echo started; sleep 30 & sleep 30",
+            dir.path(),
+            Duration::from_millis(300),
+            &|| false,
+        )
+        .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!result.passed);
+        assert!(result
+            .output
+            .starts_with("started\nKilled after running for"));
+    }
+
+    #[test]
+    fn test_run_check_command_can_be_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let started = std::time::Instant::now();
+        let result = run_check_command("sleep 30", dir.path(), Duration::from_secs(60), &|| {
+            started.elapsed() > Duration::from_millis(200)
+        });
+        assert!(matches!(result, Err(Error::CheckCancelled)));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
     #[test]
     fn test_prune_filter_all_matches_all_statuses() {
         let agents = vec![
@@ -1044,6 +3144,11 @@ mod tests {
             base: Some("main".to_string()),
             provider: Provider::Claude,
             provider_args: vec!["--verbose".to_string()],
+            depends_on: Vec::new(),
+            timeout: None,
+            group: None,
+            review_of: None,
+            approve_plan: false,
         };
 
         assert_eq!(request.task, "Fix the bug");
@@ -1064,6 +3169,11 @@ mod tests {
             base: None,
             provider: Provider::default(),
             provider_args: Vec::new(),
+            depends_on: Vec::new(),
+            timeout: None,
+            group: None,
+            review_of: None,
+            approve_plan: false,
         };
 
         assert!(request.branch.is_none());
@@ -1081,6 +3191,11 @@ mod tests {
             base: None,
             provider: Provider::Claude,
             provider_args: Vec::new(),
+            depends_on: Vec::new(),
+            timeout: None,
+            group: None,
+            review_of: None,
+            approve_plan: false,
         };
         assert_eq!(claude_request.provider, Provider::Claude);
 
@@ -1090,6 +3205,11 @@ mod tests {
             base: None,
             provider: Provider::Codex,
             provider_args: Vec::new(),
+            depends_on: Vec::new(),
+            timeout: None,
+            group: None,
+            review_of: None,
+            approve_plan: false,
         };
         assert_eq!(codex_request.provider, Provider::Codex);
 
@@ -1099,10 +3219,115 @@ mod tests {
             base: None,
             provider: Provider::Gemini,
             provider_args: Vec::new(),
+            depends_on: Vec::new(),
+            timeout: None,
+            group: None,
+            review_of: None,
+            approve_plan: false,
         };
         assert_eq!(gemini_request.provider, Provider::Gemini);
     }
 
+    #[test]
+    fn test_dependency_state_ready_when_dependencies_done() {
+        let agents = [
+            create_test_agent_with_status(1, AgentStatus::Completed),
+            create_test_agent_with_status(2, AgentStatus::Merged),
+        ];
+        let refs: Vec<&Agent> = agents.iter().collect();
+        let deps = [AgentId("1".to_string()), AgentId("2".to_string())];
+
+        assert_eq!(dependency_state(&refs, &deps), DependencyState::Ready);
+        assert_eq!(dependency_state(&refs, &[]), DependencyState::Ready);
+    }
+
+    #[test]
+    fn test_dependency_state_waiting_while_dependency_active() {
+        let agents = [
+            create_test_agent_with_status(1, AgentStatus::Completed),
+            create_test_agent_with_status(2, AgentStatus::Running),
+            create_test_agent_with_status(3, AgentStatus::Queued),
+        ];
+        let refs: Vec<&Agent> = agents.iter().collect();
+
+        let deps = [AgentId("1".to_string()), AgentId("2".to_string())];
+        assert_eq!(dependency_state(&refs, &deps), DependencyState::Waiting);
+        let deps = [AgentId("3".to_string())];
+        assert_eq!(dependency_state(&refs, &deps), DependencyState::Waiting);
+    }
+
+    #[test]
+    fn test_dependency_state_blocked_by_failed_or_missing_dependency() {
+        let agents = [
+            create_test_agent_with_status(1, AgentStatus::Running),
+            create_test_agent_with_status(2, AgentStatus::Failed),
+            create_test_agent_with_status(3, AgentStatus::TimedOut),
+        ];
+        let refs: Vec<&Agent> = agents.iter().collect();
+
+        let deps = [AgentId("1".to_string()), AgentId("2".to_string())];
+        assert_eq!(dependency_state(&refs, &deps), DependencyState::Blocked);
+        let deps = [AgentId("3".to_string())];
+        assert_eq!(dependency_state(&refs, &deps), DependencyState::Blocked);
+        let deps = [AgentId("9".to_string())];
+        assert_eq!(dependency_state(&refs, &deps), DependencyState::Blocked);
+    }
+
+    #[test]
+    fn test_prompt_task_describes_failed_previous_attempt() {
+        let mut agent = create_test_agent_with_status(1, AgentStatus::Failed);
+        assert_eq!(prompt_task(&agent, false), agent.task());
+
+        agent.report = Some(StatusReport {
+            schema_version: SCHEMA_VERSION,
+            status: ReportStatus::Failed,
+            summary: None,
+            files_changed: Vec::new(),
+            error: Some("tests need a database".to_string()),
+            plan: None,
+            findings: Vec::new(),
+        });
+        agent.previous_attempts.push(agent.attempt());
+        agent.attempts = 2;
+        let task = prompt_task(&agent, false);
+        assert!(task.starts_with(agent.task()));
+        assert!(task.contains("attempt 2"));
+        assert!(task.contains("failed: tests need a database"));
+        assert!(task.contains("git status"));
+        let fresh = prompt_task(&agent, true);
+        assert!(fresh.contains("start from scratch"));
+        assert!(!fresh.contains("git status"));
+
+        agent.status = AgentStatus::TimedOut;
+        agent.previous_attempts.push(agent.attempt());
+        agent.attempts = 3;
+        assert!(prompt_task(&agent, false).contains("running out of time"));
+    }
+
+    #[test]
+    fn test_prompt_task_lists_earlier_tasks_of_continued_agent() {
+        let mut agent = create_test_agent_with_status(1, AgentStatus::Completed);
+        agent.previous_attempts.push(agent.attempt());
+        agent.attempts = 2;
+        agent.tasks.push("Address review comments".to_string());
+
+        let task = prompt_task(&agent, false);
+        assert!(task.contains("- Task 1\n"));
+        assert!(task.ends_with("Follow-up task:\nAddress review comments"));
+    }
+
+    #[test]
+    fn test_prompt_task_treats_approved_plan_as_follow_up() {
+        let mut agent = create_test_agent_with_status(1, AgentStatus::AwaitingApproval);
+        agent.previous_attempts.push(agent.attempt());
+        agent.attempts = 2;
+        agent.tasks.push("Your plan was approved".to_string());
+
+        let task = prompt_task(&agent, false);
+        assert!(task.ends_with("Follow-up task:\nYour plan was approved"));
+        assert!(!task.contains("previous attempt"));
+    }
+
     #[test]
     fn test_merge_result_fields() {
         let result = MergeResult {
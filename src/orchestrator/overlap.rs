@@ -0,0 +1,302 @@
+use super::{AgentId, Severity};
+use serde::Serialize;
+
+/// Hunks closer than this many lines are likely to conflict after further edits
+const NEAR_LINES: usize = 3;
+
+/// Inclusive, 1-based range of lines; a pure insertion after line `n` is `(n, n)`
+pub type LineRange = (usize, usize);
+
+/// Lines of a file changed by a branch, in the numbering of its base
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub path: String,
+    pub ranges: Vec<LineRange>,
+}
+
+/// Changes of one agent's branch, with the branch they are relative to
+#[derive(Debug, Clone)]
+pub struct BranchChanges {
+    pub id: AgentId,
+    pub base_branch: String,
+    pub files: Vec<ChangedFile>,
+}
+
+/// A file changed by both agents of an overlap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileOverlap {
+    pub path: String,
+    pub severity: Severity,
+    /// Line ranges each agent changed, when both share a base branch
+    pub lines: Option<(Vec<LineRange>, Vec<LineRange>)>,
+}
+
+/// Two agents whose branches change the same files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Overlap {
+    pub agents: (AgentId, AgentId),
+    /// Highest severity of the overlapping files
+    pub severity: Severity,
+    pub files: Vec<FileOverlap>,
+}
+
+impl Overlap {
+    /// The other agent of the pair, if `id` is one of them
+    pub fn other(&self, id: &AgentId) -> Option<&AgentId> {
+        if self.agents.0 == *id {
+            Some(&self.agents.1)
+        } else if self.agents.1 == *id {
+            Some(&self.agents.0)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse `git diff -U0` output into the changed line ranges of each file
+pub fn parse_diff(diff: &str) -> Vec<ChangedFile> {
+    let mut files: Vec<ChangedFile> = Vec::new();
+    let mut old_path: Option<String> = None;
+    // Removed or added lines can also start with `---` or `+++`
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            in_header = true;
+        } else if !in_header {
+            if line.starts_with("@@ ") {
+                if let (Some(file), Some(range)) = (files.last_mut(), parse_hunk_header(line)) {
+                    file.ranges.push(range);
+                }
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            old_path = path.strip_prefix("a/").map(str::to_string);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            // Added files have no old path, deleted files no new one
+            let path = path
+                .strip_prefix("b/")
+                .map(str::to_string)
+                .or(old_path.take());
+            if let Some(path) = path {
+                files.push(ChangedFile {
+                    path,
+                    ranges: Vec::new(),
+                });
+            }
+            in_header = false;
+        }
+    }
+    files
+}
+
+/// Old-side range of a `@@ -start,count +start,count @@` header
+fn parse_hunk_header(line: &str) -> Option<LineRange> {
+    let old = line.split_whitespace().nth(1)?.strip_prefix('-')?;
+    let (start, count) = match old.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (old.parse().ok()?, 1),
+    };
+    Some(match count {
+        0 => (start, start),
+        n => (start, start + n - 1),
+    })
+}
+
+/// Severity of two agents changing the same file: high if their line ranges
+/// overlap or touch, which git reports as a conflict, medium if they are
+/// within a few lines, and low otherwise
+fn file_severity(a: &[LineRange], b: &[LineRange]) -> Severity {
+    let gap = a
+        .iter()
+        .flat_map(|x| b.iter().map(move |y| (x, y)))
+        .map(|(x, y)| {
+            if x.1 < y.0 {
+                y.0 - x.1
+            } else {
+                x.0.saturating_sub(y.1)
+            }
+        })
+        .min();
+    match gap {
+        Some(gap) if gap <= 1 => Severity::High,
+        Some(gap) if gap <= NEAR_LINES => Severity::Medium,
+        _ => Severity::Low,
+    }
+}
+
+/// Pairs of agents whose changes touch the same files, most severe first.
+/// Line ranges are only compared between branches with the same base; other
+/// pairs sharing a file are rated medium.
+pub fn find_overlaps(changes: &[BranchChanges], only: Option<&AgentId>) -> Vec<Overlap> {
+    let mut overlaps = Vec::new();
+
+    for (i, a) in changes.iter().enumerate() {
+        for b in &changes[i + 1..] {
+            if only.is_some_and(|id| a.id != *id && b.id != *id) {
+                continue;
+            }
+            let same_base = a.base_branch == b.base_branch;
+            let files: Vec<FileOverlap> = a
+                .files
+                .iter()
+                .filter_map(|fa| {
+                    let fb = b.files.iter().find(|fb| fb.path == fa.path)?;
+                    Some(FileOverlap {
+                        path: fa.path.clone(),
+                        severity: if same_base {
+                            file_severity(&fa.ranges, &fb.ranges)
+                        } else {
+                            Severity::Medium
+                        },
+                        lines: same_base.then(|| (fa.ranges.clone(), fb.ranges.clone())),
+                    })
+                })
+                .collect();
+
+            if let Some(severity) = files.iter().map(|f| f.severity).max() {
+                overlaps.push(Overlap {
+                    agents: (a.id.clone(), b.id.clone()),
+                    severity,
+                    files,
+                });
+            }
+        }
+    }
+
+    overlaps.sort_by_key(|o| std::cmp::Reverse(o.severity));
+    overlaps
+}
+
+/// `10-12, 40` for display
+pub fn format_ranges(ranges: &[LineRange]) -> String {
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(id: &str, base: &str, files: &[(&str, &[(usize, usize)])]) -> BranchChanges {
+        BranchChanges {
+            id: AgentId(id.to_string()),
+            base_branch: base.to_string(),
+            files: files
+                .iter()
+                .map(|(path, ranges)| ChangedFile {
+                    path: path.to_string(),
+                    ranges: ranges.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_diff_reads_old_side_ranges() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,3 +10,4 @@ fn main() {
+--- a comment removed
++++ a line added
+@@ -40 +41 @@
+@@ -50,0 +52,2 @@
+diff --git a/new.txt b/new.txt
+new file mode 100644
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,3 @@
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1,5 +0,0 @@
+";
+        assert_eq!(
+            parse_diff(diff),
+            vec![
+                ChangedFile {
+                    path: "src/lib.rs".to_string(),
+                    ranges: vec![(10, 12), (40, 40), (50, 50)],
+                },
+                ChangedFile {
+                    path: "new.txt".to_string(),
+                    ranges: vec![(0, 0)],
+                },
+                ChangedFile {
+                    path: "old.txt".to_string(),
+                    ranges: vec![(1, 5)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_severity_by_distance() {
+        assert_eq!(file_severity(&[(10, 20)], &[(15, 16)]), Severity::High);
+        // Adjacent changes conflict in git too
+        assert_eq!(file_severity(&[(10, 20)], &[(21, 22)]), Severity::High);
+        assert_eq!(file_severity(&[(10, 20)], &[(23, 25)]), Severity::Medium);
+        assert_eq!(file_severity(&[(10, 20)], &[(100, 101)]), Severity::Low);
+        assert_eq!(
+            file_severity(&[(100, 101), (1, 2)], &[(3, 3)]),
+            Severity::High
+        );
+    }
+
+    #[test]
+    fn test_find_overlaps_pairs_agents_sharing_files() {
+        let all = [
+            changes("1", "main", &[("a.rs", &[(10, 20)]), ("b.rs", &[(1, 1)])]),
+            changes("2", "main", &[("b.rs", &[(100, 100)])]),
+            changes("3", "main", &[("a.rs", &[(15, 15)])]),
+            changes("4", "feature", &[("b.rs", &[(1, 1)])]),
+            changes("5", "main", &[("c.rs", &[(1, 1)])]),
+        ];
+
+        let overlaps = find_overlaps(&all, None);
+        let summary: Vec<(&str, &str, Severity)> = overlaps
+            .iter()
+            .map(|o| (o.agents.0 .0.as_str(), o.agents.1 .0.as_str(), o.severity))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("1", "3", Severity::High),
+                ("1", "4", Severity::Medium),
+                ("2", "4", Severity::Medium),
+                ("1", "2", Severity::Low),
+            ]
+        );
+        // Different bases have no comparable line numbers
+        assert!(overlaps[1].files[0].lines.is_none());
+        assert_eq!(
+            overlaps[0].other(&AgentId("3".to_string())),
+            Some(&AgentId("1".to_string()))
+        );
+
+        let of_two = find_overlaps(&all, Some(&AgentId("2".to_string())));
+        let pairs: Vec<(&str, &str)> = of_two
+            .iter()
+            .map(|o| (o.agents.0 .0.as_str(), o.agents.1 .0.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("2", "4"), ("1", "2")]);
+    }
+
+    #[test]
+    fn test_format_ranges() {
+        assert_eq!(format_ranges(&[(10, 12), (40, 40)]), "10-12, 40");
+    }
+}
@@ -0,0 +1,321 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Version of the status file format written by agents
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Outcome an agent reports in its status file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportStatus {
+    Completed,
+    Failed,
+    /// A plan waiting for approval, written by `wta report plan`
+    #[value(skip)]
+    Plan,
+}
+
+impl std::fmt::Display for ReportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportStatus::Completed => write!(f, "completed"),
+            ReportStatus::Failed => write!(f, "failed"),
+            ReportStatus::Plan => write!(f, "plan"),
+        }
+    }
+}
+
+/// Status file an agent writes when it finishes its task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusReport {
+    /// Reports written before the schema was versioned are treated as version 1
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    pub status: ReportStatus,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub files_changed: Vec<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Plan proposed by an agent launched with `--approve-plan`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>,
+    /// Problems found by a reviewer agent, recorded with `wta report finding`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+}
+
+fn default_schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+impl StatusReport {
+    /// Parse and validate a status file, describing what is wrong with it on failure
+    pub fn parse(content: &str) -> std::result::Result<Self, String> {
+        let report: Self = serde_json::from_str(content).map_err(|e| e.to_string())?;
+
+        if report.schema_version != SCHEMA_VERSION {
+            return Err(format!(
+                "unsupported schema_version {} (expected {SCHEMA_VERSION})",
+                report.schema_version
+            ));
+        }
+
+        Ok(report)
+    }
+}
+
+/// How much a review finding matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Low => write!(f, "low"),
+            Severity::Medium => write!(f, "medium"),
+            Severity::High => write!(f, "high"),
+        }
+    }
+}
+
+/// A problem a reviewer agent found in the reviewed branch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+impl Finding {
+    /// `file:line`, `file`, or nothing when the finding is not tied to a file
+    pub fn location(&self) -> Option<String> {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => Some(format!("{file}:{line}")),
+            (Some(file), None) => Some(file.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Progress update from a running agent. Each one also serves as a heartbeat.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    pub at: DateTime<Utc>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+}
+
+/// A question an agent asked with `wta ask`, and the user's answer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Question {
+    /// Position in the agent's questions log, from 1
+    pub number: u32,
+    pub at: DateTime<Utc>,
+    pub question: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answered_at: Option<DateTime<Utc>>,
+}
+
+impl Question {
+    pub fn is_open(&self) -> bool {
+        self.answer.is_none()
+    }
+}
+
+/// Line of a questions log. The agent appends questions and the user appends
+/// answers, so neither rewrites what the other wrote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum QuestionEvent {
+    Asked {
+        number: u32,
+        at: DateTime<Utc>,
+        question: String,
+    },
+    Answered {
+        number: u32,
+        at: DateTime<Utc>,
+        answer: String,
+    },
+}
+
+/// Parse a progress log, one JSON event per line. Malformed lines (such as a
+/// line cut short by a crash) are skipped.
+pub fn parse_progress(content: &str) -> Vec<ProgressEvent> {
+    parse_lines(content)
+}
+
+/// Parse a findings log, one JSON finding per line, skipping malformed lines
+pub fn parse_findings(content: &str) -> Vec<Finding> {
+    parse_lines(content)
+}
+
+/// Parse a questions log into its questions, in the order they were asked.
+/// Malformed lines and answers to unknown questions are skipped; a question
+/// keeps its first answer.
+pub fn parse_questions(content: &str) -> Vec<Question> {
+    let mut questions: Vec<Question> = Vec::new();
+    for event in parse_lines::<QuestionEvent>(content) {
+        match event {
+            QuestionEvent::Asked {
+                number,
+                at,
+                question,
+            } => questions.push(Question {
+                number,
+                at,
+                question,
+                answer: None,
+                answered_at: None,
+            }),
+            QuestionEvent::Answered { number, at, answer } => {
+                if let Some(q) = questions
+                    .iter_mut()
+                    .find(|q| q.number == number && q.is_open())
+                {
+                    q.answer = Some(answer);
+                    q.answered_at = Some(at);
+                }
+            }
+        }
+    }
+    questions
+}
+
+fn parse_lines<T: DeserializeOwned>(content: &str) -> Vec<T> {
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_report() {
+        let report = StatusReport::parse(
+            r#"{"schema_version": 1, "status": "completed", "summary": "Added login",
+                "files_changed": ["src/login.rs"], "error": null}"#,
+        )
+        .unwrap();
+
+        assert_eq!(report.status, ReportStatus::Completed);
+        assert_eq!(report.summary.as_deref(), Some("Added login"));
+        assert_eq!(report.files_changed, vec!["src/login.rs"]);
+        assert!(report.error.is_none());
+    }
+
+    #[test]
+    fn test_parse_unversioned_report() {
+        // Written by agents prompted before schema_version existed
+        let report = StatusReport::parse(r#"{"status": "failed", "error": "tests fail"}"#).unwrap();
+
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!(report.status, ReportStatus::Failed);
+        assert_eq!(report.error.as_deref(), Some("tests fail"));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_reports() {
+        assert!(StatusReport::parse("{\"status\": \"completed\"").is_err());
+        assert!(StatusReport::parse(r#"{"status": "done"}"#).is_err());
+        assert!(StatusReport::parse(r#"{"summary": "no status"}"#).is_err());
+        assert!(
+            StatusReport::parse(r#"{"status": "completed", "files_changed": "a.rs"}"#).is_err()
+        );
+
+        let err =
+            StatusReport::parse(r#"{"schema_version": 2, "status": "completed"}"#).unwrap_err();
+        assert!(err.contains("unsupported schema_version 2"));
+    }
+
+    #[test]
+    fn test_parse_plan_report() {
+        let report =
+            StatusReport::parse(r#"{"status": "plan", "plan": "1. Add the table\n2. Migrate"}"#)
+                .unwrap();
+        assert_eq!(report.status, ReportStatus::Plan);
+        assert_eq!(report.plan.as_deref(), Some("1. Add the table\n2. Migrate"));
+
+        // Plans are reported with `wta report plan`, not `--status`
+        assert!(ReportStatus::from_str("plan", true).is_err());
+    }
+
+    #[test]
+    fn test_parse_report_with_findings() {
+        let report = StatusReport::parse(
+            r#"{"status": "completed", "summary": "Two issues",
+                "findings": [
+                    {"severity": "high", "message": "Token is logged", "file": "src/auth.rs", "line": 42},
+                    {"severity": "low", "message": "Missing docs"}
+                ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(report.findings.len(), 2);
+        assert_eq!(report.findings[0].severity, Severity::High);
+        assert_eq!(
+            report.findings[0].location().as_deref(),
+            Some("src/auth.rs:42")
+        );
+        assert_eq!(report.findings[1].location(), None);
+    }
+
+    #[test]
+    fn test_parse_progress_skips_malformed_lines() {
+        let content = concat!(
+            r#"{"at":"2026-01-01T10:00:00Z","message":"reading code"}"#,
+            "\n",
+            r#"{"at":"2026-01-01T10:05:00Z","mess"#,
+            "\n",
+            r#"{"at":"2026-01-01T10:10:00Z","message":"writing tests","percent":60}"#,
+            "\n",
+        );
+
+        let events = parse_progress(content);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].message, "reading code");
+        assert_eq!(events[0].percent, None);
+        assert_eq!(events[1].message, "writing tests");
+        assert_eq!(events[1].percent, Some(60));
+    }
+
+    #[test]
+    fn test_parse_questions_matches_answers() {
+        let content = concat!(
+            r#"{"kind":"asked","number":1,"at":"2026-01-01T10:00:00Z","question":"sqlx or diesel?"}"#,
+            "\n",
+            r#"{"kind":"asked","number":2,"at":"2026-01-01T10:01:00Z","question":"Keep the old API?"}"#,
+            "\n",
+            r#"{"kind":"answered","number":1,"at":"2026-01-01T10:02:00Z","answer":"sqlx"}"#,
+            "\n",
+            r#"{"kind":"answered","number":1,"at":"2026-01-01T10:03:00Z","answer":"diesel"}"#,
+            "\n",
+            r#"{"kind":"answered","number":9,"at":"2026-01-01T10:03:00Z","answer":"?"}"#,
+            "\n",
+        );
+
+        let questions = parse_questions(content);
+        assert_eq!(questions.len(), 2);
+        assert_eq!(questions[0].answer.as_deref(), Some("sqlx"));
+        assert!(!questions[0].is_open());
+        assert!(questions[1].is_open());
+    }
+}
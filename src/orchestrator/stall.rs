@@ -0,0 +1,235 @@
+use super::AgentStatus;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How often an active agent's pane is captured, to bound tmux calls
+pub const PANE_CHECK_INTERVAL: TimeDelta = TimeDelta::seconds(30);
+/// Lines of pane output captured per check
+pub const PANE_LINES: usize = 50;
+/// No pane output, commits or heartbeats for this long marks an agent stalled
+pub const STALL_AFTER: TimeDelta = TimeDelta::minutes(10);
+/// A prompt must sit unchanged this long before the agent is flagged, so
+/// prompts answered automatically are not reported
+pub const NEEDS_INPUT_AFTER: TimeDelta = TimeDelta::seconds(30);
+
+/// Lines kept to show why an agent was flagged
+const EXCERPT_LINES: usize = 10;
+/// Only the bottom of the pane is searched for prompts
+const PROMPT_LINES: usize = 6;
+
+/// Permission prompts and questions from the supported agent CLIs
+const PROMPT_PATTERNS: &[&str] = &[
+    "do you want to",
+    "(y/n)",
+    "[y/n]",
+    "(yes/no)",
+    "❯ 1. yes",
+    "press enter to continue",
+    "enter to confirm",
+    "allow this",
+    "approve this",
+];
+
+/// Pane contents seen at the last check of an active agent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneActivity {
+    pub hash: u64,
+    /// When the pane contents last changed
+    pub changed_at: DateTime<Utc>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl PaneActivity {
+    /// Record a new capture of the pane
+    pub fn observe(previous: Option<&PaneActivity>, pane: &str, now: DateTime<Utc>) -> Self {
+        let hash = hash_pane(pane);
+        let changed_at = match previous {
+            Some(p) if p.hash == hash => p.changed_at,
+            _ => now,
+        };
+        Self {
+            hash,
+            changed_at,
+            checked_at: now,
+        }
+    }
+
+    /// Whether the pane is due to be captured again
+    pub fn due(&self, now: DateTime<Utc>) -> bool {
+        now - self.checked_at >= PANE_CHECK_INTERVAL
+    }
+}
+
+/// 64-bit FNV-1a of the pane, which unlike std's hasher stays the same
+/// across builds, as the hash is kept in the state file
+fn hash_pane(pane: &str) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    // tmux pads lines to the pane width; resizing the window is not activity
+    pane.lines()
+        .flat_map(|line| line.trim_end().bytes().chain([b'\n']))
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
+
+fn last_lines(pane: &str, count: usize) -> Vec<&str> {
+    let lines: Vec<&str> = pane
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(count)..].to_vec()
+}
+
+/// Whether the bottom of the pane shows a prompt waiting for an answer
+pub fn awaits_input(pane: &str) -> bool {
+    last_lines(pane, PROMPT_LINES).iter().any(|line| {
+        let line = line.to_lowercase();
+        PROMPT_PATTERNS.iter().any(|p| line.contains(p))
+    })
+}
+
+/// Last non-empty lines of the pane, shown with a stalled or waiting agent
+pub fn excerpt(pane: &str) -> String {
+    last_lines(pane, EXCERPT_LINES).join("\n")
+}
+
+/// Classify an active agent from its pane, last commit and last heartbeat
+pub fn classify(
+    pane: &str,
+    activity: &PaneActivity,
+    last_commit: Option<DateTime<Utc>>,
+    heartbeat: Option<DateTime<Utc>>,
+    launched_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> AgentStatus {
+    if now - activity.changed_at >= NEEDS_INPUT_AFTER && awaits_input(pane) {
+        return AgentStatus::NeedsInput;
+    }
+
+    let last_activity = [Some(activity.changed_at), last_commit, heartbeat]
+        .into_iter()
+        .flatten()
+        .fold(launched_at, DateTime::max);
+    if now - last_activity >= STALL_AFTER {
+        AgentStatus::Stalled
+    } else {
+        AgentStatus::Running
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERMISSION_PROMPT: &str = "\
+⏺ Bash(npm install)
+  Do you want to proceed?
+  ❯ 1. Yes
+    2. No
+";
+
+    fn activity(changed_at: DateTime<Utc>) -> PaneActivity {
+        PaneActivity {
+            hash: 0,
+            changed_at,
+            checked_at: changed_at,
+        }
+    }
+
+    #[test]
+    fn test_observe_tracks_when_pane_changed() {
+        let t0 = Utc::now();
+        let t1 = t0 + TimeDelta::minutes(1);
+
+        let first = PaneActivity::observe(None, "building", t0);
+        assert_eq!(first.changed_at, t0);
+
+        // Trailing padding from a resized window is not a change
+        let same = PaneActivity::observe(Some(&first), "building   ", t1);
+        assert_eq!(same.changed_at, t0);
+        assert_eq!(same.checked_at, t1);
+
+        let changed = PaneActivity::observe(Some(&first), "testing", t1);
+        assert_eq!(changed.changed_at, t1);
+    }
+
+    #[test]
+    fn test_hash_pane_is_stable() {
+        // Persisted hashes must still match after wta is rebuilt
+        assert_eq!(hash_pane("hello   \nworld"), 0x2925_8354_1038_8969);
+    }
+
+    #[test]
+    fn test_awaits_input_only_at_bottom_of_pane() {
+        assert!(awaits_input(PERMISSION_PROMPT));
+        assert!(awaits_input("Overwrite config? (y/N)\n\n"));
+        assert!(awaits_input(
+            " ❯ 1. Yes, I trust this folder\n   2. No, exit\n\n Enter to confirm · Esc to cancel\n"
+        ));
+
+        let answered = format!("{PERMISSION_PROMPT}{}", "compiling...\n".repeat(10));
+        assert!(!awaits_input(&answered));
+        assert!(!awaits_input("Running tests\nok\n"));
+    }
+
+    #[test]
+    fn test_classify_needs_input_once_prompt_settles() {
+        let now = Utc::now();
+        let launched = now - TimeDelta::minutes(5);
+
+        let fresh = activity(now - TimeDelta::seconds(5));
+        assert_eq!(
+            classify(PERMISSION_PROMPT, &fresh, None, None, launched, now),
+            AgentStatus::Running
+        );
+
+        let settled = activity(now - NEEDS_INPUT_AFTER);
+        assert_eq!(
+            classify(PERMISSION_PROMPT, &settled, None, None, launched, now),
+            AgentStatus::NeedsInput
+        );
+    }
+
+    #[test]
+    fn test_classify_stalled_without_any_activity() {
+        let now = Utc::now();
+        let long_ago = now - STALL_AFTER - TimeDelta::minutes(1);
+        let quiet = activity(long_ago);
+
+        assert_eq!(
+            classify("thinking", &quiet, None, None, long_ago, now),
+            AgentStatus::Stalled
+        );
+
+        // A recent commit or heartbeat means the agent is still working
+        let recent = Some(now - TimeDelta::minutes(1));
+        assert_eq!(
+            classify("thinking", &quiet, recent, None, long_ago, now),
+            AgentStatus::Running
+        );
+        assert_eq!(
+            classify("thinking", &quiet, None, recent, long_ago, now),
+            AgentStatus::Running
+        );
+    }
+
+    #[test]
+    fn test_excerpt_keeps_last_non_empty_lines() {
+        let pane = format!(
+            "{}\n\n   \nlast\n",
+            (1..=20)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        let excerpt = excerpt(&pane);
+        let lines: Vec<&str> = excerpt.lines().collect();
+
+        assert_eq!(lines.len(), EXCERPT_LINES);
+        assert_eq!(lines.first(), Some(&"12"));
+        assert_eq!(lines.last(), Some(&"last"));
+    }
+}
@@ -1,48 +1,157 @@
 use crate::error::{Error, Result};
-use crate::orchestrator::Agent;
+use crate::orchestrator::lock::FileLock;
+use crate::orchestrator::{Agent, Integration};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 const STATE_FILE: &str = "state.json";
+const LOCK_FILE: &str = "state.lock";
+
+/// Modification time of each state file as this process last wrote it, so
+/// file watchers can tell its own writes from those of other wta processes
+static OWN_WRITES: Mutex<BTreeMap<PathBuf, SystemTime>> = Mutex::new(BTreeMap::new());
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct State {
     #[serde(skip)]
     state_dir: PathBuf,
     next_id: u64,
+    /// Counter for attempt group IDs; missing from state files written before groups
+    #[serde(default = "first_group")]
+    next_group: u64,
     agents: Vec<Agent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    integrations: Vec<Integration>,
+    /// The state as last read from or written to disk, to tell our changes
+    /// from those of other wta processes when saving
+    #[serde(skip)]
+    base: Value,
+}
+
+fn first_group() -> u64 {
+    1
 }
 
 impl State {
     pub fn load_or_create(state_dir: &Path) -> Result<Self> {
         let state_file = state_dir.join(STATE_FILE);
 
-        if state_file.exists() {
-            let content = std::fs::read_to_string(&state_file)?;
-            let mut state: State =
-                serde_json::from_str(&content).map_err(|e| Error::StateCorrupted(e.to_string()))?;
-            state.state_dir = state_dir.to_path_buf();
-            Ok(state)
-        } else {
-            Ok(Self {
+        match read_state_file(&state_file)? {
+            Some(value) => Self::from_value(value, state_dir),
+            None => Ok(Self {
                 state_dir: state_dir.to_path_buf(),
                 next_id: 1,
+                next_group: first_group(),
                 agents: Vec::new(),
-            })
+                integrations: Vec::new(),
+                base: Value::Null,
+            }),
         }
     }
 
-    pub fn save(&self) -> Result<()> {
+    fn from_value(value: Value, state_dir: &Path) -> Result<Self> {
+        let mut state: State = serde_json::from_value(value.clone())
+            .map_err(|e| Error::StateCorrupted(e.to_string()))?;
+        state.state_dir = state_dir.to_path_buf();
+        state.base = value;
+        Ok(state)
+    }
+
+    /// Write the state, keeping what other wta processes (the daemon, the
+    /// dashboard, other commands) saved since it was loaded: each agent field
+    /// changed here is written over theirs, the rest is taken from disk, and
+    /// the result becomes the state in memory.
+    pub fn save(&mut self) -> Result<()> {
+        let _lock = FileLock::acquire(self.state_dir.join(LOCK_FILE))?;
         let state_file = self.state_dir.join(STATE_FILE);
-        let content = serde_json::to_string_pretty(&self)?;
-        std::fs::write(state_file, content)?;
+
+        let ours = serde_json::to_value(&*self)?;
+        let merged = match read_state_file(&state_file)? {
+            Some(theirs) => merge_state(&self.base, ours, theirs),
+            None => ours,
+        };
+        self.write(&merged)?;
+
+        *self = Self::from_value(merged, &self.state_dir)?;
         Ok(())
     }
 
-    pub fn next_id(&mut self) -> u64 {
-        let id = self.next_id;
-        self.next_id += 1;
-        id
+    /// Replace the state file with `value`. Readers never see a partly
+    /// written file. The caller holds the lock.
+    fn write(&self, value: &Value) -> Result<()> {
+        let tmp = self
+            .state_dir
+            .join(format!("{STATE_FILE}.{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+        let state_file = self.state_dir.join(STATE_FILE);
+        std::fs::rename(&tmp, &state_file)?;
+
+        if let Ok(modified) = std::fs::metadata(&state_file).and_then(|m| m.modified()) {
+            if let Ok(mut writes) = OWN_WRITES.lock() {
+                writes.insert(state_file, modified);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a change to `path` comes from this process's own bookkeeping:
+    /// the state lock, a temporary state file, or the state file unchanged
+    /// since this process last wrote it
+    pub fn is_own_write(path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+        if name == LOCK_FILE || name.starts_with(&format!("{STATE_FILE}.")) {
+            return true;
+        }
+        if name != STATE_FILE {
+            return false;
+        }
+        let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+            return false;
+        };
+        OWN_WRITES
+            .lock()
+            .is_ok_and(|writes| writes.get(path) == Some(&modified))
+    }
+
+    /// Take the next agent ID
+    pub fn next_id(&mut self) -> Result<u64> {
+        let id = self.take("next_id", self.next_id)?;
+        self.next_id = id + 1;
+        Ok(id)
+    }
+
+    /// Allocate an ID for a new attempt group: `g1`, `g2`, ...
+    pub fn next_group_id(&mut self) -> Result<String> {
+        let id = self.take("next_group", self.next_group)?;
+        self.next_group = id + 1;
+        Ok(format!("g{id}"))
+    }
+
+    /// Take the value of the counter `key`, reading it from disk and bumping
+    /// it there under the lock, so no other wta process takes the same one
+    fn take(&mut self, key: &str, ours: u64) -> Result<u64> {
+        let _lock = FileLock::acquire(self.state_dir.join(LOCK_FILE))?;
+        match read_state_file(&self.state_dir.join(STATE_FILE))? {
+            Some(mut theirs) => {
+                let id = theirs[key].as_u64().map_or(ours, |n| n.max(ours));
+                theirs[key] = Value::from(id + 1);
+                self.write(&theirs)?;
+                Ok(id)
+            }
+            None => {
+                let mut ours_value = serde_json::to_value(&*self)?;
+                ours_value[key] = Value::from(ours + 1);
+                self.write(&ours_value)?;
+                self.base = ours_value;
+                Ok(ours)
+            }
+        }
     }
 
     pub fn add_agent(&mut self, agent: Agent) -> Result<()> {
@@ -68,12 +177,124 @@ impl State {
         self.agents.retain(|a| a.id.0 != id && a.branch != id);
         self.save()
     }
+
+    pub fn integrations(&self) -> &[Integration] {
+        &self.integrations
+    }
+
+    /// Record an integration branch, replacing any earlier one of the same name
+    pub fn set_integration(&mut self, integration: Integration) -> Result<()> {
+        self.integrations.retain(|i| i.branch != integration.branch);
+        self.integrations.push(integration);
+        self.save()
+    }
+
+    /// Forget integration branches whose worktree was removed
+    pub fn prune_integrations(&mut self) -> Result<()> {
+        let before = self.integrations.len();
+        self.integrations.retain(|i| i.worktree_path.exists());
+        if self.integrations.len() != before {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+fn read_state_file(path: &Path) -> Result<Option<Value>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| Error::StateCorrupted(e.to_string()))
+}
+
+/// Three-way merge of the state from `base`, the state it was loaded as,
+/// to `ours` and `theirs`, the state now on disk
+fn merge_state(base: &Value, ours: Value, theirs: Value) -> Value {
+    let (Value::Object(ours), Value::Object(mut merged)) = (ours, theirs) else {
+        return Value::Null;
+    };
+    for (key, value) in ours {
+        let merged_value = match (key.as_str(), merged.remove(&key)) {
+            // Counters only move forward. IDs are taken from the file itself
+            // (see `take`), so this only keeps a stale copy from moving it back.
+            ("next_id" | "next_group", Some(theirs)) => {
+                if value.as_u64() >= theirs.as_u64() {
+                    value
+                } else {
+                    theirs
+                }
+            }
+            ("agents", Some(theirs)) => merge_list(&base[&key], &value, &theirs, "id"),
+            ("integrations", Some(theirs)) => merge_list(&base[&key], &value, &theirs, "branch"),
+            (_, Some(theirs)) if base.get(&key) == Some(&value) => theirs,
+            _ => value,
+        };
+        merged.insert(key, merged_value);
+    }
+    Value::Object(merged)
+}
+
+/// Merge lists of objects identified by their `key` field. Items added or
+/// removed on either side are added or removed; items on both are merged
+/// field by field.
+fn merge_list(base: &Value, ours: &Value, theirs: &Value, key: &str) -> Value {
+    let items = |list: &Value| list.as_array().cloned().unwrap_or_default();
+    let (base, ours, theirs) = (items(base), items(ours), items(theirs));
+    let find = |list: &[Value], id: &Value| list.iter().find(|v| v.get(key) == Some(id)).cloned();
+
+    let mut merged = Vec::new();
+    for item in &ours {
+        let id = &item[key];
+        let original = find(&base, id);
+        match find(&theirs, id) {
+            Some(their_item) => {
+                merged.push(merge_fields(original.as_ref(), item.clone(), their_item))
+            }
+            // Removed elsewhere
+            None if original.is_some() => {}
+            None => merged.push(item.clone()),
+        }
+    }
+    for item in theirs {
+        let id = &item[key];
+        // Added elsewhere; an item removed here stays removed
+        if find(&ours, id).is_none() && find(&base, id).is_none() {
+            merged.push(item);
+        }
+    }
+    Value::Array(merged)
+}
+
+/// Take the fields changed from `base` in `ours`, and the others from `theirs`
+fn merge_fields(base: Option<&Value>, ours: Value, theirs: Value) -> Value {
+    let (Value::Object(ours), Value::Object(mut merged)) = (ours, theirs) else {
+        return Value::Null;
+    };
+    let field = |key: &str| base.and_then(|b| b.get(key));
+    // Fields cleared here are left out when serialized
+    if let Some(Value::Object(base)) = base {
+        for (key, value) in base {
+            if !ours.contains_key(key) && merged.get(key) == Some(value) {
+                merged.remove(key);
+            }
+        }
+    }
+    for (key, value) in ours {
+        if field(&key) != Some(&value) {
+            merged.insert(key, value);
+        }
+    }
+    Value::Object(merged)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::orchestrator::AgentStatus;
+    use std::time::Duration;
     use tempfile::TempDir;
 
     #[test]
@@ -90,9 +311,73 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let mut state = State::load_or_create(temp_dir.path()).unwrap();
 
-        assert_eq!(state.next_id(), 1);
-        assert_eq!(state.next_id(), 2);
-        assert_eq!(state.next_id(), 3);
+        assert_eq!(state.next_id().unwrap(), 1);
+        assert_eq!(state.next_id().unwrap(), 2);
+        assert_eq!(state.next_id().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_state_is_own_write_until_another_process_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let state_file = temp_dir.path().join("state.json");
+        assert!(State::is_own_write(&temp_dir.path().join("state.lock")));
+        assert!(State::is_own_write(&temp_dir.path().join("state.json.123")));
+        assert!(!State::is_own_write(&state_file));
+
+        State::load_or_create(temp_dir.path())
+            .unwrap()
+            .save()
+            .unwrap();
+        assert!(State::is_own_write(&state_file));
+
+        // Written by someone else, as far as this process knows
+        let content = std::fs::read_to_string(&state_file).unwrap();
+        let modified = std::fs::metadata(&state_file).unwrap().modified().unwrap();
+        std::fs::write(&state_file, content).unwrap();
+        let file = std::fs::File::options()
+            .write(true)
+            .open(&state_file)
+            .unwrap();
+        file.set_modified(modified + std::time::Duration::from_secs(1))
+            .unwrap();
+        assert!(!State::is_own_write(&state_file));
+    }
+
+    #[test]
+    fn test_state_next_id_is_not_taken_twice_by_stale_copies() {
+        let temp_dir = TempDir::new().unwrap();
+        State::load_or_create(temp_dir.path())
+            .unwrap()
+            .save()
+            .unwrap();
+        let mut first = State::load_or_create(temp_dir.path()).unwrap();
+        let mut second = State::load_or_create(temp_dir.path()).unwrap();
+
+        assert_eq!(first.next_id().unwrap(), 1);
+        assert_eq!(second.next_id().unwrap(), 2);
+        assert_eq!(second.next_group_id().unwrap(), "g1");
+        assert_eq!(first.next_group_id().unwrap(), "g2");
+        first.save().unwrap();
+        second.save().unwrap();
+
+        let state = State::load_or_create(temp_dir.path()).unwrap();
+        assert_eq!(state.next_id, 3);
+        assert_eq!(state.next_group, 3);
+    }
+
+    #[test]
+    fn test_state_next_group_id_defaults_for_old_state_files() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("state.json"),
+            r#"{"next_id": 4, "agents": []}"#,
+        )
+        .unwrap();
+        let mut state = State::load_or_create(temp_dir.path()).unwrap();
+
+        assert_eq!(state.next_group_id().unwrap(), "g1");
+        assert_eq!(state.next_group_id().unwrap(), "g2");
+        assert_eq!(state.next_id().unwrap(), 4);
     }
 
     #[test]
@@ -116,10 +401,10 @@ mod tests {
         state.add_agent(Agent::create_test_agent(2)).unwrap();
 
         let agent = state.get_agent("1").unwrap();
-        assert_eq!(agent.task, "Task 1");
+        assert_eq!(agent.task(), "Task 1");
 
         let agent = state.get_agent("2").unwrap();
-        assert_eq!(agent.task, "Task 2");
+        assert_eq!(agent.task(), "Task 2");
 
         assert!(state.get_agent("99").is_none());
     }
@@ -134,10 +419,10 @@ mod tests {
 
         // Access by branch name
         let agent = state.get_agent("wta/1").unwrap();
-        assert_eq!(agent.task, "Task 1");
+        assert_eq!(agent.task(), "Task 1");
 
         let agent = state.get_agent("wta/2").unwrap();
-        assert_eq!(agent.task, "Task 2");
+        assert_eq!(agent.task(), "Task 2");
 
         // Non-existent branch
         assert!(state.get_agent("wta/99").is_none());
@@ -201,8 +486,8 @@ mod tests {
         // Create state and add agents
         {
             let mut state = State::load_or_create(temp_dir.path()).unwrap();
-            let _ = state.next_id(); // Consume ID 1
-            let _ = state.next_id(); // Consume ID 2
+            state.next_id().unwrap(); // Consume ID 1
+            state.next_id().unwrap(); // Consume ID 2
             state.add_agent(Agent::create_test_agent(1)).unwrap();
             state.add_agent(Agent::create_test_agent(2)).unwrap();
             state.save().unwrap();
@@ -213,8 +498,8 @@ mod tests {
             let state = State::load_or_create(temp_dir.path()).unwrap();
             assert_eq!(state.next_id, 3); // Next ID should be 3
             assert_eq!(state.agents().len(), 2);
-            assert_eq!(state.get_agent("1").unwrap().task, "Task 1");
-            assert_eq!(state.get_agent("2").unwrap().task, "Task 2");
+            assert_eq!(state.get_agent("1").unwrap().task(), "Task 1");
+            assert_eq!(state.get_agent("2").unwrap().task(), "Task 2");
         }
     }
 
@@ -260,4 +545,111 @@ mod tests {
             e => panic!("Expected StateCorrupted error, got: {e:?}"),
         }
     }
+
+    #[test]
+    fn test_state_set_integration_replaces_same_branch() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut state = State::load_or_create(temp_dir.path()).unwrap();
+        let integration = |branch: &str, path: PathBuf| Integration {
+            branch: branch.to_string(),
+            base_branch: "main".to_string(),
+            worktree_path: path,
+            created_at: chrono::Utc::now(),
+            agents: Vec::new(),
+        };
+
+        state
+            .set_integration(integration("integration/x", temp_dir.path().join("gone")))
+            .unwrap();
+        state
+            .set_integration(integration("integration/y", temp_dir.path().join("gone")))
+            .unwrap();
+        state
+            .set_integration(integration("integration/x", temp_dir.path().to_path_buf()))
+            .unwrap();
+        let branches: Vec<&str> = state
+            .integrations()
+            .iter()
+            .map(|i| i.branch.as_str())
+            .collect();
+        assert_eq!(branches, vec!["integration/y", "integration/x"]);
+
+        state.prune_integrations().unwrap();
+        let state = State::load_or_create(temp_dir.path()).unwrap();
+        assert_eq!(state.integrations().len(), 1);
+        assert_eq!(state.integrations()[0].branch, "integration/x");
+    }
+
+    #[test]
+    fn test_state_save_keeps_changes_saved_by_others() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut state = State::load_or_create(temp_dir.path()).unwrap();
+        state.add_agent(Agent::create_test_agent(1)).unwrap();
+        state.add_agent(Agent::create_test_agent(2)).unwrap();
+        state.add_agent(Agent::create_test_agent(3)).unwrap();
+
+        let mut ours = State::load_or_create(temp_dir.path()).unwrap();
+        let mut theirs = State::load_or_create(temp_dir.path()).unwrap();
+
+        // Different fields of the same agent, and different agents
+        theirs.get_agent_mut("1").unwrap().status = AgentStatus::Completed;
+        theirs.get_agent_mut("2").unwrap().status = AgentStatus::Failed;
+        theirs.next_id = 5;
+        theirs.add_agent(Agent::create_test_agent(4)).unwrap();
+        ours.get_agent_mut("1").unwrap().completed_at = Some(chrono::Utc::now());
+        ours.remove_agent("3").unwrap();
+
+        let state = State::load_or_create(temp_dir.path()).unwrap();
+        let agent = state.get_agent("1").unwrap();
+        assert_eq!(agent.status, AgentStatus::Completed);
+        assert!(agent.completed_at.is_some());
+        assert_eq!(state.get_agent("2").unwrap().status, AgentStatus::Failed);
+        assert!(state.get_agent("3").is_none());
+        assert!(state.get_agent("4").is_some());
+        assert_eq!(state.next_id, 5);
+
+        // The merged state is what the saving side now holds
+        assert_eq!(ours.get_agent("1").unwrap().status, AgentStatus::Completed);
+        assert!(ours.get_agent("4").is_some());
+    }
+
+    #[test]
+    fn test_state_save_keeps_agents_removed_by_others_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut state = State::load_or_create(temp_dir.path()).unwrap();
+        state.add_agent(Agent::create_test_agent(1)).unwrap();
+        state.add_agent(Agent::create_test_agent(2)).unwrap();
+
+        let mut ours = State::load_or_create(temp_dir.path()).unwrap();
+        let mut theirs = State::load_or_create(temp_dir.path()).unwrap();
+        theirs.remove_agent("1").unwrap();
+        ours.get_agent_mut("2").unwrap().status = AgentStatus::Completed;
+        ours.save().unwrap();
+
+        let state = State::load_or_create(temp_dir.path()).unwrap();
+        assert!(state.get_agent("1").is_none());
+        assert_eq!(state.get_agent("2").unwrap().status, AgentStatus::Completed);
+    }
+
+    #[test]
+    fn test_state_save_waits_for_lock_and_releases_it() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock = temp_dir.path().join(LOCK_FILE);
+        std::fs::write(&lock, "").unwrap();
+
+        let held = std::thread::spawn({
+            let lock = lock.clone();
+            move || {
+                std::thread::sleep(Duration::from_millis(50));
+                std::fs::remove_file(lock).unwrap();
+            }
+        });
+        let mut state = State::load_or_create(temp_dir.path()).unwrap();
+        state.add_agent(Agent::create_test_agent(1)).unwrap();
+        held.join().unwrap();
+
+        assert!(!lock.exists());
+        let state = State::load_or_create(temp_dir.path()).unwrap();
+        assert!(state.get_agent("1").is_some());
+    }
 }
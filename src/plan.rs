@@ -0,0 +1,188 @@
+use crate::error::{Error, Result};
+use crate::provider::Provider;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const PLAN_PROMPT: &str = r#"Split the task below into subtasks that separate coding agents can work on in parallel, each on its own git branch. Look at the repository in the current directory to decide how to split it.
+
+Prefer a few independent subtasks that touch different files. When a subtask needs another one's changes, list that subtask's id in its depends_on; it then starts from that subtask's branch once it completes. Do not split work that is too small to be worth a separate branch.
+
+Respond with ONLY a JSON array, no other text, where each element is:
+{"id": "short-kebab-case-name", "task": "complete instructions for the agent, including the context it needs", "branch": "feature/short-name", "depends_on": ["id of another subtask"]}
+
+Task:
+"#;
+
+const PLAN_HEADER: &str = "\
+# Review the plan below; each [[tasks]] entry is launched as an agent.
+# Edit, add or remove entries. Besides id, task, branch and depends_on,
+# entries accept base, provider, provider_args and timeout, as in
+# `wta launch --from`. Delete every entry to abort.
+";
+
+/// A subtask proposed by the provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedTask {
+    pub id: String,
+    pub task: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+/// Plans are edited as a TOML launch manifest
+#[derive(Serialize)]
+struct PlanManifest<'a> {
+    tasks: &'a [PlannedTask],
+}
+
+/// Ask `provider` to split `task` into subtasks, running it in print mode
+/// in `dir` so it can look at the repository
+pub fn generate(provider: Provider, task: &str, dir: &Path) -> Result<Vec<PlannedTask>> {
+    let args = provider
+        .print_args()
+        .ok_or_else(|| Error::PlanUnsupported(provider.to_string()))?;
+    let binary = provider.binary_name();
+
+    let mut child = Command::new(binary)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::PlanFailed(format!("Failed to run {binary}: {e}")))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(format!("{PLAN_PROMPT}{task}\n").as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .map(str::trim)
+            .unwrap_or("no error output");
+        return Err(Error::PlanFailed(format!(
+            "{binary} exited with {}: {reason}",
+            output.status
+        )));
+    }
+
+    parse_plan(&String::from_utf8_lossy(&output.stdout)).map_err(Error::PlanFailed)
+}
+
+/// Parse the JSON array in a provider's response, which may have extra text
+/// around it. Dependencies that name no other subtask are dropped.
+fn parse_plan(response: &str) -> std::result::Result<Vec<PlannedTask>, String> {
+    let (Some(start), Some(end)) = (response.find('['), response.rfind(']')) else {
+        return Err("no JSON array in the response".to_string());
+    };
+    if end < start {
+        return Err("no JSON array in the response".to_string());
+    }
+    let mut plan: Vec<PlannedTask> =
+        serde_json::from_str(&response[start..=end]).map_err(|e| e.to_string())?;
+
+    if plan.is_empty() {
+        return Err("no subtasks in the response".to_string());
+    }
+
+    let ids: HashSet<String> = plan.iter().map(|t| t.id.clone()).collect();
+    for task in &mut plan {
+        let id = task.id.clone();
+        task.depends_on.retain(|d| *d != id && ids.contains(d));
+    }
+
+    Ok(plan)
+}
+
+/// Render a plan as a commented TOML manifest for editing
+pub fn to_toml(task: &str, plan: &[PlannedTask]) -> Result<String> {
+    let tasks = toml::to_string(&PlanManifest { tasks: plan })
+        .map_err(|e| Error::InvalidPlan(e.to_string()))?;
+    let request = task
+        .lines()
+        .map(|l| format!("#   {l}").trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(format!("{PLAN_HEADER}#\n# Plan for:\n{request}\n\n{tasks}"))
+}
+
+/// True if an edited plan has nothing left but comments and blank lines
+pub fn is_empty(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .all(|l| l.is_empty() || l.starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest;
+
+    #[test]
+    fn test_parse_plan_with_surrounding_text() {
+        let response = r#"Here is the plan:
+[
+  {"id": "api", "task": "Write the API", "branch": "feature/api"},
+  {"id": "client", "task": "Write the client", "depends_on": ["api", "7", "client"]}
+]
+Good luck!"#;
+        let plan = parse_plan(response).unwrap();
+
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].branch.as_deref(), Some("feature/api"));
+        assert!(plan[1].branch.is_none());
+        // Unknown and self dependencies are dropped
+        assert_eq!(plan[1].depends_on, vec!["api"]);
+    }
+
+    #[test]
+    fn test_parse_plan_rejects_missing_or_empty_array() {
+        assert!(parse_plan("I cannot split this task").is_err());
+        assert!(parse_plan("[]").is_err());
+        assert!(parse_plan(r#"[{"task": "no id"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_to_toml_round_trips_as_manifest() {
+        let plan = vec![
+            PlannedTask {
+                id: "api".to_string(),
+                task: "Write the API\nwith # in it".to_string(),
+                branch: Some("feature/api".to_string()),
+                depends_on: Vec::new(),
+            },
+            PlannedTask {
+                id: "client".to_string(),
+                task: "Write the client".to_string(),
+                branch: None,
+                depends_on: vec!["api".to_string()],
+            },
+        ];
+        let content = to_toml("Build it\nall", &plan).unwrap();
+        assert!(content.contains("#   all\n"));
+
+        let entries = manifest::parse(&content, true).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id.as_deref(), Some("api"));
+        assert_eq!(entries[0].task, "Write the API\nwith # in it");
+        assert_eq!(entries[0].branch.as_deref(), Some("feature/api"));
+        assert_eq!(entries[1].depends_on, vec!["api"]);
+    }
+
+    #[test]
+    fn test_is_empty_ignores_comments() {
+        assert!(is_empty("# Plan for:\n#   x\n\n"));
+        assert!(!is_empty("# header\n[[tasks]]\ntask = \"x\"\n"));
+    }
+}
@@ -47,24 +47,56 @@ impl Provider {
         }
     }
 
+    /// Arguments that resume the provider's previous session in
+    /// `worktree_path`, when it supports that and a session exists
+    pub fn resume_args(&self, worktree_path: &Path) -> Vec<String> {
+        match self {
+            Provider::Claude if claude_session_exists(worktree_path) => {
+                vec!["--continue".to_string()]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Arguments that keep the agent from changing the worktree, for
+    /// providers that support it; others are only told so in the prompt
+    pub fn read_only_args(&self) -> Vec<String> {
+        match self {
+            Provider::Claude => vec![
+                "--disallowedTools".to_string(),
+                "'Edit,Write,NotebookEdit,Bash(git add:*),Bash(git commit:*)'".to_string(),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Arguments that make the provider answer the prompt on stdin once and
+    /// print the response, for providers that have such a mode
+    pub fn print_args(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Provider::Claude => Some(&["--print", "--output-format", "text"]),
+            // `codex exec` runs in a read-only sandbox unless told otherwise
+            Provider::Codex => Some(&["exec", "-"]),
+            // Gemini runs non-interactively when stdin is not a terminal
+            Provider::Gemini => Some(&[]),
+            _ => None,
+        }
+    }
+
     /// Build the command string to run the AI agent
     ///
     /// # Arguments
     /// * `worktree_path` - Path to the worktree directory
     /// * `prompt_file` - Path to the prompt file
-    /// * `status_file` - Path to the status file
     /// * `extra_args` - Extra arguments to pass to the provider
     pub fn build_command(
         &self,
         worktree_path: &Path,
         prompt_file: &Path,
-        status_file: &Path,
         extra_args: &[String],
     ) -> String {
         match self {
-            Provider::Claude => {
-                self.build_claude_command(worktree_path, prompt_file, status_file, extra_args)
-            }
+            Provider::Claude => self.build_claude_command(worktree_path, prompt_file, extra_args),
             Provider::Codex => self.build_codex_command(worktree_path, prompt_file, extra_args),
             Provider::Gemini => self.build_gemini_command(worktree_path, prompt_file, extra_args),
             Provider::Deepagents => {
@@ -81,7 +113,6 @@ impl Provider {
         &self,
         worktree_path: &Path,
         prompt_file: &Path,
-        status_file: &Path,
         extra_args: &[String],
     ) -> String {
         // Check if dangerously-allow-all is in extra_args
@@ -127,6 +158,9 @@ impl Provider {
             "Bash(git commit:*)",
             "Bash(ls:*)",
             "Bash(pwd)",
+            // Lets the agent report completion and ask the user questions
+            "Bash(wta report:*)",
+            "Bash(wta ask:*)",
         ];
 
         // Add Edit and Read tools if requested
@@ -135,18 +169,7 @@ impl Provider {
             default_allowed_tools.insert(1, "Read");
         }
 
-        // Allow writing to the status directory so agent can report completion
-        // Use directory wildcard pattern to ensure permission is granted
-        let status_dir = status_file
-            .parent()
-            .map(|p| p.display().to_string())
-            .unwrap_or_default();
-        let status_file_pattern = format!("Write({status_dir}/*)");
-        let allowed_tools_arg = format!(
-            "--allowedTools '{},{}'",
-            default_allowed_tools.join(","),
-            status_file_pattern
-        );
+        let allowed_tools_arg = format!("--allowedTools '{}'", default_allowed_tools.join(","));
 
         format!(
             "cd {} && cat {} | claude --permission-mode acceptEdits {allowed_tools_arg}{extra_args_str}",
@@ -272,6 +295,32 @@ impl Provider {
     }
 }
 
+/// Claude keeps the conversations of each directory under
+/// `~/.claude/projects/<directory with non-alphanumerics replaced by ->`
+fn claude_session_exists(worktree_path: &Path) -> bool {
+    let Ok(home) = std::env::var("HOME") else {
+        return false;
+    };
+    let sessions = Path::new(&home)
+        .join(".claude")
+        .join("projects")
+        .join(claude_project_dir(worktree_path));
+    std::fs::read_dir(sessions)
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.path().extension().is_some_and(|ext| ext == "jsonl"))
+        })
+        .unwrap_or(false)
+}
+
+fn claude_project_dir(path: &Path) -> String {
+    path.to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,29 +397,28 @@ mod tests {
     fn test_build_claude_command() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status/agent.json");
 
-        let cmd = Provider::Claude.build_command(&worktree, &prompt, &status, &[]);
+        let cmd = Provider::Claude.build_command(&worktree, &prompt, &[]);
 
         assert!(cmd.contains("cd /tmp/worktree"));
         assert!(cmd.contains("cat /tmp/prompt.txt"));
         assert!(cmd.contains("claude --permission-mode acceptEdits"));
         assert!(cmd.contains("--allowedTools"));
-        assert!(cmd.contains("Write(/tmp/status/*)"));
+        assert!(cmd.contains("Bash(wta report:*)"));
+        assert!(!cmd.contains("Write("));
     }
 
     #[test]
     fn test_build_claude_command_with_extra_args() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status/agent.json");
         let extra_args = vec![
             "--verbose".to_string(),
             "--model".to_string(),
             "opus".to_string(),
         ];
 
-        let cmd = Provider::Claude.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Claude.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("--verbose --model opus"));
     }
@@ -379,10 +427,9 @@ mod tests {
     fn test_build_claude_command_dangerously_allow_all() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status/agent.json");
         let extra_args = vec!["--dangerously-allow-all".to_string()];
 
-        let cmd = Provider::Claude.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Claude.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("claude --dangerously-allow-all"));
         // Should not contain allowedTools when dangerously-allow-all is used
@@ -394,10 +441,9 @@ mod tests {
     fn test_build_claude_command_enable_edits() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status/agent.json");
         let extra_args = vec!["--enable-edits".to_string()];
 
-        let cmd = Provider::Claude.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Claude.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("claude --permission-mode acceptEdits"));
         assert!(cmd.contains("--allowedTools"));
@@ -407,13 +453,24 @@ mod tests {
         assert!(!cmd.contains("--enable-edits"));
     }
 
+    #[test]
+    fn test_build_claude_command_read_only() {
+        let worktree = PathBuf::from("/tmp/worktree");
+        let prompt = PathBuf::from("/tmp/prompt.txt");
+
+        let cmd =
+            Provider::Claude.build_command(&worktree, &prompt, &Provider::Claude.read_only_args());
+
+        assert!(cmd.contains("--disallowedTools 'Edit,Write,NotebookEdit,"));
+        assert!(Provider::Codex.read_only_args().is_empty());
+    }
+
     #[test]
     fn test_build_codex_command() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
 
-        let cmd = Provider::Codex.build_command(&worktree, &prompt, &status, &[]);
+        let cmd = Provider::Codex.build_command(&worktree, &prompt, &[]);
 
         assert!(cmd.contains("cd /tmp/worktree"));
         assert!(cmd.contains("cat /tmp/prompt.txt"));
@@ -425,10 +482,9 @@ mod tests {
     fn test_build_codex_command_with_extra_args() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
         let extra_args = vec!["--model".to_string(), "o3".to_string()];
 
-        let cmd = Provider::Codex.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Codex.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("--model o3"));
         assert!(cmd.ends_with(" -"));
@@ -438,9 +494,8 @@ mod tests {
     fn test_build_gemini_command() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
 
-        let cmd = Provider::Gemini.build_command(&worktree, &prompt, &status, &[]);
+        let cmd = Provider::Gemini.build_command(&worktree, &prompt, &[]);
 
         assert!(cmd.contains("cd /tmp/worktree"));
         assert!(cmd.contains("cat /tmp/prompt.txt"));
@@ -451,10 +506,9 @@ mod tests {
     fn test_build_gemini_command_with_extra_args() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
         let extra_args = vec!["--sandbox".to_string()];
 
-        let cmd = Provider::Gemini.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Gemini.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("--sandbox"));
     }
@@ -463,9 +517,8 @@ mod tests {
     fn test_build_deepagents_command() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
 
-        let cmd = Provider::Deepagents.build_command(&worktree, &prompt, &status, &[]);
+        let cmd = Provider::Deepagents.build_command(&worktree, &prompt, &[]);
 
         assert!(cmd.contains("cd /tmp/worktree"));
         assert!(cmd.contains("cat /tmp/prompt.txt"));
@@ -476,10 +529,9 @@ mod tests {
     fn test_build_deepagents_command_with_extra_args() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
         let extra_args = vec!["--agent".to_string(), "backend-dev".to_string()];
 
-        let cmd = Provider::Deepagents.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Deepagents.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("--agent backend-dev"));
     }
@@ -488,9 +540,8 @@ mod tests {
     fn test_build_amp_command() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
 
-        let cmd = Provider::Amp.build_command(&worktree, &prompt, &status, &[]);
+        let cmd = Provider::Amp.build_command(&worktree, &prompt, &[]);
 
         assert!(cmd.contains("cd /tmp/worktree"));
         assert!(cmd.contains("cat /tmp/prompt.txt"));
@@ -501,10 +552,9 @@ mod tests {
     fn test_build_amp_command_with_extra_args() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
         let extra_args = vec!["--mode".to_string(), "rush".to_string()];
 
-        let cmd = Provider::Amp.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Amp.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("--mode rush"));
     }
@@ -513,10 +563,9 @@ mod tests {
     fn test_build_amp_command_dangerously_allow_all() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
         let extra_args = vec!["--dangerously-allow-all".to_string()];
 
-        let cmd = Provider::Amp.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Amp.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("amp --dangerously-allow-all"));
     }
@@ -525,9 +574,8 @@ mod tests {
     fn test_build_opencode_command() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
 
-        let cmd = Provider::Opencode.build_command(&worktree, &prompt, &status, &[]);
+        let cmd = Provider::Opencode.build_command(&worktree, &prompt, &[]);
 
         assert!(cmd.contains("cd /tmp/worktree"));
         assert!(cmd.contains("cat /tmp/prompt.txt"));
@@ -538,14 +586,49 @@ mod tests {
     fn test_build_opencode_command_with_extra_args() {
         let worktree = PathBuf::from("/tmp/worktree");
         let prompt = PathBuf::from("/tmp/prompt.txt");
-        let status = PathBuf::from("/tmp/status.json");
         let extra_args = vec!["--verbose".to_string()];
 
-        let cmd = Provider::Opencode.build_command(&worktree, &prompt, &status, &extra_args);
+        let cmd = Provider::Opencode.build_command(&worktree, &prompt, &extra_args);
 
         assert!(cmd.contains("--verbose"));
     }
 
+    #[test]
+    fn test_claude_project_dir() {
+        assert_eq!(claude_project_dir(Path::new("/root/crate")), "-root-crate");
+        assert_eq!(
+            claude_project_dir(Path::new("/home/me/my.repo-wta-3")),
+            "-home-me-my-repo-wta-3"
+        );
+    }
+
+    #[test]
+    fn test_resume_args_only_for_claude() {
+        let home = tempfile::tempdir().unwrap();
+        let worktree = Path::new("/nonexistent/repo-wta-1");
+        let sessions = home
+            .path()
+            .join(".claude")
+            .join("projects")
+            .join(claude_project_dir(worktree));
+        std::fs::create_dir_all(&sessions).unwrap();
+
+        let original = std::env::var_os("HOME");
+        std::env::set_var("HOME", home.path());
+        let without_session = Provider::Claude.resume_args(worktree);
+        std::fs::write(sessions.join("session.jsonl"), "{}\n").unwrap();
+        let claude = Provider::Claude.resume_args(worktree);
+        let codex = Provider::Codex.resume_args(worktree);
+        match original {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+
+        assert!(without_session.is_empty());
+        assert_eq!(claude, vec!["--continue".to_string()]);
+        assert!(codex.is_empty());
+    }
+
     #[test]
     fn test_provider_equality() {
         assert_eq!(Provider::Claude, Provider::Claude);
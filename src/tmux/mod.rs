@@ -1,13 +1,20 @@
 use crate::error::{Error, Result};
+use std::collections::HashSet;
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::time::Duration;
 
 const TMUX: &str = "tmux";
 const MAIN_WINDOW: &str = "main";
 const ERR_CREATE_SESSION: &str = "Failed to create tmux session";
 const ERR_CREATE_WINDOW: &str = "Failed to create tmux window";
 const ERR_SEND_KEYS: &str = "Failed to send keys to tmux";
+const ERR_PASTE: &str = "Failed to paste into tmux";
+/// Time for the agent's TUI to take in a paste before it is submitted
+const PASTE_SETTLE: Duration = Duration::from_millis(200);
 
+#[derive(Debug, Clone)]
 pub struct TmuxManager {
     session_name: String,
 }
@@ -96,6 +103,50 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Paste text into a window and submit it. The text goes through a tmux
+    /// buffer as a bracketed paste, so its newlines are not sent as separate
+    /// Enter presses.
+    pub fn paste(&self, window: &str, text: &str) -> Result<()> {
+        let target = self.target(window);
+        // Buffers are shared by the whole tmux server
+        let buffer = format!("{}-{window}", self.session_name);
+
+        let mut child = Command::new(TMUX)
+            .args(["load-buffer", "-b", &buffer, "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::Tmux(format!(
+                "{ERR_PASTE}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let output = self.run_tmux(&["paste-buffer", "-d", "-p", "-b", &buffer, "-t", &target])?;
+        if !output.status.success() {
+            return Err(Error::Tmux(format!(
+                "{ERR_PASTE}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        std::thread::sleep(PASTE_SETTLE);
+        let output = self.run_tmux(&["send-keys", "-t", &target, "Enter"])?;
+        if !output.status.success() {
+            return Err(Error::Tmux(format!(
+                "{ERR_SEND_KEYS}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(())
+    }
+
     /// Capture pane output
     pub fn capture_pane(&self, window: &str, lines: usize) -> Result<String> {
         let target = self.target(window);
@@ -117,6 +168,24 @@ impl TmuxManager {
             .unwrap_or(false)
     }
 
+    /// Names of all windows in the session, from a single tmux call.
+    /// A missing session has no windows.
+    pub fn list_windows(&self) -> Result<HashSet<String>> {
+        let output = self.run_tmux(&[
+            "list-windows",
+            "-t",
+            &self.session_name,
+            "-F",
+            "#{window_name}",
+        ])?;
+
+        if !output.status.success() {
+            return Ok(HashSet::new());
+        }
+
+        Ok(parse_window_names(&String::from_utf8_lossy(&output.stdout)))
+    }
+
     /// Kill a window
     pub fn kill_window(&self, window: &str) -> Result<()> {
         let target = self.target(window);
@@ -146,6 +215,15 @@ impl TmuxManager {
     }
 }
 
+fn parse_window_names(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,6 +272,15 @@ mod tests {
         assert_eq!(target, "session:");
     }
 
+    #[test]
+    fn test_parse_window_names() {
+        let windows = parse_window_names("main\n1\n12\n\n");
+        assert_eq!(windows.len(), 3);
+        assert!(windows.contains("main"));
+        assert!(windows.contains("12"));
+        assert!(!windows.contains("2"));
+    }
+
     #[test]
     fn test_main_window_constant() {
         assert_eq!(MAIN_WINDOW, "main");
@@ -209,5 +296,6 @@ mod tests {
         assert!(ERR_CREATE_SESSION.contains("session"));
         assert!(ERR_CREATE_WINDOW.contains("window"));
         assert!(ERR_SEND_KEYS.contains("keys") || ERR_SEND_KEYS.contains("tmux"));
+        assert!(ERR_PASTE.contains("paste"));
     }
 }
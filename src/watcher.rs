@@ -0,0 +1,164 @@
+use crate::daemon::{LOG_FILE, PID_FILE};
+use crate::error::Result;
+use crate::orchestrator::{Orchestrator, State};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Bursts of events (git rewriting its index, an agent writing a status file
+/// in several steps) are coalesced into one wake-up
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Watches the files that signal agent progress: the status, progress and
+/// questions directories, state.json (written by other wta processes) and each
+/// worktree's git metadata under `.git/worktrees`. Worktree contents themselves
+/// are not watched, so large build directories do not exhaust inotify watches.
+pub struct StatusWatcher {
+    // Dropping the watcher stops event delivery
+    _watcher: RecommendedWatcher,
+    events: mpsc::UnboundedReceiver<()>,
+}
+
+impl StatusWatcher {
+    pub fn new(orchestrator: &Orchestrator) -> Result<Self> {
+        let (tx, events) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                if is_relevant(&event) {
+                    let _ = tx.send(());
+                }
+            }
+        })?;
+
+        let state_dir = orchestrator.state_dir();
+        watcher.watch(&state_dir, RecursiveMode::NonRecursive)?;
+        watcher.watch(&state_dir.join("status"), RecursiveMode::NonRecursive)?;
+        watcher.watch(&state_dir.join("progress"), RecursiveMode::NonRecursive)?;
+        watcher.watch(&state_dir.join("questions"), RecursiveMode::NonRecursive)?;
+
+        let git_worktrees = orchestrator.repo_root().join(".git").join("worktrees");
+        if git_worktrees.is_dir() {
+            watcher.watch(&git_worktrees, RecursiveMode::Recursive)?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Wait for relevant file activity, or until `fallback` elapses. The
+    /// fallback catches what the filesystem cannot report, such as an agent's
+    /// tmux window being closed. Returns whether a file event caused the wake-up.
+    pub async fn wait(&mut self, fallback: Duration) -> bool {
+        tokio::select! {
+            Some(()) = self.events.recv() => {
+                tokio::time::sleep(DEBOUNCE).await;
+                while self.events.try_recv().is_ok() {}
+                true
+            }
+            _ = tokio::time::sleep(fallback) => false,
+        }
+    }
+}
+
+fn is_relevant(event: &Event) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    // The daemon's own bookkeeping would otherwise wake every watcher, and a
+    // process's own state writes would wake it again after every refresh
+    !event
+        .paths
+        .iter()
+        .all(|p| is_daemon_file(p) || State::is_own_write(p))
+}
+
+fn is_daemon_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n == LOG_FILE || n == PID_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+    use std::path::PathBuf;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_status_file_write_is_relevant() {
+        let e = event(
+            EventKind::Create(CreateKind::File),
+            "/repo/.worktree-agents/status/3.json",
+        );
+        assert!(is_relevant(&e));
+
+        let e = event(
+            EventKind::Modify(ModifyKind::Any),
+            "/repo/.worktree-agents/state.json",
+        );
+        assert!(is_relevant(&e));
+    }
+
+    #[test]
+    fn test_access_and_daemon_files_are_ignored() {
+        let e = event(
+            EventKind::Access(AccessKind::Any),
+            "/repo/.worktree-agents/status/3.json",
+        );
+        assert!(!is_relevant(&e));
+
+        let e = event(
+            EventKind::Modify(ModifyKind::Any),
+            "/repo/.worktree-agents/daemon.log",
+        );
+        assert!(!is_relevant(&e));
+
+        let e = event(
+            EventKind::Create(CreateKind::File),
+            "/repo/.worktree-agents/state.lock",
+        );
+        assert!(!is_relevant(&e));
+
+        let e = event(
+            EventKind::Create(CreateKind::File),
+            "/repo/.worktree-agents/state.json.4242",
+        );
+        assert!(!is_relevant(&e));
+    }
+
+    #[tokio::test]
+    async fn test_wait_falls_back_without_events() {
+        let (_tx, events) = mpsc::unbounded_channel();
+        let watcher = notify::recommended_watcher(|_: notify::Result<Event>| {}).unwrap();
+        let mut status_watcher = StatusWatcher {
+            _watcher: watcher,
+            events,
+        };
+
+        assert!(!status_watcher.wait(Duration::from_millis(10)).await);
+    }
+
+    #[tokio::test]
+    async fn test_wait_coalesces_bursts() {
+        let (tx, events) = mpsc::unbounded_channel();
+        let watcher = notify::recommended_watcher(|_: notify::Result<Event>| {}).unwrap();
+        let mut status_watcher = StatusWatcher {
+            _watcher: watcher,
+            events,
+        };
+
+        for _ in 0..5 {
+            tx.send(()).unwrap();
+        }
+        assert!(status_watcher.wait(Duration::from_secs(5)).await);
+        // The whole burst was consumed by the first wake-up
+        assert!(!status_watcher.wait(Duration::from_millis(10)).await);
+    }
+}
@@ -1,15 +1,65 @@
-use crate::orchestrator::{Agent, MergeStrategy, Orchestrator};
+use crate::git::repository::{diff_stats, DiffStats};
+use crate::merge_queue::MergeQueue;
+use crate::orchestrator::{
+    Agent, Attempt, CheckResult, FileConflict, MergeStrategy, Orchestrator, Overlap, ProgressEvent,
+    Question, Review, SentMessage, Severity, StatusChange, StatusReport,
+};
 use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::IntoResponse;
-use axum::Json;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
 use std::process::Command;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
 
 pub type AppState = Arc<Mutex<Orchestrator>>;
 
+/// File in the state directory the dashboard's merge queue is kept in
+const MERGE_QUEUE_FILE: &str = "merge-queue.json";
+
+/// The dashboard's merge queue, landed one agent at a time by a background
+/// task and saved so a restarted server picks it up again
+#[derive(Clone)]
+pub struct MergeQueueHandle {
+    queue: Arc<std::sync::Mutex<MergeQueue>>,
+    path: std::path::PathBuf,
+    pub wake: Arc<tokio::sync::Notify>,
+}
+
+impl MergeQueueHandle {
+    pub fn load(state_dir: &std::path::Path) -> crate::Result<Self> {
+        let path = state_dir.join(MERGE_QUEUE_FILE);
+        Ok(Self {
+            queue: Arc::new(std::sync::Mutex::new(MergeQueue::load(&path)?)),
+            path,
+            wake: Arc::default(),
+        })
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.queue.lock().ok()?.position(id)
+    }
+
+    pub fn snapshot(&self) -> Option<MergeQueue> {
+        Some(self.queue.lock().ok()?.clone())
+    }
+
+    /// Change the queue and save it; None if the queue is unavailable
+    pub fn update<R>(&self, change: impl FnOnce(&mut MergeQueue) -> R) -> Option<R> {
+        let mut queue = self.queue.lock().ok()?;
+        let result = change(&mut queue);
+        if let Err(e) = queue.save(&self.path) {
+            eprintln!("Warning: could not save the merge queue: {e}");
+        }
+        Some(result)
+    }
+}
+
 #[derive(Serialize)]
 pub struct ApiError {
     pub error: String,
@@ -25,8 +75,19 @@ fn map_err(e: impl std::fmt::Display) -> (StatusCode, Json<ApiError>) {
     let error_str = e.to_string();
     let status = if error_str.contains("not found") || error_str.contains("NotFound") {
         StatusCode::NOT_FOUND
-    } else if error_str.contains("still running") {
+    } else if error_str.contains("still running")
+        || error_str.contains("is queued")
+        || error_str.contains("not running")
+        || error_str.contains("not awaiting approval")
+        || error_str.contains("no open question")
+        || error_str.contains("is not open")
+        || error_str.contains("already been merged")
+        || error_str.contains("can be landed")
+        || error_str.contains("which is checked out")
+    {
         StatusCode::CONFLICT
+    } else if error_str.contains("Message is empty") || error_str.contains("not forked") {
+        StatusCode::BAD_REQUEST
     } else {
         StatusCode::INTERNAL_SERVER_ERROR
     };
@@ -37,59 +98,167 @@ fn map_err(e: impl std::fmt::Display) -> (StatusCode, Json<ApiError>) {
 pub struct AgentResponse {
     pub id: String,
     pub task: String,
+    pub tasks: Vec<String>,
     pub branch: String,
     pub base_branch: String,
     pub status: String,
     pub provider: String,
     pub launched_at: String,
     pub completed_at: Option<String>,
+    pub depends_on: Vec<String>,
+    pub parent: Option<String>,
+    pub group: Option<String>,
+    pub review_of: Option<String>,
+    pub approve_plan: bool,
+    pub report: Option<StatusReport>,
+    pub report_error: Option<String>,
+    pub progress: Option<ProgressEvent>,
+    pub questions: Vec<Question>,
+    pub stall_output: Option<String>,
+    pub timeout_secs: Option<u64>,
+    pub messages: Vec<SentMessage>,
+    pub attempts: u32,
+    pub previous_attempts: Vec<Attempt>,
+    pub check: Option<CheckResult>,
+    pub reviews: Vec<Review>,
+    pub overlaps: Vec<AgentOverlap>,
+    /// Place in the dashboard's merge queue, 0 being landed now
+    pub merge_queue_position: Option<usize>,
+    /// Commit of the default branch, which mergeability is checked against
+    pub target_head: Option<String>,
+}
+
+/// Another active agent whose changes touch the same files
+#[derive(Serialize)]
+pub struct AgentOverlap {
+    pub agent: String,
+    pub severity: Severity,
+    pub files: Vec<String>,
+}
+
+impl AgentResponse {
+    /// Response for an agent, with the overlaps that involve it, its place
+    /// in the merge queue and the head of the default branch
+    fn with_context(
+        agent: &Agent,
+        overlaps: &[Overlap],
+        queue: &MergeQueueHandle,
+        target_head: Option<&String>,
+    ) -> Self {
+        let mut response = Self::from(agent);
+        response.merge_queue_position = queue.position(&agent.id.0);
+        response.target_head = target_head.cloned();
+        response.overlaps = overlaps
+            .iter()
+            .filter_map(|o| {
+                Some(AgentOverlap {
+                    agent: o.other(&agent.id)?.0.clone(),
+                    severity: o.severity,
+                    files: o.files.iter().map(|f| f.path.clone()).collect(),
+                })
+            })
+            .collect();
+        response
+    }
 }
 
 impl From<&Agent> for AgentResponse {
     fn from(agent: &Agent) -> Self {
         Self {
             id: agent.id.0.clone(),
-            task: agent.task.clone(),
+            task: agent.task().to_string(),
+            tasks: agent.tasks.clone(),
             branch: agent.branch.clone(),
             base_branch: agent.base_branch.clone(),
             status: agent.status.to_string(),
             provider: agent.provider.to_string(),
             launched_at: agent.launched_at.to_rfc3339(),
             completed_at: agent.completed_at.map(|t| t.to_rfc3339()),
+            depends_on: agent.depends_on.iter().map(|d| d.0.clone()).collect(),
+            parent: agent.parent.as_ref().map(|p| p.0.clone()),
+            group: agent.group.clone(),
+            review_of: agent.review_of.as_ref().map(|r| r.0.clone()),
+            approve_plan: agent.approve_plan,
+            report: agent.report.clone(),
+            report_error: agent.report_error.clone(),
+            progress: agent.progress.clone(),
+            questions: agent.questions.clone(),
+            stall_output: agent.stall_output.clone(),
+            timeout_secs: agent.timeout_secs,
+            messages: agent.messages.clone(),
+            attempts: agent.attempts,
+            previous_attempts: agent.previous_attempts.clone(),
+            check: agent.check.clone(),
+            reviews: agent.reviews.clone(),
+            overlaps: Vec::new(),
+            merge_queue_position: None,
+            target_head: None,
         }
     }
 }
 
 pub async fn list_agents(
     State(state): State<AppState>,
+    Extension(queue): Extension<MergeQueueHandle>,
 ) -> std::result::Result<Json<Vec<AgentResponse>>, (StatusCode, Json<ApiError>)> {
     let mut orchestrator = state.lock().await;
 
-    // Update status for all agents
-    let ids: Vec<String> = orchestrator.list().iter().map(|a| a.id.0.clone()).collect();
-    for id in ids {
-        let _ = orchestrator.check_status(&id);
-    }
+    // Statuses are kept current by the server's watcher; only pick up
+    // agents launched from the CLI since then
+    orchestrator.reload_state().map_err(map_err)?;
 
+    let overlaps = orchestrator.overlaps();
+    let target_head = orchestrator.default_target_head();
     let agents: Vec<AgentResponse> = orchestrator
         .list()
         .iter()
-        .map(|a| AgentResponse::from(*a))
+        .map(|a| AgentResponse::with_context(a, &overlaps, &queue, target_head.as_ref()))
         .collect();
     Ok(Json(agents))
 }
 
+/// Pairs of active agents whose changes touch the same files
+pub async fn list_overlaps(
+    State(state): State<AppState>,
+) -> std::result::Result<Json<Vec<Overlap>>, (StatusCode, Json<ApiError>)> {
+    let mut orchestrator = state.lock().await;
+    orchestrator.reload_state().map_err(map_err)?;
+    Ok(Json(orchestrator.overlaps()))
+}
+
+/// Server-sent events stream with one `status` event per agent status change
+pub async fn events(
+    Extension(events): Extension<broadcast::Sender<StatusChange>>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(events.subscribe()).filter_map(|change| {
+        // A lagging client skips missed changes; its next fetch catches up
+        let change = change.ok()?;
+        Event::default()
+            .event("status")
+            .json_data(change)
+            .ok()
+            .map(Ok)
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 pub async fn get_agent(
     State(state): State<AppState>,
+    Extension(queue): Extension<MergeQueueHandle>,
     Path(id): Path<String>,
 ) -> std::result::Result<Json<AgentResponse>, (StatusCode, Json<ApiError>)> {
     let mut orchestrator = state.lock().await;
+    orchestrator.reload_state().map_err(map_err)?;
 
-    // Update status
-    let _ = orchestrator.check_status(&id);
-
+    let overlaps = orchestrator.overlaps_of(&id).map_err(map_err)?;
+    let target_head = orchestrator.default_target_head();
     let agent = orchestrator.get_agent(&id).map_err(map_err)?;
-    Ok(Json(AgentResponse::from(agent)))
+    Ok(Json(AgentResponse::with_context(
+        agent,
+        &overlaps,
+        &queue,
+        target_head.as_ref(),
+    )))
 }
 
 #[derive(Serialize)]
@@ -99,13 +268,6 @@ pub struct DiffResponse {
     pub stats: DiffStats,
 }
 
-#[derive(Serialize)]
-pub struct DiffStats {
-    pub additions: usize,
-    pub deletions: usize,
-    pub files_changed: usize,
-}
-
 pub async fn get_diff(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -118,11 +280,7 @@ pub async fn get_diff(
         return Ok(Json(DiffResponse {
             diff: String::new(),
             files_changed: Vec::new(),
-            stats: DiffStats {
-                additions: 0,
-                deletions: 0,
-                files_changed: 0,
-            },
+            stats: DiffStats::default(),
         }));
     }
 
@@ -149,15 +307,7 @@ pub async fn get_diff(
         .map(String::from)
         .collect();
 
-    // Get diff stats
-    let stat_output = Command::new("git")
-        .args(["diff", "--shortstat", &diff_range])
-        .current_dir(&agent.worktree_path)
-        .output()
-        .map_err(map_err)?;
-
-    let stat_str = String::from_utf8_lossy(&stat_output.stdout);
-    let stats = parse_diff_stats(&stat_str);
+    let stats = diff_stats(&agent.worktree_path, &diff_range).map_err(map_err)?;
 
     Ok(Json(DiffResponse {
         diff,
@@ -166,39 +316,15 @@ pub async fn get_diff(
     }))
 }
 
-fn parse_diff_stats(stat_str: &str) -> DiffStats {
-    let mut stats = DiffStats {
-        additions: 0,
-        deletions: 0,
-        files_changed: 0,
-    };
-
-    // Parse "3 files changed, 10 insertions(+), 5 deletions(-)"
-    for part in stat_str.split(',') {
-        let part = part.trim();
-        if part.contains("file") {
-            if let Some(num) = part.split_whitespace().next() {
-                stats.files_changed = num.parse().unwrap_or(0);
-            }
-        } else if part.contains("insertion") {
-            if let Some(num) = part.split_whitespace().next() {
-                stats.additions = num.parse().unwrap_or(0);
-            }
-        } else if part.contains("deletion") {
-            if let Some(num) = part.split_whitespace().next() {
-                stats.deletions = num.parse().unwrap_or(0);
-            }
-        }
-    }
-
-    stats
-}
-
 #[derive(Deserialize)]
 pub struct MergeRequest {
     pub target: Option<String>,
+    /// Merge a fork into its parent agent's branch instead of `target`
+    pub into_parent: Option<bool>,
     pub strategy: Option<String>,
     pub force: Option<bool>,
+    /// Fast-forward the target where it is checked out [default: config]
+    pub update_checkout: Option<bool>,
 }
 
 #[derive(Serialize)]
@@ -218,13 +344,22 @@ pub async fn merge_agent(
     let strategy = match req.strategy.as_deref() {
         Some("rebase") => MergeStrategy::Rebase,
         Some("squash") => MergeStrategy::Squash,
-        _ => MergeStrategy::Merge,
+        Some(_) => MergeStrategy::Merge,
+        None => orchestrator.config().merge_strategy.value,
     };
 
     let force = req.force.unwrap_or(false);
+    let update_checkout = req
+        .update_checkout
+        .unwrap_or(orchestrator.config().update_checkout.value);
+    let target = if req.into_parent.unwrap_or(false) {
+        Some(orchestrator.parent_branch(&id).map_err(map_err)?)
+    } else {
+        req.target.clone()
+    };
 
     let result = orchestrator
-        .merge(&id, req.target.clone(), strategy, force)
+        .merge(&id, target, strategy, force, update_checkout)
         .await
         .map_err(map_err)?;
 
@@ -239,6 +374,92 @@ pub async fn merge_agent(
     }))
 }
 
+#[derive(Serialize)]
+pub struct QueueResponse {
+    pub position: usize,
+}
+
+/// Add a finished agent to the merge queue
+pub async fn queue_merge(
+    State(state): State<AppState>,
+    Extension(queue): Extension<MergeQueueHandle>,
+    Path(id): Path<String>,
+) -> std::result::Result<Json<QueueResponse>, (StatusCode, Json<ApiError>)> {
+    {
+        let mut orchestrator = state.lock().await;
+        orchestrator.check_status(&id).map_err(map_err)?;
+        orchestrator.check_landable(&id).map_err(map_err)?;
+    }
+
+    let position = queue
+        .update(|q| q.push(&id))
+        .ok_or_else(|| map_err("Merge queue is unavailable"))?;
+    queue.wake.notify_one();
+    Ok(Json(QueueResponse { position }))
+}
+
+/// Take an agent out of the merge queue, stopping its landing and any
+/// running check if it is being landed now
+pub async fn dequeue_merge(
+    Extension(queue): Extension<MergeQueueHandle>,
+    Path(id): Path<String>,
+) -> std::result::Result<StatusCode, (StatusCode, Json<ApiError>)> {
+    let removed = queue
+        .update(|q| q.remove(&id))
+        .ok_or_else(|| map_err("Merge queue is unavailable"))?;
+    if !removed {
+        return Err(map_err(crate::Error::NotInMergeQueue(id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Agents waiting in the merge queue and the results of those processed
+pub async fn get_merge_queue(
+    Extension(queue): Extension<MergeQueueHandle>,
+) -> std::result::Result<Json<MergeQueue>, (StatusCode, Json<ApiError>)> {
+    queue
+        .snapshot()
+        .map(Json)
+        .ok_or_else(|| map_err("Merge queue is unavailable"))
+}
+
+#[derive(Deserialize)]
+pub struct MergeabilityQuery {
+    pub target: Option<String>,
+    pub into_parent: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct MergeabilityResponse {
+    pub branch: String,
+    pub target: String,
+    pub clean: bool,
+    pub conflicts: Vec<FileConflict>,
+}
+
+/// Whether the agent's branch merges cleanly, without merging it
+pub async fn get_mergeability(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<MergeabilityQuery>,
+) -> std::result::Result<Json<MergeabilityResponse>, (StatusCode, Json<ApiError>)> {
+    let orchestrator = state.lock().await;
+
+    let target = if query.into_parent.unwrap_or(false) {
+        Some(orchestrator.parent_branch(&id).map_err(map_err)?)
+    } else {
+        query.target
+    };
+    let check = orchestrator.mergeability(&id, target).map_err(map_err)?;
+
+    Ok(Json(MergeabilityResponse {
+        clean: check.is_clean(),
+        branch: check.branch,
+        target: check.target,
+        conflicts: check.conflicts,
+    }))
+}
+
 #[derive(Deserialize)]
 pub struct PrRequest {
     pub title: Option<String>,
@@ -268,6 +489,107 @@ pub async fn create_pr(
     Ok(Json(PrResponse { url: result.url }))
 }
 
+#[derive(Deserialize)]
+pub struct MessageRequest {
+    pub message: String,
+}
+
+pub async fn send_message(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<MessageRequest>,
+) -> std::result::Result<Json<AgentResponse>, (StatusCode, Json<ApiError>)> {
+    let delivery = {
+        let mut orchestrator = state.lock().await;
+        orchestrator.reload_state().map_err(map_err)?;
+        orchestrator
+            .prepare_message(&id, &req.message)
+            .map_err(map_err)?
+    };
+
+    // Pasting waits for the agent to take the text in; other requests need
+    // not wait for that
+    let pasted = delivery.clone();
+    tokio::task::spawn_blocking(move || pasted.deliver())
+        .await
+        .map_err(map_err)?
+        .map_err(map_err)?;
+
+    let mut orchestrator = state.lock().await;
+    orchestrator.reload_state().map_err(map_err)?;
+    orchestrator
+        .record_message(&id, delivery.text())
+        .map_err(map_err)?;
+
+    let agent = orchestrator.get_agent(&id).map_err(map_err)?;
+    Ok(Json(AgentResponse::from(agent)))
+}
+
+#[derive(Deserialize)]
+pub struct AnswerRequest {
+    pub answer: String,
+    /// Question number; the oldest open question when unset
+    pub question: Option<u32>,
+}
+
+pub async fn answer_question(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<AnswerRequest>,
+) -> std::result::Result<Json<AgentResponse>, (StatusCode, Json<ApiError>)> {
+    let mut orchestrator = state.lock().await;
+    orchestrator.reload_state().map_err(map_err)?;
+
+    orchestrator
+        .answer(&id, req.question, &req.answer)
+        .map_err(map_err)?;
+    orchestrator.check_status(&id).map_err(map_err)?;
+
+    let agent = orchestrator.get_agent(&id).map_err(map_err)?;
+    Ok(Json(AgentResponse::from(agent)))
+}
+
+#[derive(Deserialize)]
+pub struct ApproveRequest {
+    /// Edited plan to send instead of the proposed one
+    pub plan: Option<String>,
+}
+
+pub async fn approve_plan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<ApproveRequest>,
+) -> std::result::Result<Json<AgentResponse>, (StatusCode, Json<ApiError>)> {
+    let mut orchestrator = state.lock().await;
+    orchestrator.reload_state().map_err(map_err)?;
+
+    let plan = req.plan.filter(|p| !p.trim().is_empty());
+    orchestrator.approve(&id, plan).map_err(map_err)?;
+
+    let agent = orchestrator.get_agent(&id).map_err(map_err)?;
+    Ok(Json(AgentResponse::from(agent)))
+}
+
+#[derive(Deserialize)]
+pub struct RejectRequest {
+    pub reason: Option<String>,
+}
+
+pub async fn reject_plan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<RejectRequest>,
+) -> std::result::Result<Json<AgentResponse>, (StatusCode, Json<ApiError>)> {
+    let mut orchestrator = state.lock().await;
+    orchestrator.reload_state().map_err(map_err)?;
+
+    let reason = req.reason.filter(|r| !r.trim().is_empty());
+    orchestrator.reject(&id, reason).map_err(map_err)?;
+
+    let agent = orchestrator.get_agent(&id).map_err(map_err)?;
+    Ok(Json(AgentResponse::from(agent)))
+}
+
 #[derive(Deserialize)]
 pub struct RemoveRequest {
     pub force: Option<bool>,
@@ -316,44 +638,3 @@ pub async fn get_output(
 
     Ok(Json(OutputResponse { output }))
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_diff_stats_full() {
-        let stat_str = " 3 files changed, 10 insertions(+), 5 deletions(-)";
-        let stats = parse_diff_stats(stat_str);
-        assert_eq!(stats.files_changed, 3);
-        assert_eq!(stats.additions, 10);
-        assert_eq!(stats.deletions, 5);
-    }
-
-    #[test]
-    fn test_parse_diff_stats_no_deletions() {
-        let stat_str = " 1 file changed, 5 insertions(+)";
-        let stats = parse_diff_stats(stat_str);
-        assert_eq!(stats.files_changed, 1);
-        assert_eq!(stats.additions, 5);
-        assert_eq!(stats.deletions, 0);
-    }
-
-    #[test]
-    fn test_parse_diff_stats_no_insertions() {
-        let stat_str = " 2 files changed, 3 deletions(-)";
-        let stats = parse_diff_stats(stat_str);
-        assert_eq!(stats.files_changed, 2);
-        assert_eq!(stats.additions, 0);
-        assert_eq!(stats.deletions, 3);
-    }
-
-    #[test]
-    fn test_parse_diff_stats_empty() {
-        let stat_str = "";
-        let stats = parse_diff_stats(stat_str);
-        assert_eq!(stats.files_changed, 0);
-        assert_eq!(stats.additions, 0);
-        assert_eq!(stats.deletions, 0);
-    }
-}
@@ -1,17 +1,23 @@
-use crate::orchestrator::Orchestrator;
-use crate::web::api::{self, AppState};
+use crate::merge_queue;
+use crate::orchestrator::{Orchestrator, StatusChange};
+use crate::watcher::StatusWatcher;
+use crate::web::api::{self, AppState, MergeQueueHandle};
 use crate::Result;
 use axum::body::Body;
 use axum::http::{header, Response, StatusCode, Uri};
 use axum::routing::{get, post};
-use axum::Router;
+use axum::{Extension, Router};
 use rust_embed::Embed;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{broadcast, Mutex};
 use tower_http::cors::{Any, CorsLayer};
 
-const DEFAULT_PORT: u16 = 3847;
+/// Fallback refresh for changes the filesystem cannot report, such as a
+/// closed tmux window
+const WATCH_FALLBACK: Duration = Duration::from_secs(5);
 
 #[derive(Embed)]
 #[folder = "dashboard/dist"]
@@ -62,9 +68,15 @@ fn serve_file(path: &str) -> Response<Body> {
 }
 
 pub async fn run_server(port: Option<u16>, open_browser: bool) -> Result<()> {
-    let port = port.unwrap_or(DEFAULT_PORT);
     let orchestrator = Orchestrator::new()?;
+    let port = port.unwrap_or(orchestrator.config().dashboard_port.value);
+    let orchestrator_state_dir = orchestrator.state_dir();
     let state: AppState = Arc::new(Mutex::new(orchestrator));
+    let (events, _) = broadcast::channel(64);
+
+    tokio::spawn(watch_agents(state.clone(), events.clone()));
+    let queue = MergeQueueHandle::load(&orchestrator_state_dir)?;
+    tokio::spawn(run_merge_queue(state.clone(), queue.clone()));
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -72,17 +84,31 @@ pub async fn run_server(port: Option<u16>, open_browser: bool) -> Result<()> {
         .allow_headers(Any);
 
     let api_routes = Router::new()
+        .route("/events", get(api::events))
         .route("/agents", get(api::list_agents))
+        .route("/overlaps", get(api::list_overlaps))
+        .route("/merge-queue", get(api::get_merge_queue))
         .route("/agents/:id", get(api::get_agent).delete(api::remove_agent))
         .route("/agents/:id/diff", get(api::get_diff))
         .route("/agents/:id/merge", post(api::merge_agent))
+        .route("/agents/:id/mergeability", get(api::get_mergeability))
+        .route(
+            "/agents/:id/queue",
+            post(api::queue_merge).delete(api::dequeue_merge),
+        )
         .route("/agents/:id/pr", post(api::create_pr))
+        .route("/agents/:id/message", post(api::send_message))
+        .route("/agents/:id/answer", post(api::answer_question))
+        .route("/agents/:id/approve", post(api::approve_plan))
+        .route("/agents/:id/reject", post(api::reject_plan))
         .route("/agents/:id/output", get(api::get_output));
 
     let app = Router::new()
         .nest("/api", api_routes)
         .fallback(fallback_handler)
         .layer(cors)
+        .layer(Extension(events))
+        .layer(Extension(queue.clone()))
         .with_state(state);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
@@ -95,7 +121,68 @@ pub async fn run_server(port: Option<u16>, open_browser: bool) -> Result<()> {
     }
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    let mut sigterm = signal(SignalKind::terminate())?;
+    tokio::select! {
+        result = axum::serve(listener, app) => result?,
+        // A check runs in its own process group, so it is stopped here; the
+        // agent being landed is landed again when the dashboard restarts
+        _ = tokio::signal::ctrl_c() => {
+            queue.update(|q| q.interrupt());
+        }
+        _ = sigterm.recv() => {
+            queue.update(|q| q.interrupt());
+        }
+    }
 
     Ok(())
 }
+
+/// Land agents added to the merge queue from the dashboard, one at a time
+async fn run_merge_queue(state: AppState, queue: MergeQueueHandle) {
+    loop {
+        let next = queue.update(|q| q.start_next()).flatten();
+        let Some((id, cancel)) = next else {
+            queue.wake.notified().await;
+            continue;
+        };
+
+        let strategy = state.lock().await.config().merge_strategy.value;
+        let landing = merge_queue::land(&state, &id, None, strategy, None, cancel).await;
+        queue.update(|q| q.finish(landing));
+    }
+}
+
+/// Keep agent statuses in sync and publish each transition to `/api/events`
+async fn watch_agents(state: AppState, events: broadcast::Sender<StatusChange>) {
+    let watcher = {
+        let orchestrator = state.lock().await;
+        StatusWatcher::new(&orchestrator)
+    };
+    let mut watcher = match watcher {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            eprintln!("Warning: {e}; falling back to polling");
+            None
+        }
+    };
+
+    loop {
+        match watcher.as_mut() {
+            Some(watcher) => {
+                watcher.wait(WATCH_FALLBACK).await;
+            }
+            None => tokio::time::sleep(WATCH_FALLBACK).await,
+        }
+
+        let changes = state.lock().await.sync_status();
+        match changes {
+            Ok(changes) => {
+                for change in changes {
+                    // No subscribers is not an error
+                    let _ = events.send(change);
+                }
+            }
+            Err(e) => eprintln!("Warning: status refresh failed: {e}"),
+        }
+    }
+}
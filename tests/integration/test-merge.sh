@@ -139,10 +139,19 @@ fi
 # Test 7: Test merge without --target (should merge to main)
 echo -e "\n${BLUE}Test 7: Merge to default branch (main)${NC}"
 cd "$TEST_DIR"
-info "Running: wta merge 1"
+
+# main is checked out here, so it is only moved when asked to
+MAIN_BEFORE=$(git rev-parse main)
+if $WTA merge 1 2>&1 | grep -q "was not updated" && [ "$(git rev-parse main)" = "$MAIN_BEFORE" ]; then
+    pass "Merge into the checked-out main is refused without --update-checkout"
+else
+    fail "Merge into the checked-out main should be refused without --update-checkout"
+fi
+
+info "Running: wta merge 1 --update-checkout"
 
 # Capture output
-MERGE_OUTPUT=$($WTA merge 1 2>&1)
+MERGE_OUTPUT=$($WTA merge 1 --update-checkout 2>&1)
 echo "$MERGE_OUTPUT"
 
 # Check if merge was successful
@@ -286,7 +295,7 @@ fi
 # Try to merge from inside worktree (should still work)
 cd "$TEST_DIR-wta-3"
 info "Running wta merge 3 from inside worktree"
-MERGE_OUTPUT=$($WTA merge 3 2>&1)
+MERGE_OUTPUT=$($WTA merge 3 --update-checkout 2>&1)
 
 if echo "$MERGE_OUTPUT" | grep -q "Successfully merged"; then
     pass "wta merge works from inside worktree"